@@ -0,0 +1,185 @@
+//! Pairing every key with its own value out of a list of candidates, for
+//! puzzles where each key has exactly one answer.
+
+use std::{collections::HashMap, fmt::Debug, hash::Hash};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum AssignmentError<K: Debug> {
+    /// There is no way to give every key a value of its own.
+    #[error("no value is left for {key:?}")]
+    Infeasible { key: K },
+    /// The candidates allow more than one answer.
+    #[error("{key:?} could take more than one value")]
+    Ambiguous { key: K },
+}
+
+/// Gives every key one of its candidate values, with no value used twice.
+///
+/// Keys left with a single candidate are settled first and that value is
+/// struck from the rest. If that stalls, the remaining keys are matched with
+/// augmenting paths and the matching is checked to be the only one.
+pub fn assign<K, V, C>(
+    candidates: impl IntoIterator<Item = (K, C)>,
+) -> Result<HashMap<K, V>, AssignmentError<K>>
+where
+    K: Clone + Eq + Hash + Debug,
+    V: Clone + Eq + Hash,
+    C: IntoIterator<Item = V>,
+{
+    let mut keys = Vec::new();
+    let mut values = Vec::new();
+    let mut value_ids = HashMap::new();
+    let mut options = Vec::new();
+    for (key, candidates) in candidates {
+        let mut ids: Vec<usize> = candidates
+            .into_iter()
+            .map(|value| {
+                *value_ids.entry(value.clone()).or_insert_with(|| {
+                    values.push(value);
+                    values.len() - 1
+                })
+            })
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        keys.push(key);
+        options.push(ids);
+    }
+
+    let mut owner = vec![None; values.len()];
+    let mut settled = vec![false; keys.len()];
+    let mut progress = true;
+    while progress {
+        progress = false;
+        for k in 0..keys.len() {
+            if settled[k] {
+                continue;
+            }
+            options[k].retain(|&v| owner[v].is_none());
+            match options[k][..] {
+                [] => {
+                    return Err(AssignmentError::Infeasible {
+                        key: keys[k].clone(),
+                    })
+                }
+                [v] => {
+                    owner[v] = Some(k);
+                    settled[k] = true;
+                    progress = true;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let rest: Vec<usize> = (0..keys.len()).filter(|&k| !settled[k]).collect();
+    for &k in &rest {
+        let mut seen = vec![false; values.len()];
+        if !augment(k, &options, &mut owner, &mut seen, None) {
+            return Err(AssignmentError::Infeasible {
+                key: keys[k].clone(),
+            });
+        }
+    }
+    // The matching is the only one if no key can be moved off its value
+    // while every other key still gets one.
+    for &k in &rest {
+        let v = owner.iter().position(|&o| o == Some(k)).unwrap();
+        owner[v] = None;
+        let mut seen = vec![false; values.len()];
+        if augment(k, &options, &mut owner, &mut seen, Some(v)) {
+            return Err(AssignmentError::Ambiguous {
+                key: keys[k].clone(),
+            });
+        }
+        owner[v] = Some(k);
+    }
+
+    let mut assignment = HashMap::with_capacity(keys.len());
+    for (v, k) in owner.into_iter().enumerate() {
+        if let Some(k) = k {
+            assignment.insert(keys[k].clone(), values[v].clone());
+        }
+    }
+    Ok(assignment)
+}
+
+/// Tries to find `key` a value, moving other keys along to make room. `key`
+/// itself may not take `banned`. Only changes `owner` when it succeeds.
+fn augment(
+    key: usize,
+    options: &[Vec<usize>],
+    owner: &mut [Option<usize>],
+    seen: &mut [bool],
+    banned: Option<usize>,
+) -> bool {
+    for &v in &options[key] {
+        if seen[v] || banned == Some(v) {
+            continue;
+        }
+        seen[v] = true;
+        let free = match owner[v] {
+            None => true,
+            Some(other) => augment(other, options, owner, seen, None),
+        };
+        if free {
+            owner[v] = Some(key);
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn eliminating() {
+        let assigned = assign([
+            ("dairy", vec!["mxmxvkd", "sqjhc"]),
+            ("fish", vec!["mxmxvkd", "sqjhc"]),
+            ("soy", vec!["sqjhc", "fvjkl"]),
+            ("nuts", vec!["sqjhc"]),
+        ]);
+        assert_eq!(
+            assigned,
+            Err(AssignmentError::Infeasible { key: "fish" }),
+            "nuts takes sqjhc, dairy then mxmxvkd, leaving fish nothing"
+        );
+        let assigned = assign([
+            ("dairy", vec!["mxmxvkd"]),
+            ("fish", vec!["mxmxvkd", "sqjhc"]),
+            ("soy", vec!["sqjhc", "fvjkl"]),
+        ])
+        .unwrap();
+        assert_eq!(assigned["dairy"], "mxmxvkd");
+        assert_eq!(assigned["fish"], "sqjhc");
+        assert_eq!(assigned["soy"], "fvjkl");
+    }
+
+    #[test]
+    fn matching_when_elimination_stalls() {
+        // Keys 0 and 1 can swap a and b.
+        let assigned = assign([
+            (0, vec!['a', 'b']),
+            (1, vec!['a', 'b']),
+            (2, vec!['b', 'c']),
+        ]);
+        assert_eq!(assigned, Err(AssignmentError::Ambiguous { key: 0 }));
+        let assigned = assign([
+            (0, vec!['a', 'b']),
+            (1, vec!['b', 'c']),
+            (2, vec!['a', 'c']),
+            (3, vec!['a', 'b', 'c']),
+        ]);
+        assert!(matches!(assigned, Err(AssignmentError::Infeasible { .. })));
+        let assigned = assign([
+            (0, vec!['a', 'b']),
+            (1, vec!['a', 'c']),
+            (2, vec!['a', 'b', 'c', 'd']),
+        ]);
+        assert!(matches!(assigned, Err(AssignmentError::Ambiguous { .. })));
+    }
+}
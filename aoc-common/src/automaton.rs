@@ -0,0 +1,488 @@
+//! Cellular automata, either sparse over any hashable coordinate or dense
+//! over a [`Grid`].
+
+use crate::grid::{Grid, Pos};
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    hash::{Hash, Hasher},
+};
+use thiserror::Error;
+
+/// A cell state that is either alive or not when counting neighbours.
+pub trait State: Clone + Eq + Hash {
+    fn is_alive(&self) -> bool;
+}
+
+impl State for bool {
+    fn is_alive(&self) -> bool {
+        *self
+    }
+}
+
+/// Works out a cell's next state from its current one and how many of its
+/// neighbours are alive.
+pub trait Rule<S> {
+    fn next(&self, cell: &S, live_neighbours: usize) -> S;
+}
+
+impl<S, F: Fn(&S, usize) -> S> Rule<S> for F {
+    fn next(&self, cell: &S, live_neighbours: usize) -> S {
+        self(cell, live_neighbours)
+    }
+}
+
+/// A neighbour count too big for a [`BirthSurvival`] rule to hold.
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+#[error(
+    "cannot count {count} neighbours, at most {} can be",
+    BirthSurvival::MAX_COUNT
+)]
+pub struct NeighbourCountError {
+    pub count: usize,
+}
+
+/// A life-like rule: a dead cell comes alive with a neighbour count listed
+/// in `birth` and a live cell stays alive with one listed in `survival`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BirthSurvival {
+    birth: u128,
+    survival: u128,
+}
+
+impl BirthSurvival {
+    /// The most neighbours a count can list.
+    pub const MAX_COUNT: usize = u128::BITS as usize - 1;
+
+    pub fn new(birth: &[usize], survival: &[usize]) -> Result<Self, NeighbourCountError> {
+        let mask = |counts: &[usize]| {
+            counts.iter().try_fold(0u128, |mask, &count| match count {
+                0..=Self::MAX_COUNT => Ok(mask | 1 << count),
+                _ => Err(NeighbourCountError { count }),
+            })
+        };
+        Ok(Self {
+            birth: mask(birth)?,
+            survival: mask(survival)?,
+        })
+    }
+
+    /// Conway's Game of Life, B3/S23.
+    pub fn life() -> Self {
+        Self::new(&[3], &[2, 3]).expect("counts fit")
+    }
+}
+
+impl Rule<bool> for BirthSurvival {
+    fn next(&self, &alive: &bool, live_neighbours: usize) -> bool {
+        let mask = if alive { self.survival } else { self.birth };
+        live_neighbours <= Self::MAX_COUNT && mask & 1 << live_neighbours != 0
+    }
+}
+
+/// The cells next to a cell.
+pub trait Neighbourhood<P> {
+    fn neighbours(&self, cell: &P) -> impl Iterator<Item = P>;
+}
+
+impl<P, F, I> Neighbourhood<P> for F
+where
+    F: Fn(&P) -> I,
+    I: IntoIterator<Item = P>,
+{
+    fn neighbours(&self, cell: &P) -> impl Iterator<Item = P> {
+        self(cell).into_iter()
+    }
+}
+
+/// Every cell touching one in `N` dimensions, diagonals included.
+#[derive(Debug, Clone)]
+pub struct Moore<const N: usize> {
+    offsets: Vec<[i32; N]>,
+}
+
+impl<const N: usize> Moore<N> {
+    pub fn new() -> Self {
+        let count = 3usize.pow(N as u32);
+        let offsets = (0..count)
+            .filter(|&i| i != count / 2)
+            .map(|i| {
+                let mut offset = [0; N];
+                let mut rest = i;
+                for d in offset.iter_mut() {
+                    *d = (rest % 3) as i32 - 1;
+                    rest /= 3;
+                }
+                offset
+            })
+            .collect();
+        Self { offsets }
+    }
+}
+
+impl<const N: usize> Default for Moore<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Neighbourhood<[i32; N]> for Moore<N> {
+    fn neighbours(&self, cell: &[i32; N]) -> impl Iterator<Item = [i32; N]> {
+        self.offsets.iter().map(move |offset| {
+            let mut next = *cell;
+            for (c, d) in next.iter_mut().zip(offset) {
+                *c += d;
+            }
+            next
+        })
+    }
+}
+
+/// The cells of an automaton at some generation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot<C> {
+    pub generation: usize,
+    pub cells: C,
+}
+
+/// The automaton went back to the state it had at generation `start` and
+/// repeats every `period` generations from there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub period: usize,
+}
+
+pub trait Automaton {
+    type Cells: Clone + PartialEq;
+
+    /// Moves on a generation, returning whether any cell changed.
+    fn step(&mut self) -> bool;
+
+    fn generation(&self) -> usize;
+
+    /// How many cells are alive.
+    fn population(&self) -> usize;
+
+    fn snapshot(&self) -> Snapshot<Self::Cells>;
+
+    fn restore(&mut self, snapshot: Snapshot<Self::Cells>);
+
+    /// A hash of the cells, equal whenever the cells are.
+    fn fingerprint(&self) -> u64;
+
+    fn run(&mut self, generations: usize) {
+        for _ in 0..generations {
+            self.step();
+        }
+    }
+
+    /// Steps until nothing changes, giving up after `limit` generations.
+    /// Returns the first generation that stays the same.
+    fn settle(&mut self, limit: usize) -> Option<usize> {
+        for _ in 0..limit {
+            if !self.step() {
+                return Some(self.generation() - 1);
+            }
+        }
+        None
+    }
+
+    /// Steps until a state comes round again, giving up after `limit`
+    /// generations. A steady state is a cycle with a period of one.
+    fn find_cycle(&mut self, limit: usize) -> Option<Cycle> {
+        let first = self.generation();
+        let mut seen: HashMap<u64, Vec<Snapshot<Self::Cells>>> = HashMap::new();
+        loop {
+            let snapshot = self.snapshot();
+            let earlier = seen.entry(self.fingerprint()).or_default();
+            if let Some(earlier) = earlier.iter().find(|s| s.cells == snapshot.cells) {
+                return Some(Cycle {
+                    start: earlier.generation,
+                    period: snapshot.generation - earlier.generation,
+                });
+            }
+            earlier.push(snapshot);
+            if self.generation() - first >= limit {
+                return None;
+            }
+            self.step();
+        }
+    }
+}
+
+/// An unbounded automaton that only keeps track of the live cells.
+#[derive(Debug, Clone)]
+pub struct Sparse<P, N, R> {
+    alive: HashSet<P>,
+    neighbourhood: N,
+    rule: R,
+    generation: usize,
+}
+
+impl<P, N, R> Sparse<P, N, R>
+where
+    P: Clone + Eq + Hash,
+    N: Neighbourhood<P>,
+    R: Rule<bool>,
+{
+    pub fn new(alive: impl IntoIterator<Item = P>, neighbourhood: N, rule: R) -> Self {
+        Self {
+            alive: alive.into_iter().collect(),
+            neighbourhood,
+            rule,
+            generation: 0,
+        }
+    }
+
+    pub fn alive(&self) -> &HashSet<P> {
+        &self.alive
+    }
+}
+
+impl<P, N, R> Automaton for Sparse<P, N, R>
+where
+    P: Clone + Eq + Hash,
+    N: Neighbourhood<P>,
+    R: Rule<bool>,
+{
+    type Cells = HashSet<P>;
+
+    fn step(&mut self) -> bool {
+        let mut counts: HashMap<P, usize> = HashMap::with_capacity(self.alive.len() * 4);
+        for cell in &self.alive {
+            // Live cells with no live neighbours still need a say.
+            counts.entry(cell.clone()).or_insert(0);
+            for neighbour in self.neighbourhood.neighbours(cell) {
+                *counts.entry(neighbour).or_insert(0) += 1;
+            }
+        }
+        let next: HashSet<P> = counts
+            .into_iter()
+            .filter(|(cell, n)| self.rule.next(&self.alive.contains(cell), *n))
+            .map(|(cell, _)| cell)
+            .collect();
+        let changed = next != self.alive;
+        self.alive = next;
+        self.generation += 1;
+        changed
+    }
+
+    fn generation(&self) -> usize {
+        self.generation
+    }
+
+    fn population(&self) -> usize {
+        self.alive.len()
+    }
+
+    fn snapshot(&self) -> Snapshot<Self::Cells> {
+        Snapshot {
+            generation: self.generation,
+            cells: self.alive.clone(),
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot<Self::Cells>) {
+        self.generation = snapshot.generation;
+        self.alive = snapshot.cells;
+    }
+
+    /// Adds up each cell's hash so the set's order does not matter.
+    fn fingerprint(&self) -> u64 {
+        self.alive.iter().fold(0u64, |sum, cell| {
+            let mut hasher = DefaultHasher::new();
+            cell.hash(&mut hasher);
+            sum.wrapping_add(hasher.finish())
+        })
+    }
+}
+
+/// An automaton over every cell of a grid. Each cell's neighbours are
+/// worked out once up front.
+#[derive(Debug, Clone)]
+pub struct Dense<T, R> {
+    grid: Grid<T>,
+    next: Grid<T>,
+    neighbours: Vec<Vec<usize>>,
+    rule: R,
+    generation: usize,
+}
+
+impl<T, R> Dense<T, R>
+where
+    T: State,
+    R: Rule<T>,
+{
+    pub fn new<I>(grid: Grid<T>, neighbourhood: impl Fn(&Grid<T>, Pos) -> I, rule: R) -> Self
+    where
+        I: IntoIterator<Item = Pos>,
+    {
+        let neighbours = grid
+            .positions()
+            .map(|pos| {
+                neighbourhood(&grid, pos)
+                    .into_iter()
+                    .filter_map(|n| grid.index_of(n))
+                    .collect()
+            })
+            .collect();
+        Self {
+            next: grid.clone(),
+            grid,
+            neighbours,
+            rule,
+            generation: 0,
+        }
+    }
+
+    /// Uses the eight cells touching each cell as its neighbours.
+    pub fn moore(grid: Grid<T>, rule: R) -> Self {
+        Self::new(
+            grid,
+            |grid, pos| grid.neighbours8(pos).collect::<Vec<_>>(),
+            rule,
+        )
+    }
+
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+}
+
+impl<T, R> Automaton for Dense<T, R>
+where
+    T: State,
+    R: Rule<T>,
+{
+    type Cells = Grid<T>;
+
+    fn step(&mut self) -> bool {
+        let cells = self.grid.cells();
+        let mut changed = false;
+        for (i, next) in self.next.cells_mut().iter_mut().enumerate() {
+            let live = self.neighbours[i]
+                .iter()
+                .filter(|&&n| cells[n].is_alive())
+                .count();
+            *next = self.rule.next(&cells[i], live);
+            changed |= next != &cells[i];
+        }
+        std::mem::swap(&mut self.grid, &mut self.next);
+        self.generation += 1;
+        changed
+    }
+
+    fn generation(&self) -> usize {
+        self.generation
+    }
+
+    fn population(&self) -> usize {
+        self.grid.cells().iter().filter(|c| c.is_alive()).count()
+    }
+
+    fn snapshot(&self) -> Snapshot<Self::Cells> {
+        Snapshot {
+            generation: self.generation,
+            cells: self.grid.clone(),
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot<Self::Cells>) {
+        self.generation = snapshot.generation;
+        self.grid = snapshot.cells;
+    }
+
+    fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.grid.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn blinker() -> Sparse<[i32; 2], Moore<2>, BirthSurvival> {
+        Sparse::new(
+            [[0, -1], [0, 0], [0, 1]],
+            Moore::new(),
+            BirthSurvival::life(),
+        )
+    }
+
+    #[test]
+    fn moore_neighbourhoods() {
+        assert_eq!(Moore::<2>::new().neighbours(&[0, 0]).count(), 8);
+        assert_eq!(Moore::<4>::new().neighbours(&[5, 5, 5, 5]).count(), 80);
+        assert!(!Moore::<3>::new()
+            .neighbours(&[1, 1, 1])
+            .any(|n| n == [1, 1, 1]));
+    }
+
+    #[test]
+    fn birth_survival_counts() {
+        let rule = BirthSurvival::new(&[0], &[BirthSurvival::MAX_COUNT]).unwrap();
+        assert!(rule.next(&false, 0));
+        assert!(rule.next(&true, 127));
+        assert!(!rule.next(&true, 128));
+        assert_eq!(
+            BirthSurvival::new(&[3], &[2, 128]),
+            Err(NeighbourCountError { count: 128 })
+        );
+    }
+
+    #[test]
+    fn sparse_cycles() {
+        let mut life = blinker();
+        assert!(life.step());
+        let mut alive: Vec<_> = life.alive().iter().copied().collect();
+        alive.sort();
+        assert_eq!(alive, vec![[-1, 0], [0, 0], [1, 0]]);
+        assert_eq!(
+            life.find_cycle(10),
+            Some(Cycle {
+                start: 1,
+                period: 2
+            })
+        );
+
+        let mut block = Sparse::new(
+            [[0, 0], [0, 1], [1, 0], [1, 1]],
+            Moore::new(),
+            BirthSurvival::life(),
+        );
+        assert_eq!(block.settle(10), Some(0));
+        assert_eq!(block.find_cycle(10).map(|c| c.period), Some(1));
+    }
+
+    #[test]
+    fn snapshots() {
+        let mut life = blinker();
+        let start = life.snapshot();
+        life.run(3);
+        assert_eq!(life.generation(), 3);
+        assert_ne!(life.snapshot().cells, start.cells);
+        life.restore(start.clone());
+        assert_eq!(life.snapshot(), start);
+    }
+
+    #[test]
+    fn dense() {
+        let grid = Grid::parse_with(".....\n..#..\n..#..\n..#..\n.....", |c| {
+            Ok::<_, &str>(c == '#')
+        })
+        .unwrap();
+        let mut life = Dense::moore(grid.clone(), BirthSurvival::life());
+        life.step();
+        assert_eq!(life.grid().row(2), &[false, true, true, true, false]);
+        assert_eq!(life.population(), 3);
+        assert_eq!(
+            life.find_cycle(10),
+            Some(Cycle {
+                start: 1,
+                period: 2
+            })
+        );
+    }
+}
@@ -0,0 +1,192 @@
+//! The eight ways to turn a square over and around, for rotating and
+//! reflecting grids, the cells in them and the steps between them.
+
+use crate::grid::{Grid, Pos};
+
+/// An element of the dihedral group of the square: mirrored left to right
+/// or not, then turned clockwise by a number of quarter turns.
+///
+/// Turns are clockwise as drawn, with `y` growing downwards like the rows of
+/// a [`Grid`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct D4 {
+    flipped: bool,
+    turns: u8,
+}
+
+impl D4 {
+    pub const IDENTITY: D4 = D4::new(false, 0);
+    /// Mirrors left to right.
+    pub const FLIP: D4 = D4::new(true, 0);
+    pub const ALL: [D4; 8] = [
+        D4::new(false, 0),
+        D4::new(false, 1),
+        D4::new(false, 2),
+        D4::new(false, 3),
+        D4::new(true, 0),
+        D4::new(true, 1),
+        D4::new(true, 2),
+        D4::new(true, 3),
+    ];
+
+    pub const fn new(flipped: bool, turns: u8) -> Self {
+        Self {
+            flipped,
+            turns: turns % 4,
+        }
+    }
+
+    /// Quarter turns clockwise, or anticlockwise when negative.
+    pub const fn rotation(quarter_turns: i32) -> Self {
+        Self::new(false, quarter_turns.rem_euclid(4) as u8)
+    }
+
+    pub fn iter() -> impl Iterator<Item = D4> {
+        Self::ALL.into_iter()
+    }
+
+    pub fn flipped(self) -> bool {
+        self.flipped
+    }
+
+    /// Clockwise quarter turns made after any flip.
+    pub fn turns(self) -> u8 {
+        self.turns
+    }
+
+    /// Doing `self` and then `next`.
+    pub fn then(self, next: D4) -> D4 {
+        // Turning after a flip looks like turning the other way before it.
+        let turns = if next.flipped {
+            next.turns + 4 - self.turns
+        } else {
+            next.turns + self.turns
+        };
+        D4::new(self.flipped != next.flipped, turns)
+    }
+
+    /// The transform that undoes this one.
+    pub fn inverse(self) -> D4 {
+        if self.flipped {
+            self
+        } else {
+            D4::new(false, 4 - self.turns)
+        }
+    }
+
+    pub fn apply<T: Transform>(self, value: &T) -> T {
+        value.transform(self)
+    }
+
+    /// The width and height of a `width` by `height` grid afterwards.
+    pub fn size(self, width: usize, height: usize) -> (usize, usize) {
+        if self.turns % 2 == 1 {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// Where the cell at `(x, y)` of a `width` by `height` grid ends up.
+    pub fn apply_pos(self, (mut x, mut y): Pos, mut width: usize, mut height: usize) -> Pos {
+        if self.flipped {
+            x = width - 1 - x;
+        }
+        for _ in 0..self.turns {
+            (x, y) = (height - 1 - y, x);
+            (width, height) = (height, width);
+        }
+        (x, y)
+    }
+}
+
+/// Something a [`D4`] element can act on.
+pub trait Transform {
+    fn transform(&self, by: D4) -> Self;
+}
+
+/// Moves every cell, swapping the width and height for odd turns.
+impl<T: Clone> Transform for Grid<T> {
+    fn transform(&self, by: D4) -> Self {
+        let (width, height) = by.size(self.width(), self.height());
+        let mut cells = self.cells().to_vec();
+        for (pos, cell) in self.iter() {
+            let (x, y) = by.apply_pos(pos, self.width(), self.height());
+            cells[y * width + x] = cell.clone();
+        }
+        Grid::from_vec(width, height, cells).expect("transforming keeps every cell")
+    }
+}
+
+macro_rules! impl_transform_vector {
+    ($($t:ty),*) => {
+        $(
+            /// Turns a step or a point around the origin.
+            impl Transform for ($t, $t) {
+                fn transform(&self, by: D4) -> Self {
+                    let (mut x, mut y) = *self;
+                    if by.flipped {
+                        x = -x;
+                    }
+                    for _ in 0..by.turns {
+                        (x, y) = (-y, x);
+                    }
+                    (x, y)
+                }
+            }
+        )*
+    };
+}
+
+impl_transform_vector!(i32, i64, isize);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::grid::ORTHOGONAL;
+
+    fn grid(map: &str) -> Grid<char> {
+        map.parse().unwrap()
+    }
+
+    #[test]
+    fn grids() {
+        let tile = grid("ab\ncd\nef");
+        assert_eq!(D4::rotation(1).apply(&tile), grid("eca\nfdb"));
+        assert_eq!(D4::FLIP.apply(&tile), grid("ba\ndc\nfe"));
+        assert_eq!(D4::new(true, 3).apply(&tile), grid("ace\nbdf"));
+        let all: Vec<_> = D4::iter().map(|d4| d4.apply(&tile)).collect();
+        assert!(all.iter().enumerate().all(|(i, g)| !all[..i].contains(g)));
+        assert_eq!(D4::rotation(-1).apply_pos((0, 0), 2, 3), (0, 1));
+    }
+
+    #[test]
+    fn vectors() {
+        // Clockwise from up, so a quarter turn moves to the next one.
+        for (i, &step) in ORTHOGONAL.iter().enumerate() {
+            assert_eq!(D4::rotation(1).apply(&step), ORTHOGONAL[(i + 1) % 4]);
+            assert_eq!(D4::rotation(-1).apply(&step), ORTHOGONAL[(i + 3) % 4]);
+        }
+        assert_eq!(D4::FLIP.apply(&(3i64, 2)), (-3, 2));
+        assert_eq!(D4::rotation(2).apply(&(3i32, 2)), (-3, -2));
+    }
+
+    #[test]
+    fn group() {
+        let tile = grid("abc\ndef");
+        let point = (2isize, -5);
+        for a in D4::iter() {
+            assert_eq!(a.then(a.inverse()), D4::IDENTITY);
+            assert_eq!(a.inverse().then(a), D4::IDENTITY);
+            for b in D4::iter() {
+                let ab = a.then(b);
+                assert!(D4::ALL.contains(&ab));
+                assert_eq!(ab.apply(&tile), b.apply(&a.apply(&tile)));
+                assert_eq!(ab.apply(&point), b.apply(&a.apply(&point)));
+            }
+        }
+        assert_eq!(D4::rotation(4), D4::IDENTITY);
+        assert_eq!(D4::rotation(-1), D4::rotation(3));
+        assert_eq!(D4::FLIP.then(D4::rotation(1)), D4::new(true, 1));
+    }
+}
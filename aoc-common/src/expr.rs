@@ -0,0 +1,337 @@
+//! Arithmetic expressions read with a Pratt parser, where which operators
+//! exist and how tightly they bind comes from a table given at runtime.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    iter::Peekable,
+    str::CharIndices,
+};
+use thiserror::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+impl BinOp {
+    fn from_char(c: char) -> Option<Self> {
+        Some(match c {
+            '+' => BinOp::Add,
+            '-' => BinOp::Sub,
+            '*' => BinOp::Mul,
+            '/' => BinOp::Div,
+            '^' => BinOp::Pow,
+            _ => return None,
+        })
+    }
+
+    fn symbol(self) -> char {
+        match self {
+            BinOp::Add => '+',
+            BinOp::Sub => '-',
+            BinOp::Mul => '*',
+            BinOp::Div => '/',
+            BinOp::Pow => '^',
+        }
+    }
+}
+
+/// Which side operators of the same precedence group from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Assoc {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Number(i64),
+    Variable(String),
+    Neg(Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ParseError {
+    #[error("unexpected end of expression")]
+    UnexpectedEnd,
+    #[error("unexpected `{found}` at {at}")]
+    Unexpected { at: usize, found: char },
+    /// An operator that the table does not have.
+    #[error("`{op}` at {at} is not an operator here")]
+    UnknownOperator { at: usize, op: char },
+    #[error("{number} at {at} is too big")]
+    Number { at: usize, number: String },
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum EvalError {
+    #[error("overflow")]
+    Overflow,
+    #[error("division by zero")]
+    DivisionByZero,
+    #[error("negative exponent")]
+    NegativeExponent,
+    #[error("`{name}` has no value")]
+    Unbound { name: String },
+}
+
+/// The operators a parser knows, with a precedence for each. Higher
+/// precedences bind tighter, and must be from 1 to 127.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Operators {
+    binary: HashMap<BinOp, (u8, Assoc)>,
+    negation: Option<u8>,
+}
+
+impl Operators {
+    /// A table with no operators at all.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The usual rules: `^` binds tightest and groups from the right, then
+    /// unary minus, then `*` and `/`, then `+` and `-`.
+    pub fn arithmetic() -> Self {
+        Self::new()
+            .binary(BinOp::Add, 1, Assoc::Left)
+            .binary(BinOp::Sub, 1, Assoc::Left)
+            .binary(BinOp::Mul, 2, Assoc::Left)
+            .binary(BinOp::Div, 2, Assoc::Left)
+            .negation(3)
+            .binary(BinOp::Pow, 4, Assoc::Right)
+    }
+
+    pub fn binary(mut self, op: BinOp, precedence: u8, assoc: Assoc) -> Self {
+        assert!((1..128).contains(&precedence), "precedences are 1 to 127");
+        self.binary.insert(op, (precedence, assoc));
+        self
+    }
+
+    /// Allows a leading `-` to negate whatever binds tighter than `precedence`.
+    pub fn negation(mut self, precedence: u8) -> Self {
+        assert!((1..128).contains(&precedence), "precedences are 1 to 127");
+        self.negation = Some(precedence);
+        self
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Expr, ParseError> {
+        let mut parser = Parser {
+            operators: self,
+            input,
+            chars: input.char_indices().peekable(),
+        };
+        let expr = parser.expr(0)?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some((at, found)) => Err(ParseError::Unexpected { at, found }),
+        }
+    }
+}
+
+struct Parser<'a> {
+    operators: &'a Operators,
+    input: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&mut self) -> Option<(usize, char)> {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+        self.chars.peek().copied()
+    }
+
+    /// Everything from here on while `pred` holds.
+    fn take_while(&mut self, start: usize, pred: impl Fn(char) -> bool) -> &'a str {
+        let mut end = start;
+        while let Some((at, c)) = self.chars.next_if(|&(_, c)| pred(c)) {
+            end = at + c.len_utf8();
+        }
+        &self.input[start..end]
+    }
+
+    /// Parses operators that bind at least as tightly as `min`. Each
+    /// precedence `p` binds at `2p` on its left and `2p + 1` on its right
+    /// when it groups from the left, so an equal operator to the right stops.
+    fn expr(&mut self, min: u8) -> Result<Expr, ParseError> {
+        let mut lhs = self.prefix()?;
+        while let Some((at, c)) = self.peek() {
+            if c == ')' {
+                break;
+            }
+            let op = BinOp::from_char(c).ok_or(ParseError::Unexpected { at, found: c })?;
+            let &(precedence, assoc) = self
+                .operators
+                .binary
+                .get(&op)
+                .ok_or(ParseError::UnknownOperator { at, op: c })?;
+            if precedence * 2 < min {
+                break;
+            }
+            self.chars.next();
+            let right = match assoc {
+                Assoc::Left => precedence * 2 + 1,
+                Assoc::Right => precedence * 2,
+            };
+            let rhs = self.expr(right)?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn prefix(&mut self) -> Result<Expr, ParseError> {
+        let (at, c) = self.peek().ok_or(ParseError::UnexpectedEnd)?;
+        if c.is_ascii_digit() {
+            let number = self.take_while(at, |c| c.is_ascii_digit());
+            return number
+                .parse()
+                .map(Expr::Number)
+                .map_err(|_| ParseError::Number {
+                    at,
+                    number: number.to_owned(),
+                });
+        }
+        if c.is_alphabetic() || c == '_' {
+            let name = self.take_while(at, |c| c.is_alphanumeric() || c == '_');
+            return Ok(Expr::Variable(name.to_owned()));
+        }
+        self.chars.next();
+        match c {
+            '(' => {
+                let expr = self.expr(0)?;
+                match self.peek() {
+                    Some((_, ')')) => {
+                        self.chars.next();
+                        Ok(expr)
+                    }
+                    Some((at, found)) => Err(ParseError::Unexpected { at, found }),
+                    None => Err(ParseError::UnexpectedEnd),
+                }
+            }
+            '-' => match self.operators.negation {
+                Some(precedence) => Ok(Expr::Neg(Box::new(self.expr(precedence * 2)?))),
+                None => Err(ParseError::UnknownOperator { at, op: c }),
+            },
+            _ => Err(ParseError::Unexpected { at, found: c }),
+        }
+    }
+}
+
+impl Expr {
+    /// The value of an expression without variables.
+    pub fn evaluate(&self) -> Result<i64, EvalError> {
+        self.evaluate_with(&HashMap::new())
+    }
+
+    /// The value of the expression, looking up variables in `vars`.
+    pub fn evaluate_with(&self, vars: &HashMap<String, i64>) -> Result<i64, EvalError> {
+        Ok(match self {
+            Expr::Number(n) => *n,
+            Expr::Variable(name) => *vars
+                .get(name)
+                .ok_or_else(|| EvalError::Unbound { name: name.clone() })?,
+            Expr::Neg(e) => e
+                .evaluate_with(vars)?
+                .checked_neg()
+                .ok_or(EvalError::Overflow)?,
+            Expr::Binary(op, l, r) => {
+                let (l, r) = (l.evaluate_with(vars)?, r.evaluate_with(vars)?);
+                match op {
+                    BinOp::Add => l.checked_add(r).ok_or(EvalError::Overflow)?,
+                    BinOp::Sub => l.checked_sub(r).ok_or(EvalError::Overflow)?,
+                    BinOp::Mul => l.checked_mul(r).ok_or(EvalError::Overflow)?,
+                    BinOp::Div if r == 0 => return Err(EvalError::DivisionByZero),
+                    BinOp::Div => l.checked_div(r).ok_or(EvalError::Overflow)?,
+                    BinOp::Pow if r < 0 => return Err(EvalError::NegativeExponent),
+                    BinOp::Pow => u32::try_from(r)
+                        .ok()
+                        .and_then(|r| l.checked_pow(r))
+                        .ok_or(EvalError::Overflow)?,
+                }
+            }
+        })
+    }
+}
+
+impl Display for Expr {
+    /// Writes the expression with every operation in brackets.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Number(n) => write!(f, "{n}"),
+            Expr::Variable(name) => write!(f, "{name}"),
+            Expr::Neg(e) => write!(f, "(-{e})"),
+            Expr::Binary(op, l, r) => write!(f, "({l} {} {r})", op.symbol()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn show(operators: &Operators, input: &str) -> String {
+        operators.parse(input).unwrap().to_string()
+    }
+
+    #[test]
+    fn precedence_tables() {
+        let maths = Operators::arithmetic();
+        assert_eq!(show(&maths, "1 + 2 * 3 - 4"), "((1 + (2 * 3)) - 4)");
+        assert_eq!(show(&maths, "2 ^ 3 ^ 2"), "(2 ^ (3 ^ 2))");
+        assert_eq!(show(&maths, "-2 ^ 2"), "(-(2 ^ 2))");
+        assert_eq!(show(&maths, "-x * -(y)"), "((-x) * (-y))");
+        assert_eq!(show(&maths, "8 / 4 / 2"), "((8 / 4) / 2)");
+        let flat =
+            Operators::new()
+                .binary(BinOp::Add, 1, Assoc::Left)
+                .binary(BinOp::Mul, 1, Assoc::Left);
+        assert_eq!(show(&flat, "1 + 2 * 3 + 4"), "(((1 + 2) * 3) + 4)");
+    }
+
+    #[test]
+    fn parse_errors() {
+        let maths = Operators::arithmetic();
+        assert_eq!(maths.parse("1 +"), Err(ParseError::UnexpectedEnd));
+        assert_eq!(maths.parse("(1"), Err(ParseError::UnexpectedEnd));
+        assert_eq!(
+            maths.parse("1 2"),
+            Err(ParseError::Unexpected { at: 2, found: '2' })
+        );
+        assert_eq!(
+            maths.parse("1)"),
+            Err(ParseError::Unexpected { at: 1, found: ')' })
+        );
+        let sums = Operators::new().binary(BinOp::Add, 1, Assoc::Left);
+        assert_eq!(
+            sums.parse("1 * 2"),
+            Err(ParseError::UnknownOperator { at: 2, op: '*' })
+        );
+        assert_eq!(
+            sums.parse("-1"),
+            Err(ParseError::UnknownOperator { at: 0, op: '-' })
+        );
+    }
+
+    #[test]
+    fn evaluating() {
+        let maths = Operators::arithmetic();
+        let eval = |input| maths.parse(input).unwrap().evaluate();
+        assert_eq!(eval("2 + 3 * 4 ^ 2 - 10 / 3"), Ok(47));
+        assert_eq!(eval("-3 ^ 2"), Ok(-9));
+        assert_eq!(eval("1 / (2 - 2)"), Err(EvalError::DivisionByZero));
+        assert_eq!(eval("2 ^ -1"), Err(EvalError::NegativeExponent));
+        assert_eq!(eval("2 ^ 63"), Err(EvalError::Overflow));
+        let vars = HashMap::from([("x".to_owned(), 6)]);
+        let expr = maths.parse("x * (x - 1)").unwrap();
+        assert_eq!(expr.evaluate_with(&vars), Ok(30));
+        assert_eq!(
+            expr.evaluate(),
+            Err(EvalError::Unbound { name: "x".into() })
+        );
+    }
+}
@@ -0,0 +1,678 @@
+//! Context-free grammars over characters, with a CYK parser for their
+//! Chomsky normal form and an Earley parser for the grammar as written.
+//!
+//! Grammars are written one rule per line, in the style of 2020 day 19:
+//!
+//! ```text
+//! 0: 4 1 5
+//! 1: 2 3 | 3 2
+//! 4: "a"
+//! ```
+//!
+//! A rule's name is anything up to the colon. Each alternative is a list of
+//! variable names and quoted strings, and `""` or an empty alternative
+//! matches nothing. Blank lines and lines starting with `#` are skipped.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{self, Display},
+    str::FromStr,
+};
+use thiserror::Error;
+
+/// The index a variable was interned at.
+pub type VarId = usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Symbol {
+    Terminal(char),
+    Variable(VarId),
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum GrammarError {
+    #[error("line {line}: {reason}")]
+    Syntax { line: usize, reason: String },
+    /// A variable is used but has no rule.
+    #[error("`{name}` is used but never defined")]
+    Undefined { name: String },
+    #[error("the grammar has no rules")]
+    Empty,
+}
+
+/// A context-free grammar. Each variable has a list of alternatives, each a
+/// sequence of symbols.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grammar {
+    names: Vec<String>,
+    ids: HashMap<String, VarId>,
+    rules: Vec<Vec<Vec<Symbol>>>,
+    /// Variables in the order their rules were first given.
+    defined: Vec<VarId>,
+    start: VarId,
+}
+
+impl Grammar {
+    /// A grammar with no rules that starts at `start`.
+    pub fn new(start: &str) -> Self {
+        let mut grammar = Self {
+            names: Vec::new(),
+            ids: HashMap::new(),
+            rules: Vec::new(),
+            defined: Vec::new(),
+            start: 0,
+        };
+        grammar.start = grammar.variable(start);
+        grammar
+    }
+
+    /// The id of the variable called `name`, adding it if it is new.
+    pub fn variable(&mut self, name: &str) -> VarId {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        self.names.push(name.to_owned());
+        self.rules.push(Vec::new());
+        self.ids.insert(name.to_owned(), self.names.len() - 1);
+        self.names.len() - 1
+    }
+
+    pub fn id(&self, name: &str) -> Option<VarId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: VarId) -> &str {
+        &self.names[id]
+    }
+
+    pub fn start(&self) -> VarId {
+        self.start
+    }
+
+    pub fn set_start(&mut self, name: &str) {
+        self.start = self.variable(name);
+    }
+
+    /// The alternatives for `id`.
+    pub fn rules(&self, id: VarId) -> &[Vec<Symbol>] {
+        &self.rules[id]
+    }
+
+    /// Adds an alternative for `name`.
+    pub fn add_rule(&mut self, name: &str, body: Vec<Symbol>) {
+        let id = self.variable(name);
+        if self.rules[id].is_empty() {
+            self.defined.push(id);
+        }
+        self.rules[id].push(body);
+    }
+
+    /// Parses one line of the text format and replaces every alternative of
+    /// the rule it defines.
+    pub fn define(&mut self, line: &str) -> Result<VarId, GrammarError> {
+        self.define_line(line, 1)
+    }
+
+    fn define_line(&mut self, line: &str, number: usize) -> Result<VarId, GrammarError> {
+        let syntax = |reason: &str| GrammarError::Syntax {
+            line: number,
+            reason: reason.to_owned(),
+        };
+        let (name, body) = line.split_once(':').ok_or_else(|| syntax("missing `:`"))?;
+        let name = name.trim();
+        if !is_name(name) {
+            return Err(syntax(
+                "rule names cannot be empty or hold spaces, `|` or `\"`",
+            ));
+        }
+        let mut alternatives = vec![Vec::new()];
+        let mut rest = body.trim_start();
+        while let Some(c) = rest.chars().next() {
+            if c == '|' {
+                alternatives.push(Vec::new());
+                rest = &rest[1..];
+            } else if c == '"' {
+                let end = rest[1..].find('"').ok_or_else(|| syntax("unclosed `\"`"))?;
+                let alternative = alternatives.last_mut().unwrap();
+                alternative.extend(rest[1..=end].chars().map(Symbol::Terminal));
+                rest = &rest[end + 2..];
+            } else {
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || c == '|' || c == '"')
+                    .unwrap_or(rest.len());
+                let variable = Symbol::Variable(self.variable(&rest[..end]));
+                alternatives.last_mut().unwrap().push(variable);
+                rest = &rest[end..];
+            }
+            rest = rest.trim_start();
+        }
+        let id = self.variable(name);
+        if self.rules[id].is_empty() {
+            self.defined.push(id);
+        }
+        self.rules[id] = alternatives;
+        Ok(id)
+    }
+
+    /// Checks every variable that is used has a rule.
+    fn check(&self) -> Result<(), GrammarError> {
+        let used = self.rules.iter().flatten().flatten();
+        for symbol in std::iter::once(&Symbol::Variable(self.start)).chain(used) {
+            if let Symbol::Variable(id) = *symbol {
+                if self.rules[id].is_empty() {
+                    return Err(GrammarError::Undefined {
+                        name: self.names[id].clone(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The variables that can match the empty string.
+    fn nullable(&self) -> Vec<bool> {
+        nullable(self.names.len(), self.productions())
+    }
+
+    fn productions(&self) -> impl Iterator<Item = (VarId, &[Symbol])> + Clone {
+        self.rules
+            .iter()
+            .enumerate()
+            .flat_map(|(id, alts)| alts.iter().map(move |body| (id, &body[..])))
+    }
+
+    /// Whether the whole of `input` can be derived from the start variable,
+    /// found with an Earley parser. Unlike CYK this works on the grammar as
+    /// written, so left recursion and empty rules need no rewriting.
+    pub fn matches(&self, input: &str) -> bool {
+        let input: Vec<char> = input.chars().collect();
+        let productions: Vec<(VarId, &[Symbol])> = self.productions().collect();
+        let mut by_variable = vec![Vec::new(); self.names.len()];
+        for (p, (id, _)) in productions.iter().enumerate() {
+            by_variable[*id].push(p);
+        }
+        let nullable = self.nullable();
+
+        // An item is a production, how far through it we are and where in
+        // the input it started.
+        let mut sets: Vec<Vec<(usize, usize, usize)>> = vec![Vec::new(); input.len() + 1];
+        let mut seen: Vec<HashSet<(usize, usize, usize)>> = vec![HashSet::new(); input.len() + 1];
+        for &p in &by_variable[self.start] {
+            seen[0].insert((p, 0, 0));
+            sets[0].push((p, 0, 0));
+        }
+        for at in 0..=input.len() {
+            let mut next = 0;
+            while let Some(&(p, dot, origin)) = sets[at].get(next) {
+                next += 1;
+                let (lhs, body) = productions[p];
+                let mut add = |set: usize, item, sets: &mut Vec<Vec<_>>| {
+                    if seen[set].insert(item) {
+                        sets[set].push(item);
+                    }
+                };
+                match body.get(dot) {
+                    None => {
+                        for i in 0..sets[origin].len() {
+                            let (q, d, o) = sets[origin][i];
+                            if productions[q].1.get(d) == Some(&Symbol::Variable(lhs)) {
+                                add(at, (q, d + 1, o), &mut sets);
+                            }
+                        }
+                    }
+                    Some(&Symbol::Variable(id)) => {
+                        for &q in &by_variable[id] {
+                            add(at, (q, 0, at), &mut sets);
+                        }
+                        // Completing an empty match can happen before the
+                        // item waiting on it is added, so step over it now.
+                        if nullable[id] {
+                            add(at, (p, dot + 1, origin), &mut sets);
+                        }
+                    }
+                    Some(&Symbol::Terminal(c)) => {
+                        if input.get(at) == Some(&c) {
+                            add(at + 1, (p, dot + 1, origin), &mut sets);
+                        }
+                    }
+                }
+            }
+        }
+        sets[input.len()].iter().any(|&(p, dot, origin)| {
+            let (lhs, body) = productions[p];
+            lhs == self.start && origin == 0 && dot == body.len()
+        })
+    }
+
+    /// The grammar in Chomsky normal form, where every rule either makes a
+    /// single terminal or a pair of variables.
+    pub fn to_cnf(&self) -> Cnf {
+        let mut names = self.names.clone();
+        let mut helper = vec![false; names.len()];
+        let mut fresh = |names: &mut Vec<String>, name: String, is_helper: bool| {
+            names.push(name);
+            helper.push(is_helper);
+            names.len() - 1
+        };
+
+        // START: a new start variable that never appears on the right.
+        let start = fresh(&mut names, self.names[self.start].clone(), false);
+        let mut rules = vec![(start, vec![Symbol::Variable(self.start)])];
+        rules.extend(self.productions().map(|(id, body)| (id, body.to_vec())));
+
+        // TERM: terminals alongside anything else get a variable of their own.
+        let mut terminals = HashMap::new();
+        let mut term_rules = Vec::new();
+        for (_, body) in rules.iter_mut().filter(|(_, body)| body.len() > 1) {
+            for symbol in body.iter_mut() {
+                if let Symbol::Terminal(c) = *symbol {
+                    let id = *terminals.entry(c).or_insert_with(|| {
+                        let id = fresh(&mut names, format!("\"{c}\""), true);
+                        term_rules.push((id, vec![Symbol::Terminal(c)]));
+                        id
+                    });
+                    *symbol = Symbol::Variable(id);
+                }
+            }
+        }
+        rules.extend(term_rules);
+
+        // BIN: long bodies become a chain of pairs.
+        let mut binary = Vec::with_capacity(rules.len());
+        for (id, mut body) in rules {
+            let mut lhs = id;
+            while body.len() > 2 {
+                let name = format!("{}'", names[lhs]);
+                let rest = fresh(&mut names, name, true);
+                let first = body.remove(0);
+                binary.push((lhs, vec![first, Symbol::Variable(rest)]));
+                lhs = rest;
+            }
+            binary.push((lhs, body));
+        }
+
+        // DEL: drop empty rules, adding every way of leaving out a nullable
+        // variable instead.
+        let nullable = nullable(names.len(), binary.iter().map(|(id, b)| (*id, &b[..])));
+        let accepts_empty = nullable[start];
+        let mut unit_free = HashSet::new();
+        for (id, body) in binary {
+            let keep = |i: usize| match body[i] {
+                Symbol::Variable(v) if nullable[v] => &[true, false][..],
+                _ => &[true][..],
+            };
+            match body.len() {
+                0 => {}
+                1 => {
+                    unit_free.insert((id, body));
+                }
+                _ => {
+                    for &a in keep(0) {
+                        for &b in keep(1) {
+                            let kept: Vec<_> = [(a, body[0]), (b, body[1])]
+                                .into_iter()
+                                .filter_map(|(k, s)| k.then_some(s))
+                                .collect();
+                            if !kept.is_empty() {
+                                unit_free.insert((id, kept));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // UNIT: a variable that is just another variable takes on all of that
+        // variable's other rules.
+        let mut units = vec![Vec::new(); names.len()];
+        for (id, body) in &unit_free {
+            if let [Symbol::Variable(to)] = body[..] {
+                units[*id].push(to);
+            }
+        }
+        let mut cnf = Cnf {
+            names,
+            helper,
+            start,
+            accepts_empty,
+            terminals: HashMap::new(),
+            pairs: HashMap::new(),
+        };
+        let mut by_variable: Vec<Vec<&[Symbol]>> = vec![Vec::new(); cnf.names.len()];
+        for (id, body) in &unit_free {
+            by_variable[*id].push(body);
+        }
+        for id in 0..cnf.names.len() {
+            let mut reach = vec![id];
+            let mut seen = HashSet::from([id]);
+            let mut at = 0;
+            while let Some(&from) = reach.get(at) {
+                for &to in &units[from] {
+                    if seen.insert(to) {
+                        reach.push(to);
+                    }
+                }
+                at += 1;
+            }
+            let (mut chars, mut pairs) = (HashSet::new(), HashSet::new());
+            for body in reach.iter().flat_map(|&from| &by_variable[from]) {
+                match **body {
+                    [Symbol::Terminal(c)] if chars.insert(c) => {
+                        cnf.terminals.entry(c).or_default().push(id);
+                    }
+                    [Symbol::Variable(l), Symbol::Variable(r)] if pairs.insert((l, r)) => {
+                        cnf.pairs.entry((l, r)).or_default().push(id);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        cnf
+    }
+}
+
+fn is_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(|c: char| c.is_whitespace() || c == '|' || c == '"')
+}
+
+fn nullable<'a>(
+    variables: usize,
+    productions: impl Iterator<Item = (VarId, &'a [Symbol])> + Clone,
+) -> Vec<bool> {
+    let mut nullable = vec![false; variables];
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (id, body) in productions.clone() {
+            if !nullable[id]
+                && body
+                    .iter()
+                    .all(|s| matches!(s, Symbol::Variable(v) if nullable[*v]))
+            {
+                nullable[id] = true;
+                changed = true;
+            }
+        }
+    }
+    nullable
+}
+
+impl FromStr for Grammar {
+    type Err = GrammarError;
+
+    /// Reads a grammar in the text format, starting at the first rule.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut grammar: Option<Grammar> = None;
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let grammar = grammar.get_or_insert_with(|| {
+                let name = line.split_once(':').map_or(line, |(n, _)| n.trim());
+                Grammar::new(name)
+            });
+            grammar.define_line(line, i + 1)?;
+        }
+        let grammar = grammar.ok_or(GrammarError::Empty)?;
+        grammar.check()?;
+        Ok(grammar)
+    }
+}
+
+impl Display for Grammar {
+    /// Writes the grammar in the text format, starting rule first.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let others = self.defined.iter().filter(|&&id| id != self.start);
+        let first = Some(self.start).filter(|&id| !self.rules[id].is_empty());
+        for (n, id) in first.into_iter().chain(others.copied()).enumerate() {
+            if n > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}:", self.names[id])?;
+            for (i, body) in self.rules[id].iter().enumerate() {
+                if i > 0 {
+                    write!(f, " |")?;
+                }
+                if body.is_empty() {
+                    write!(f, " \"\"")?;
+                }
+                let mut symbols = body.iter().peekable();
+                while let Some(symbol) = symbols.next() {
+                    match symbol {
+                        Symbol::Variable(v) => write!(f, " {}", self.names[*v])?,
+                        Symbol::Terminal(c) => {
+                            write!(f, " \"{c}")?;
+                            while let Some(Symbol::Terminal(c)) = symbols.peek() {
+                                write!(f, "{c}")?;
+                                symbols.next();
+                            }
+                            write!(f, "\"")?;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A derivation of some input. Variables the normal form added are folded
+/// back into their parents, but variables that were only renamed by unit
+/// rules do not appear.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Tree {
+    Leaf(char),
+    Node {
+        variable: String,
+        children: Vec<Tree>,
+    },
+}
+
+/// How CYK found a variable over a span of the input.
+#[derive(Debug, Clone, Copy)]
+enum Step {
+    Terminal,
+    Split {
+        len: usize,
+        left: VarId,
+        right: VarId,
+    },
+}
+
+/// A grammar in Chomsky normal form, from [`Grammar::to_cnf`].
+#[derive(Debug, Clone)]
+pub struct Cnf {
+    names: Vec<String>,
+    /// Whether a variable was made up by the conversion.
+    helper: Vec<bool>,
+    start: VarId,
+    accepts_empty: bool,
+    terminals: HashMap<char, Vec<VarId>>,
+    pairs: HashMap<(VarId, VarId), Vec<VarId>>,
+}
+
+impl Cnf {
+    /// Whether the whole of `input` can be derived from the start variable.
+    pub fn matches(&self, input: &str) -> bool {
+        self.parse(input).is_some()
+    }
+
+    /// A parse tree for `input`, found with the CYK algorithm.
+    pub fn parse(&self, input: &str) -> Option<Tree> {
+        let input: Vec<char> = input.chars().collect();
+        let n = input.len();
+        if n == 0 {
+            return self.accepts_empty.then(|| Tree::Node {
+                variable: self.names[self.start].clone(),
+                children: Vec::new(),
+            });
+        }
+        // table[len - 1][i] holds the variables that make input[i..i + len].
+        let mut table: Vec<Vec<Vec<(VarId, Step)>>> = Vec::with_capacity(n);
+        table.push(
+            input
+                .iter()
+                .map(|c| {
+                    let vars = self.terminals.get(c).map_or(&[][..], |v| &v[..]);
+                    vars.iter().map(|&v| (v, Step::Terminal)).collect()
+                })
+                .collect(),
+        );
+        // Which cell last added each variable, to skip adding it twice.
+        let mut added = vec![usize::MAX; self.names.len()];
+        let mut cells = 0;
+        for len in 2..=n {
+            let mut row = Vec::with_capacity(n - len + 1);
+            for i in 0..=n - len {
+                let mut cell = Vec::new();
+                cells += 1;
+                for split in 1..len {
+                    for &(left, _) in &table[split - 1][i] {
+                        for &(right, _) in &table[len - split - 1][i + split] {
+                            for &v in self.pairs.get(&(left, right)).into_iter().flatten() {
+                                if added[v] != cells {
+                                    added[v] = cells;
+                                    let step = Step::Split {
+                                        len: split,
+                                        left,
+                                        right,
+                                    };
+                                    cell.push((v, step));
+                                }
+                            }
+                        }
+                    }
+                }
+                row.push(cell);
+            }
+            table.push(row);
+        }
+        table[n - 1][0]
+            .iter()
+            .any(|&(v, _)| v == self.start)
+            .then(|| self.tree(&table, &input, self.start, 0, n).remove(0))
+    }
+
+    /// The trees under `var` over `input[at..at + len]`, more than one if
+    /// `var` is a helper that gets folded away.
+    fn tree(
+        &self,
+        table: &[Vec<Vec<(VarId, Step)>>],
+        input: &[char],
+        var: VarId,
+        at: usize,
+        len: usize,
+    ) -> Vec<Tree> {
+        let (_, step) = table[len - 1][at]
+            .iter()
+            .find(|(v, _)| *v == var)
+            .expect("a variable found by CYK has a step");
+        let children = match *step {
+            Step::Terminal => vec![Tree::Leaf(input[at])],
+            Step::Split {
+                len: split,
+                left,
+                right,
+            } => {
+                let mut children = self.tree(table, input, left, at, split);
+                children.extend(self.tree(table, input, right, at + split, len - split));
+                children
+            }
+        };
+        if self.helper[var] {
+            children
+        } else {
+            vec![Tree::Node {
+                variable: self.names[var].clone(),
+                children,
+            }]
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    static RULES: &str = r#"0: 4 1 5
+1: 2 3 | 3 2
+2: 4 4 | 5 5
+3: 4 5 | 5 4
+4: "a"
+5: "b""#;
+
+    #[test]
+    fn text_format() {
+        let grammar: Grammar = RULES.parse().unwrap();
+        assert_eq!(grammar.name(grammar.start()), "0");
+        assert_eq!(grammar.to_string(), RULES);
+        let grammar: Grammar = "s: \"ab\" s | \"\"\n# comment\n".parse().unwrap();
+        assert_eq!(grammar.to_string(), "s: \"ab\" s | \"\"");
+        assert_eq!(
+            "0: 1".parse::<Grammar>(),
+            Err(GrammarError::Undefined { name: "1".into() })
+        );
+        assert!(matches!(
+            "0 \"a\"".parse::<Grammar>(),
+            Err(GrammarError::Syntax { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn matching() {
+        let grammar: Grammar = RULES.parse().unwrap();
+        let cnf = grammar.to_cnf();
+        for (message, valid) in [
+            ("ababbb", true),
+            ("abbbab", true),
+            ("bababa", false),
+            ("aaabbb", false),
+            ("aaaabbb", false),
+        ] {
+            assert_eq!(cnf.matches(message), valid, "{message}");
+            assert_eq!(grammar.matches(message), valid, "{message}");
+        }
+    }
+
+    #[test]
+    fn empty_and_left_recursive_rules() {
+        // Balanced brackets, left recursive and allowed to be empty.
+        let grammar: Grammar = "s: s \"(\" s \")\" | \"\"".parse().unwrap();
+        let cnf = grammar.to_cnf();
+        for (message, valid) in [
+            ("", true),
+            ("()", true),
+            ("(()())()", true),
+            ("())(", false),
+        ] {
+            assert_eq!(cnf.matches(message), valid, "{message:?}");
+            assert_eq!(grammar.matches(message), valid, "{message:?}");
+        }
+        // Unit chains and a start variable used on the right.
+        let grammar: Grammar = "a: b | \"x\" a\nb: c\nc: \"y\"".parse().unwrap();
+        assert!(grammar.to_cnf().matches("xxy") && grammar.matches("xxy"));
+        assert!(!grammar.to_cnf().matches("xx") && !grammar.matches("xx"));
+    }
+
+    #[test]
+    fn parse_trees() {
+        let grammar: Grammar = "sum: sum \"+\" n | n\nn: \"1\" | \"2\"".parse().unwrap();
+        let node = |variable: &str, children| Tree::Node {
+            variable: variable.into(),
+            children,
+        };
+        assert_eq!(
+            grammar.to_cnf().parse("1+2"),
+            Some(node(
+                "sum",
+                vec![
+                    node("sum", vec![Tree::Leaf('1')]),
+                    Tree::Leaf('+'),
+                    node("n", vec![Tree::Leaf('2')])
+                ]
+            ))
+        );
+        assert_eq!(grammar.to_cnf().parse("1+"), None);
+    }
+}
@@ -0,0 +1,480 @@
+//! Directed graphs whose nodes are interned into dense ids.
+
+use std::{
+    borrow::Borrow,
+    collections::{HashMap, VecDeque},
+    fmt::{Display, Write},
+    hash::Hash,
+};
+use thiserror::Error;
+
+/// The index a node was interned at.
+pub type NodeId = usize;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum GraphError {
+    /// The walk needed the graph to be acyclic but came back round to a node.
+    #[error("node {node} is on a cycle")]
+    Cycle { node: NodeId },
+}
+
+/// A directed graph with a weight on every edge. Nodes are given ids in the
+/// order they are first seen, and looked up by value or by id.
+#[derive(Debug, Clone)]
+pub struct Graph<N, W = ()> {
+    nodes: Vec<N>,
+    ids: HashMap<N, NodeId>,
+    edges: Vec<Vec<(NodeId, W)>>,
+}
+
+impl<N, W> Default for Graph<N, W> {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            ids: HashMap::new(),
+            edges: Vec::new(),
+        }
+    }
+}
+
+impl<N: Clone + Eq + Hash, W> Graph<N, W> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The id of `node`, adding it if it is new.
+    pub fn intern(&mut self, node: N) -> NodeId {
+        if let Some(&id) = self.ids.get(&node) {
+            return id;
+        }
+        let id = self.nodes.len();
+        self.ids.insert(node.clone(), id);
+        self.nodes.push(node);
+        self.edges.push(Vec::new());
+        id
+    }
+
+    pub fn id<Q>(&self, node: &Q) -> Option<NodeId>
+    where
+        N: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.ids.get(node).copied()
+    }
+
+    /// The node with the given id. Panics if there is no such node.
+    pub fn node(&self, id: NodeId) -> &N {
+        &self.nodes[id]
+    }
+
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Adds an edge, interning both ends, and returns their ids.
+    pub fn add_edge(&mut self, from: N, to: N, weight: W) -> (NodeId, NodeId) {
+        let (from, to) = (self.intern(from), self.intern(to));
+        self.edges[from].push((to, weight));
+        (from, to)
+    }
+
+    /// The edges leaving `id` in the order they were added.
+    pub fn edges(&self, id: NodeId) -> &[(NodeId, W)] {
+        &self.edges[id]
+    }
+
+    pub fn neighbours(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.edges[id].iter().map(|&(to, _)| to)
+    }
+
+    /// The same graph with every edge pointing the other way.
+    pub fn reversed(&self) -> Self
+    where
+        W: Clone,
+    {
+        let mut edges = vec![Vec::new(); self.len()];
+        for (from, out) in self.edges.iter().enumerate() {
+            for (to, weight) in out {
+                edges[*to].push((from, weight.clone()));
+            }
+        }
+        Self {
+            nodes: self.nodes.clone(),
+            ids: self.ids.clone(),
+            edges,
+        }
+    }
+
+    /// Every node that can be reached from `from`, including itself, in
+    /// breadth first order.
+    pub fn reachable(&self, from: NodeId) -> Vec<NodeId> {
+        let mut seen = vec![false; self.len()];
+        seen[from] = true;
+        let mut order = vec![from];
+        let mut at = 0;
+        while let Some(&id) = order.get(at) {
+            for next in self.neighbours(id) {
+                if !seen[next] {
+                    seen[next] = true;
+                    order.push(next);
+                }
+            }
+            at += 1;
+        }
+        order
+    }
+
+    /// The path with the fewest edges from `from` to the first node that
+    /// satisfies `goal`, both ends included.
+    pub fn shortest_path(
+        &self,
+        from: NodeId,
+        mut goal: impl FnMut(NodeId) -> bool,
+    ) -> Option<Vec<NodeId>> {
+        let mut parent = vec![None; self.len()];
+        parent[from] = Some(from);
+        let mut queue = VecDeque::from([from]);
+        while let Some(id) = queue.pop_front() {
+            if goal(id) {
+                let mut path = vec![id];
+                let mut at = id;
+                while at != from {
+                    at = parent[at]?;
+                    path.push(at);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for next in self.neighbours(id) {
+                if parent[next].is_none() {
+                    parent[next] = Some(id);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// The nodes ordered so every edge points forwards.
+    pub fn toposort(&self) -> Result<Vec<NodeId>, GraphError> {
+        let mut incoming = vec![0; self.len()];
+        for to in self.edges.iter().flatten().map(|(to, _)| *to) {
+            incoming[to] += 1;
+        }
+        let mut order: Vec<_> = (0..self.len()).filter(|&id| incoming[id] == 0).collect();
+        let mut at = 0;
+        while let Some(&id) = order.get(at) {
+            for next in self.neighbours(id) {
+                incoming[next] -= 1;
+                if incoming[next] == 0 {
+                    order.push(next);
+                }
+            }
+            at += 1;
+        }
+        match incoming.iter().position(|&n| n > 0) {
+            Some(node) => Err(GraphError::Cycle { node }),
+            None => Ok(order),
+        }
+    }
+
+    /// Works out a value for `from` from the values of the nodes its edges
+    /// lead to, visiting each node reachable from it once. `f` is given a node
+    /// and, for each of its edges, the value at the other end with the weight.
+    pub fn fold_dag<T: Clone>(
+        &self,
+        from: NodeId,
+        f: impl FnMut(NodeId, &[(T, &W)]) -> T,
+    ) -> Result<T, GraphError> {
+        self.fold_until(from, |_| false, f)
+    }
+
+    /// [`fold_dag`](Self::fold_dag), except that the nodes `stop` picks out
+    /// are given no children and their edges are not followed.
+    fn fold_until<T: Clone>(
+        &self,
+        from: NodeId,
+        stop: impl Fn(NodeId) -> bool,
+        mut f: impl FnMut(NodeId, &[(T, &W)]) -> T,
+    ) -> Result<T, GraphError> {
+        let mut memo = vec![Visit::<T>::New; self.len()];
+        // The nodes being folded, each with the values of the edges done so
+        // far. Every node on it is open, so meeting one again is a cycle.
+        let mut stack = vec![(from, Vec::new())];
+        memo[from] = Visit::Open;
+        loop {
+            let (id, children) = stack.last_mut().expect("the stack is never left empty");
+            let id = *id;
+            if !stop(id) {
+                if let Some((to, weight)) = self.edges[id].get(children.len()) {
+                    match &memo[*to] {
+                        Visit::Done(value) => children.push((value.clone(), weight)),
+                        Visit::Open => return Err(GraphError::Cycle { node: *to }),
+                        Visit::New => {
+                            memo[*to] = Visit::Open;
+                            stack.push((*to, Vec::new()));
+                        }
+                    }
+                    continue;
+                }
+            }
+            let (id, children) = stack.pop().expect("the stack is never left empty");
+            let value = f(id, &children);
+            memo[id] = Visit::Done(value.clone());
+            match stack.last_mut() {
+                Some((parent, siblings)) => {
+                    let weight = &self.edges[*parent][siblings.len()].1;
+                    siblings.push((value, weight));
+                }
+                None => return Ok(value),
+            }
+        }
+    }
+
+    /// How many different paths lead from `from` to `to`. Paths end at `to`,
+    /// so whatever lies beyond it, or cannot reach it at all, is never
+    /// walked and may have cycles.
+    pub fn count_paths(&self, from: NodeId, to: NodeId) -> Result<usize, GraphError> {
+        let mut leads_to = vec![false; self.len()];
+        leads_to[to] = true;
+        let mut incoming = vec![Vec::new(); self.len()];
+        for (id, out) in self.edges.iter().enumerate() {
+            for &(next, _) in out {
+                incoming[next].push(id);
+            }
+        }
+        let mut queue = vec![to];
+        while let Some(id) = queue.pop() {
+            for &prev in &incoming[id] {
+                if !leads_to[prev] {
+                    leads_to[prev] = true;
+                    queue.push(prev);
+                }
+            }
+        }
+        self.fold_until(
+            from,
+            |id| id == to || !leads_to[id],
+            |id, children| {
+                if id == to {
+                    1
+                } else {
+                    children.iter().map(|(count, _)| count).sum()
+                }
+            },
+        )
+    }
+
+    /// The strongly connected components, each listed once, with a component
+    /// coming after every component it has an edge into.
+    pub fn scc(&self) -> Vec<Vec<NodeId>> {
+        let mut tarjan = Tarjan {
+            index: vec![None; self.len()],
+            low: vec![0; self.len()],
+            on_stack: vec![false; self.len()],
+            stack: Vec::new(),
+            next: 0,
+            components: Vec::new(),
+        };
+        for id in 0..self.len() {
+            if tarjan.index[id].is_none() {
+                tarjan.visit(self, id);
+            }
+        }
+        tarjan.components
+    }
+
+    /// The graph in Graphviz's DOT language, without edge labels.
+    pub fn to_dot(&self) -> String
+    where
+        N: Display,
+    {
+        self.to_dot_with(|_| None)
+    }
+
+    /// The graph in Graphviz's DOT language, labelling each edge with
+    /// whatever `label` gives for its weight.
+    pub fn to_dot_with(&self, label: impl Fn(&W) -> Option<String>) -> String
+    where
+        N: Display,
+    {
+        let mut dot = String::from("digraph {\n");
+        for (id, node) in self.nodes.iter().enumerate() {
+            let name = node.to_string().replace('"', "\\\"");
+            writeln!(dot, "    {id} [label=\"{name}\"];").unwrap();
+        }
+        for (from, out) in self.edges.iter().enumerate() {
+            for (to, weight) in out {
+                match label(weight) {
+                    Some(l) => writeln!(dot, "    {from} -> {to} [label=\"{l}\"];"),
+                    None => writeln!(dot, "    {from} -> {to};"),
+                }
+                .unwrap();
+            }
+        }
+        dot.push('}');
+        dot
+    }
+}
+
+#[derive(Clone)]
+enum Visit<T> {
+    New,
+    Open,
+    Done(T),
+}
+
+struct Tarjan {
+    index: Vec<Option<usize>>,
+    low: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<NodeId>,
+    next: usize,
+    components: Vec<Vec<NodeId>>,
+}
+
+impl Tarjan {
+    fn open(&mut self, id: NodeId) {
+        self.index[id] = Some(self.next);
+        self.low[id] = self.next;
+        self.next += 1;
+        self.stack.push(id);
+        self.on_stack[id] = true;
+    }
+
+    /// Runs the search from `root`, keeping the nodes being visited and how
+    /// many of their edges have been followed on `calls` rather than the
+    /// call stack.
+    fn visit<N: Clone + Eq + Hash, W>(&mut self, graph: &Graph<N, W>, root: NodeId) {
+        self.open(root);
+        let mut calls = vec![(root, 0)];
+        while let Some((id, edge)) = calls.last_mut() {
+            let id = *id;
+            if let Some(&(to, _)) = graph.edges(id).get(*edge) {
+                *edge += 1;
+                match self.index[to] {
+                    None => {
+                        self.open(to);
+                        calls.push((to, 0));
+                    }
+                    Some(index) if self.on_stack[to] => self.low[id] = self.low[id].min(index),
+                    Some(_) => {}
+                }
+                continue;
+            }
+            calls.pop();
+            if let Some(&(parent, _)) = calls.last() {
+                self.low[parent] = self.low[parent].min(self.low[id]);
+            }
+            if Some(self.low[id]) == self.index[id] {
+                let mut component = Vec::new();
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack[member] = false;
+                    component.push(member);
+                    if member == id {
+                        break;
+                    }
+                }
+                self.components.push(component);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn graph(edges: &[(&'static str, &'static str)]) -> Graph<&'static str> {
+        let mut graph = Graph::new();
+        for &(from, to) in edges {
+            graph.add_edge(from, to, ());
+        }
+        graph
+    }
+
+    #[test]
+    fn interning() {
+        let mut g = graph(&[("a", "b"), ("b", "c"), ("a", "c")]);
+        assert_eq!(g.len(), 3);
+        assert_eq!(g.intern("b"), 1);
+        assert_eq!(g.id("c"), Some(2));
+        assert_eq!(g.id("d"), None);
+        assert_eq!(g.neighbours(0).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(g.reversed().neighbours(2).collect::<Vec<_>>(), vec![0, 1]);
+    }
+
+    #[test]
+    fn searching() {
+        let g = graph(&[("a", "b"), ("b", "c"), ("c", "d"), ("a", "c"), ("e", "a")]);
+        assert_eq!(g.reachable(0), vec![0, 1, 2, 3]);
+        assert_eq!(g.shortest_path(0, |id| id == 3), Some(vec![0, 2, 3]));
+        assert_eq!(g.shortest_path(0, |id| id == 4), None);
+        assert_eq!(g.toposort(), Ok(vec![4, 0, 1, 2, 3]));
+        assert_eq!(g.count_paths(0, 3), Ok(2));
+        assert_eq!(g.count_paths(4, 3), Ok(2));
+    }
+
+    #[test]
+    fn cycles() {
+        let g = graph(&[
+            ("a", "b"),
+            ("b", "c"),
+            ("c", "a"),
+            ("c", "d"),
+            ("d", "e"),
+            ("e", "d"),
+        ]);
+        assert!(matches!(g.toposort(), Err(GraphError::Cycle { .. })));
+        assert!(g.count_paths(0, 3).is_err());
+        // Everything past `to`, and everything that cannot reach it, is left
+        // alone.
+        assert_eq!(g.count_paths(0, 2), Ok(1));
+        let stray = graph(&[("a", "b"), ("a", "c"), ("c", "d"), ("d", "c"), ("b", "e")]);
+        assert_eq!(stray.count_paths(0, 1), Ok(1));
+        let mut components = g.scc();
+        components.iter_mut().for_each(|c| c.sort());
+        assert_eq!(components, vec![vec![3, 4], vec![0, 1, 2]]);
+    }
+
+    #[test]
+    fn long_chains() {
+        let mut g = Graph::new();
+        for n in 0..200_000 {
+            g.add_edge(n, n + 1, ());
+        }
+        let length = g.fold_dag(0, |_, children| {
+            children.iter().map(|(n, _)| n + 1).max().unwrap_or(0)
+        });
+        assert_eq!(length, Ok(200_000));
+        assert_eq!(g.count_paths(0, 200_000), Ok(1));
+        assert_eq!(g.scc().len(), 200_001);
+    }
+
+    #[test]
+    fn weighted_fold() {
+        let mut g = Graph::new();
+        g.add_edge("gold", "olive", 1);
+        g.add_edge("gold", "plum", 2);
+        g.add_edge("olive", "blue", 3);
+        g.add_edge("olive", "black", 4);
+        g.add_edge("plum", "blue", 5);
+        g.add_edge("plum", "black", 6);
+        let inside = g.fold_dag(0, |_, children| {
+            children.iter().map(|(n, w)| *w * (1 + n)).sum::<usize>()
+        });
+        assert_eq!(inside, Ok(32));
+        assert_eq!(
+            graph(&[("a", "b")]).to_dot(),
+            "digraph {\n    0 [label=\"a\"];\n    1 [label=\"b\"];\n    0 -> 1;\n}"
+        );
+    }
+}
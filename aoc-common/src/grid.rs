@@ -0,0 +1,471 @@
+use std::{
+    fmt::{self, Display},
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+use thiserror::Error;
+
+/// A cell's position as `(x, y)`, counting columns from the left and rows
+/// from the top.
+pub type Pos = (usize, usize);
+
+/// Up, right, down and left as `(dx, dy)` steps.
+pub const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+/// Every direction to a touching cell, clockwise from up.
+pub const ALL_DIRECTIONS: [(isize, isize); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+/// Which edges of a grid carry on from the opposite edge.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Wrap {
+    #[default]
+    None,
+    /// Walking off the left or right edge comes back on the other side.
+    Horizontal,
+    /// Walking off the top or bottom edge comes back on the other side.
+    Vertical,
+    Both,
+}
+
+impl Wrap {
+    fn horizontal(self) -> bool {
+        matches!(self, Wrap::Horizontal | Wrap::Both)
+    }
+
+    fn vertical(self) -> bool {
+        matches!(self, Wrap::Vertical | Wrap::Both)
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum GridError {
+    #[error("the grid has no cells")]
+    Empty,
+    /// A row is not as wide as the first one.
+    #[error("row {row} is {len} wide but the grid is {width} wide")]
+    Ragged {
+        row: usize,
+        len: usize,
+        width: usize,
+    },
+    /// The cells do not fill the grid exactly.
+    #[error("{len} cells do not make a {width}x{height} grid")]
+    Size {
+        width: usize,
+        height: usize,
+        len: usize,
+    },
+    /// A character in a map is not a cell.
+    #[error("`{c}` at ({x}, {y}): {reason}")]
+    Cell {
+        x: usize,
+        y: usize,
+        c: char,
+        reason: String,
+    },
+}
+
+/// A rectangle of cells stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from its cells in row order.
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Result<Self, GridError> {
+        if width * height != cells.len() {
+            return Err(GridError::Size {
+                width,
+                height,
+                len: cells.len(),
+            });
+        }
+        Ok(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Builds a grid from its rows, which must all be as wide as the first.
+    /// Like [`from_vec`](Self::from_vec), it allows a grid with no cells.
+    pub fn from_rows<R>(rows: impl IntoIterator<Item = R>) -> Result<Self, GridError>
+    where
+        R: IntoIterator<Item = T>,
+    {
+        let mut cells = Vec::new();
+        let (mut width, mut height) = (0, 0);
+        for (y, row) in rows.into_iter().enumerate() {
+            let before = cells.len();
+            cells.extend(row);
+            let len = cells.len() - before;
+            if y == 0 {
+                width = len;
+            } else if len != width {
+                return Err(GridError::Ragged { row: y, len, width });
+            }
+            height += 1;
+        }
+        Ok(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Parses a character map a line at a row, converting each character
+    /// with `cell`. Trailing blank lines are ignored, and a map with no cells
+    /// is an error.
+    pub fn parse_with<E: Display>(
+        map: &str,
+        mut cell: impl FnMut(char) -> Result<T, E>,
+    ) -> Result<Self, GridError> {
+        let mut rows = Vec::new();
+        for (y, line) in map.trim_end_matches(['\n', '\r']).lines().enumerate() {
+            let row = line
+                .chars()
+                .enumerate()
+                .map(|(x, c)| {
+                    cell(c).map_err(|err| GridError::Cell {
+                        x,
+                        y,
+                        c,
+                        reason: err.to_string(),
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            rows.push(row);
+        }
+        if rows.iter().all(Vec::is_empty) {
+            return Err(GridError::Empty);
+        }
+        Self::from_rows(rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Every cell in row order.
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn cells_mut(&mut self) -> &mut [T] {
+        &mut self.cells
+    }
+
+    pub fn contains(&self, (x, y): Pos) -> bool {
+        x < self.width && y < self.height
+    }
+
+    /// Where the cell at `pos` sits in [`cells`](Self::cells).
+    pub fn index_of(&self, pos: Pos) -> Option<usize> {
+        self.contains(pos).then_some(pos.1 * self.width + pos.0)
+    }
+
+    /// The position of the cell at `index` in [`cells`](Self::cells), or
+    /// `None` past the last one.
+    pub fn pos_of(&self, index: usize) -> Option<Pos> {
+        (index < self.cells.len()).then(|| (index % self.width, index / self.width))
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.index_of(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.index_of(pos).map(|i| &mut self.cells[i])
+    }
+
+    /// Takes a `(dx, dy)` step from `pos`, or `None` when it walks off an
+    /// edge that does not wrap or the grid has no cells that way to land on.
+    pub fn offset(&self, (x, y): Pos, (dx, dy): (isize, isize), wrap: Wrap) -> Option<Pos> {
+        Some((
+            step(x, dx, self.width, wrap.horizontal())?,
+            step(y, dy, self.height, wrap.vertical())?,
+        ))
+    }
+
+    /// The cells above, right of, below and left of `pos` that are on the
+    /// grid.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        ORTHOGONAL
+            .into_iter()
+            .filter_map(move |dir| self.offset(pos, dir, Wrap::None))
+    }
+
+    /// The cells touching `pos`, diagonals included, that are on the grid.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        ALL_DIRECTIONS
+            .into_iter()
+            .filter_map(move |dir| self.offset(pos, dir, Wrap::None))
+    }
+
+    /// Walks from `pos` in steps of `dir`, not including `pos` itself. Stops
+    /// at an edge that does not wrap or once it comes back round to `pos`.
+    pub fn ray(&self, pos: Pos, dir: (isize, isize), wrap: Wrap) -> impl Iterator<Item = Pos> + '_ {
+        let mut at = Some(pos);
+        std::iter::from_fn(move || {
+            at = self.offset(at?, dir, wrap).filter(|&next| next != pos);
+            at
+        })
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    /// Every row from the top, including the empty ones of a grid with no
+    /// width.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> + '_ {
+        assert!(
+            x < self.width,
+            "column {x} is outside a {}x{} grid",
+            self.width,
+            self.height
+        );
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T> + '_> + '_ {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Every position in row order.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> + '_ {
+        (0..self.height).flat_map(move |y| (0..self.width).map(move |x| (x, y)))
+    }
+
+    /// Every cell with its position, in row order.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> + '_ {
+        self.positions().zip(&self.cells)
+    }
+
+    /// The first position, in row order, whose cell matches.
+    pub fn position(&self, matches: impl FnMut(&T) -> bool) -> Option<Pos> {
+        self.cells
+            .iter()
+            .position(matches)
+            .and_then(|i| self.pos_of(i))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Grid<T>
+where
+    T: TryFrom<char>,
+    T::Error: Display,
+{
+    /// Parses a character map with each cell's `TryFrom<char>`.
+    pub fn parse(map: &str) -> Result<Self, GridError> {
+        Self::parse_with(map, T::try_from)
+    }
+}
+
+fn step(at: usize, by: isize, len: usize, wrap: bool) -> Option<usize> {
+    let to = at as isize + by;
+    if wrap {
+        (len > 0).then(|| to.rem_euclid(len as isize) as usize)
+    } else {
+        (0..len as isize).contains(&to).then_some(to as usize)
+    }
+}
+
+impl<T> FromStr for Grid<T>
+where
+    T: TryFrom<char>,
+    T::Error: Display,
+{
+    type Err = GridError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &Self::Output {
+        match self.index_of(pos) {
+            Some(i) => &self.cells[i],
+            None => panic!("{pos:?} is outside a {}x{} grid", self.width, self.height),
+        }
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut Self::Output {
+        match self.index_of(pos) {
+            Some(i) => &mut self.cells[i],
+            None => panic!("{pos:?} is outside a {}x{} grid", self.width, self.height),
+        }
+    }
+}
+
+/// Writes each row on its own line with no separator between cells.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    static MAP: &str = "#..\n.#.\n..#\n#..\n";
+
+    fn map() -> Grid<char> {
+        MAP.parse().unwrap()
+    }
+
+    #[test]
+    fn empty_grids() {
+        let grid = Grid::<char>::from_vec(0, 2, vec![]).unwrap();
+        assert_eq!(grid.rows().collect::<Vec<_>>(), vec![&[][..], &[][..]]);
+        assert_eq!(grid.to_string(), "\n");
+        assert_eq!(Grid::filled(0, 0, '.').to_string(), "");
+        assert_eq!(Grid::filled(3, 0, '.').rows().count(), 0);
+        assert_eq!(Grid::from_rows(vec![vec![], vec![]]), Ok(grid.clone()));
+        assert_eq!(
+            Grid::from_rows(Vec::<Vec<char>>::new()),
+            Grid::from_vec(0, 0, vec![])
+        );
+        assert_eq!(grid.pos_of(0), None);
+        assert_eq!(grid.positions().count(), 0);
+        assert_eq!(grid.offset((0, 0), (1, 1), Wrap::Both), None);
+        assert_eq!(
+            Grid::filled(2, 0, '.').offset((0, 0), (1, 0), Wrap::Both),
+            None
+        );
+    }
+
+    #[test]
+    fn parsing() {
+        let grid = map();
+        assert_eq!((grid.width(), grid.height()), (3, 4));
+        assert_eq!(grid[(1, 1)], '#');
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.to_string(), MAP.trim_end());
+        assert_eq!(
+            Grid::<char>::parse("ab\nc\n"),
+            Err(GridError::Ragged {
+                row: 1,
+                len: 1,
+                width: 2
+            })
+        );
+        assert_eq!(Grid::<char>::parse("\n"), Err(GridError::Empty));
+        assert_eq!(Grid::<char>::parse(""), Err(GridError::Empty));
+        assert_eq!(
+            Grid::parse_with("..\n.x", |c| match c {
+                '.' => Ok(()),
+                _ => Err("not a dot"),
+            }),
+            Err(GridError::Cell {
+                x: 1,
+                y: 1,
+                c: 'x',
+                reason: "not a dot".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = map();
+        assert_eq!(
+            grid.neighbours4((0, 0)).collect::<Vec<_>>(),
+            vec![(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+        assert_eq!(grid.neighbours8((2, 3)).count(), 3);
+    }
+
+    #[test]
+    fn rays() {
+        let grid = map();
+        assert_eq!(
+            grid.ray((0, 0), (1, 1), Wrap::None).collect::<Vec<_>>(),
+            vec![(1, 1), (2, 2)]
+        );
+        assert_eq!(
+            grid.ray((1, 0), (2, 1), Wrap::Horizontal)
+                .collect::<Vec<_>>(),
+            vec![(0, 1), (2, 2), (1, 3)]
+        );
+        assert_eq!(
+            grid.ray((0, 0), (1, 0), Wrap::Both).collect::<Vec<_>>(),
+            vec![(1, 0), (2, 0)]
+        );
+        assert_eq!(grid.offset((0, 0), (0, -1), Wrap::Vertical), Some((0, 3)));
+        assert_eq!(grid.offset((0, 0), (0, -1), Wrap::Horizontal), None);
+    }
+
+    #[test]
+    fn views() {
+        let grid = map();
+        assert_eq!(grid.row(3), &['#', '.', '.']);
+        assert_eq!(grid.column(0).collect::<String>(), "#..#");
+        assert_eq!(grid.columns().count(), 3);
+        assert_eq!(grid.position(|&c| c == '#'), Some((0, 0)));
+        assert_eq!(grid.pos_of(4), Some((1, 1)));
+        assert_eq!(grid.pos_of(12), None);
+        assert_eq!(
+            grid.iter()
+                .filter(|(_, &c)| c == '#')
+                .map(|(p, _)| p)
+                .collect::<Vec<_>>(),
+            vec![(0, 0), (1, 1), (2, 2), (0, 3)]
+        );
+        let flipped = grid.map(|&c| if c == '#' { '.' } else { '#' });
+        assert_eq!(flipped.row(0), &['.', '#', '#']);
+    }
+}
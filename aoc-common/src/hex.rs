@@ -0,0 +1,425 @@
+//! Hexagonal grids in cube coordinates, after the Red Blob Games guide.
+//!
+//! Directions and rendering assume pointy-topped hexes, with rows running
+//! east to west. Offset coordinates cover flat-topped hexes too.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+/// A hex in cube coordinates, where `q + r + s` is always zero.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Cube {
+    pub q: i32,
+    pub r: i32,
+    pub s: i32,
+}
+
+/// A hex in axial coordinates, cube coordinates with `s` left implied.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Axial {
+    pub q: i32,
+    pub r: i32,
+}
+
+/// The neighbours of a pointy-topped hex, clockwise from east.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    East,
+    SouthEast,
+    SouthWest,
+    West,
+    NorthWest,
+    NorthEast,
+}
+
+/// How offset coordinates shove every other row or column across by half a
+/// hex. Rows are for pointy-topped hexes and columns for flat-topped ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Offset {
+    OddRows,
+    EvenRows,
+    OddColumns,
+    EvenColumns,
+}
+
+impl Cube {
+    pub const ORIGIN: Cube = Cube { q: 0, r: 0, s: 0 };
+
+    /// Panics unless the coordinates add up to zero.
+    pub fn new(q: i32, r: i32, s: i32) -> Self {
+        assert_eq!(q + r + s, 0, "({q}, {r}, {s}) is not a cube coordinate");
+        Self { q, r, s }
+    }
+
+    pub fn neighbour(self, dir: Direction) -> Self {
+        self + dir.offset()
+    }
+
+    pub fn neighbours(self) -> impl Iterator<Item = Cube> {
+        Direction::ALL
+            .into_iter()
+            .map(move |dir| self.neighbour(dir))
+    }
+
+    /// Steps from the origin.
+    pub fn length(self) -> u32 {
+        self.q
+            .unsigned_abs()
+            .max(self.r.unsigned_abs())
+            .max(self.s.unsigned_abs())
+    }
+
+    pub fn distance(self, other: Cube) -> u32 {
+        (self - other).length()
+    }
+
+    /// The hexes exactly `radius` steps away, clockwise from the north west
+    /// corner.
+    pub fn ring(self, radius: u32) -> Vec<Cube> {
+        if radius == 0 {
+            return vec![self];
+        }
+        let mut hex = self + Direction::NorthWest.offset() * radius as i32;
+        let mut ring = Vec::with_capacity(6 * radius as usize);
+        for dir in Direction::ALL {
+            for _ in 0..radius {
+                ring.push(hex);
+                hex = hex.neighbour(dir);
+            }
+        }
+        ring
+    }
+
+    /// This hex then each ring around it out to `radius`.
+    pub fn spiral(self, radius: u32) -> Vec<Cube> {
+        (0..=radius).flat_map(|r| self.ring(r)).collect()
+    }
+
+    /// The hexes a straight line to `other` passes through, both ends
+    /// included.
+    pub fn line_to(self, other: Cube) -> Vec<Cube> {
+        let steps = self.distance(other);
+        if steps == 0 {
+            return vec![self];
+        }
+        // Nudged so a line along an edge always falls to the same side.
+        let (q, r, s) = (
+            self.q as f64 + 1e-6,
+            self.r as f64 + 2e-6,
+            self.s as f64 - 3e-6,
+        );
+        (0..=steps)
+            .map(|i| {
+                let t = i as f64 / steps as f64;
+                round(
+                    q + (other.q as f64 - q) * t,
+                    r + (other.r as f64 - r) * t,
+                    s + (other.s as f64 - s) * t,
+                )
+            })
+            .collect()
+    }
+
+    /// Turns 60° clockwise about the origin.
+    pub fn rotate_right(self) -> Self {
+        Cube::new(-self.r, -self.s, -self.q)
+    }
+
+    /// Turns 60° anticlockwise about the origin.
+    pub fn rotate_left(self) -> Self {
+        Cube::new(-self.s, -self.q, -self.r)
+    }
+
+    /// Turns `turns` lots of 60° about `centre`, clockwise when positive.
+    pub fn rotate_around(self, centre: Cube, turns: i32) -> Self {
+        let mut hex = self - centre;
+        for _ in 0..turns.rem_euclid(6) {
+            hex = hex.rotate_right();
+        }
+        hex + centre
+    }
+
+    /// The hex as `(column, row)` in an offset layout.
+    pub fn to_offset(self, offset: Offset) -> (i32, i32) {
+        let Cube { q, r, .. } = self;
+        match offset {
+            Offset::OddRows => (q + (r - (r & 1)) / 2, r),
+            Offset::EvenRows => (q + (r + (r & 1)) / 2, r),
+            Offset::OddColumns => (q, r + (q - (q & 1)) / 2),
+            Offset::EvenColumns => (q, r + (q + (q & 1)) / 2),
+        }
+    }
+
+    pub fn from_offset((col, row): (i32, i32), offset: Offset) -> Self {
+        let (q, r) = match offset {
+            Offset::OddRows => (col - (row - (row & 1)) / 2, row),
+            Offset::EvenRows => (col - (row + (row & 1)) / 2, row),
+            Offset::OddColumns => (col, row - (col - (col & 1)) / 2),
+            Offset::EvenColumns => (col, row - (col + (col & 1)) / 2),
+        };
+        Cube::from(Axial { q, r })
+    }
+}
+
+/// Rounds fractional cube coordinates to the hex they fall in.
+fn round(q: f64, r: f64, s: f64) -> Cube {
+    let (mut rq, mut rr, mut rs) = (q.round(), r.round(), s.round());
+    let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+    if dq > dr && dq > ds {
+        rq = -rr - rs;
+    } else if dr > ds {
+        rr = -rq - rs;
+    } else {
+        rs = -rq - rr;
+    }
+    Cube::new(rq as i32, rr as i32, rs as i32)
+}
+
+impl From<Axial> for Cube {
+    fn from(Axial { q, r }: Axial) -> Self {
+        Cube { q, r, s: -q - r }
+    }
+}
+
+impl From<Cube> for Axial {
+    fn from(Cube { q, r, .. }: Cube) -> Self {
+        Axial { q, r }
+    }
+}
+
+impl Add for Cube {
+    type Output = Cube;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Cube {
+            q: self.q + rhs.q,
+            r: self.r + rhs.r,
+            s: self.s + rhs.s,
+        }
+    }
+}
+
+impl AddAssign for Cube {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Cube {
+    type Output = Cube;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl SubAssign for Cube {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for Cube {
+    type Output = Cube;
+
+    fn neg(self) -> Self::Output {
+        Cube {
+            q: -self.q,
+            r: -self.r,
+            s: -self.s,
+        }
+    }
+}
+
+impl Mul<i32> for Cube {
+    type Output = Cube;
+
+    fn mul(self, rhs: i32) -> Self::Output {
+        Cube {
+            q: self.q * rhs,
+            r: self.r * rhs,
+            s: self.s * rhs,
+        }
+    }
+}
+
+impl Direction {
+    pub const ALL: [Direction; 6] = [
+        Direction::East,
+        Direction::SouthEast,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+        Direction::NorthEast,
+    ];
+
+    /// The step to the neighbour in this direction.
+    pub fn offset(self) -> Cube {
+        match self {
+            Direction::East => Cube::new(1, 0, -1),
+            Direction::SouthEast => Cube::new(0, 1, -1),
+            Direction::SouthWest => Cube::new(-1, 1, 0),
+            Direction::West => Cube::new(-1, 0, 1),
+            Direction::NorthWest => Cube::new(0, -1, 1),
+            Direction::NorthEast => Cube::new(1, -1, 0),
+        }
+    }
+
+    /// Turns `turns` lots of 60°, clockwise when positive.
+    pub fn rotate(self, turns: i32) -> Self {
+        let at = Direction::ALL.iter().position(|&d| d == self).unwrap() as i32;
+        Direction::ALL[(at + turns).rem_euclid(6) as usize]
+    }
+}
+
+impl FromStr for Direction {
+    type Err = String;
+
+    /// Reads the compass abbreviations `e`, `se`, `sw`, `w`, `nw` and `ne`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "e" => Ok(Direction::East),
+            "se" => Ok(Direction::SouthEast),
+            "sw" => Ok(Direction::SouthWest),
+            "w" => Ok(Direction::West),
+            "nw" => Ok(Direction::NorthWest),
+            "ne" => Ok(Direction::NorthEast),
+            _ => Err(format!("`{s}` is not a hex direction")),
+        }
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Direction::East => "e",
+            Direction::SouthEast => "se",
+            Direction::SouthWest => "sw",
+            Direction::West => "w",
+            Direction::NorthWest => "nw",
+            Direction::NorthEast => "ne",
+        })
+    }
+}
+
+/// Draws pointy-topped hexes as characters, each row shifted half a hex from
+/// the one above. Hexes inside the floor that are not given are `blank`.
+pub fn render(hexes: impl IntoIterator<Item = (Cube, char)>, blank: char) -> String {
+    let hexes: HashMap<Cube, char> = hexes.into_iter().collect();
+    if hexes.is_empty() {
+        return String::new();
+    }
+    // Doubled coordinates put each hex two characters along from the last.
+    let x = |h: &Cube| 2 * h.q + h.r;
+    let rows = hexes.keys().map(|h| h.r);
+    let (top, bottom) = (rows.clone().min().unwrap(), rows.max().unwrap());
+    let left = hexes.keys().map(x).min().unwrap();
+    let right = hexes.keys().map(x).max().unwrap();
+    let mut out = Vec::new();
+    for r in top..=bottom {
+        let mut line = String::new();
+        for col in left..=right {
+            if (col - r).rem_euclid(2) != 0 {
+                line.push(' ');
+                continue;
+            }
+            let hex = Cube::from(Axial {
+                q: (col - r) / 2,
+                r,
+            });
+            line.push(*hexes.get(&hex).unwrap_or(&blank));
+        }
+        out.push(line.trim_end().to_string());
+    }
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn walking() {
+        let hex = ["nw", "w", "sw", "e", "e"]
+            .iter()
+            .map(|d| d.parse::<Direction>().unwrap())
+            .fold(Cube::ORIGIN, Cube::neighbour);
+        assert_eq!(hex, Cube::ORIGIN);
+        let hex = "esew"
+            .split_inclusive(['e', 'w'])
+            .map(|d| d.parse::<Direction>().unwrap())
+            .fold(Cube::ORIGIN, Cube::neighbour);
+        assert_eq!(hex, Cube::ORIGIN.neighbour(Direction::SouthEast));
+        assert_eq!(Cube::new(3, -1, -2).distance(Cube::new(-1, 1, 0)), 4);
+        assert_eq!(Direction::East.rotate(-1), Direction::NorthEast);
+    }
+
+    #[test]
+    fn rings_and_spirals() {
+        assert_eq!(Cube::ORIGIN.ring(0), vec![Cube::ORIGIN]);
+        let ring = Cube::ORIGIN.ring(2);
+        assert_eq!(ring.len(), 12);
+        assert!(ring.iter().all(|h| h.length() == 2));
+        assert_eq!(Cube::ORIGIN.spiral(3).len(), 37);
+    }
+
+    #[test]
+    fn lines() {
+        let end = Cube::new(3, -3, 0);
+        let line = Cube::ORIGIN.line_to(end);
+        assert_eq!(line.len(), 4);
+        assert_eq!((line[0], line[3]), (Cube::ORIGIN, end));
+        assert!(line.windows(2).all(|w| w[0].distance(w[1]) == 1));
+    }
+
+    #[test]
+    fn rotation() {
+        let hex = Cube::new(2, -1, -1);
+        assert_eq!(hex.rotate_right().rotate_left(), hex);
+        assert_eq!(
+            (0..6).fold(hex, |h, _| h.rotate_right()),
+            hex,
+            "six turns go all the way round"
+        );
+        assert_eq!(
+            Direction::East.offset().rotate_right(),
+            Direction::SouthEast.offset()
+        );
+        let centre = Cube::new(1, 1, -2);
+        assert_eq!(
+            centre.neighbour(Direction::East).rotate_around(centre, -2),
+            centre.neighbour(Direction::NorthWest)
+        );
+    }
+
+    #[test]
+    fn offsets() {
+        for offset in [
+            Offset::OddRows,
+            Offset::EvenRows,
+            Offset::OddColumns,
+            Offset::EvenColumns,
+        ] {
+            for hex in Cube::ORIGIN.spiral(3) {
+                assert_eq!(Cube::from_offset(hex.to_offset(offset), offset), hex);
+            }
+        }
+        assert_eq!(Cube::new(-1, 1, 0).to_offset(Offset::OddRows), (-1, 1));
+        assert_eq!(Cube::new(-1, 1, 0).to_offset(Offset::EvenRows), (0, 1));
+        assert_eq!(Cube::new(1, -1, 0).to_offset(Offset::OddColumns), (1, -1));
+        assert_eq!(Cube::new(1, -1, 0).to_offset(Offset::EvenColumns), (1, 0));
+    }
+
+    #[test]
+    fn rendering() {
+        let floor = Cube::ORIGIN
+            .spiral(1)
+            .into_iter()
+            .map(|h| (h, if h == Cube::ORIGIN { '#' } else { 'o' }));
+        assert_eq!(render(floor, '.'), " o o\no # o\n o o");
+    }
+}
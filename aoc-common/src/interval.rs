@@ -0,0 +1,367 @@
+//! Sets of half-open intervals and maps that shift pieces of a number line.
+
+use std::{
+    cmp::{max, min},
+    ops::{Add, Range, RangeInclusive, Sub},
+};
+
+/// A set of values stored as sorted, disjoint half-open ranges. Ranges that
+/// overlap or touch are merged as they go in.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { ranges: Vec::new() }
+    }
+}
+
+impl<T: Copy + Ord> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        // Everything from `lo` up to `hi` overlaps or touches the new range.
+        let lo = self.ranges.partition_point(|r| r.end < range.start);
+        let hi = self.ranges.partition_point(|r| r.start <= range.end);
+        let merged = if lo < hi {
+            min(self.ranges[lo].start, range.start)..max(self.ranges[hi - 1].end, range.end)
+        } else {
+            range
+        };
+        self.ranges.splice(lo..hi, [merged]);
+    }
+
+    pub fn insert_inclusive(&mut self, range: RangeInclusive<T>)
+    where
+        T: Add<Output = T> + From<u8>,
+    {
+        let (start, end) = range.into_inner();
+        self.insert(start..end + T::from(1));
+    }
+
+    /// The ranges in order.
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        let at = self.ranges.partition_point(|r| r.end <= *value);
+        self.ranges.get(at).is_some_and(|r| r.start <= *value)
+    }
+
+    /// The smallest value in the set.
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|r| r.start)
+    }
+
+    /// One past the largest value in the set.
+    pub fn end(&self) -> Option<T> {
+        self.ranges.last().map(|r| r.end)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        union.extend(other.ranges.iter().cloned());
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut a, mut b) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            let (start, end) = (max(x.start, y.start), min(x.end, y.end));
+            if start < end {
+                ranges.push(start..end);
+            }
+            if x.end < y.end {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        Self { ranges }
+    }
+
+    /// Everything in this set that is not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let mut others = other.ranges.iter().peekable();
+        for range in &self.ranges {
+            let mut start = range.start;
+            while let Some(cut) = others.peek() {
+                if cut.end <= start {
+                    others.next();
+                    continue;
+                }
+                if cut.start >= range.end {
+                    break;
+                }
+                if cut.start > start {
+                    ranges.push(start..cut.start);
+                }
+                start = cut.end;
+                if cut.end > range.end {
+                    break;
+                }
+                others.next();
+            }
+            if start < range.end {
+                ranges.push(start..range.end);
+            }
+        }
+        Self { ranges }
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.intersection(other).is_empty()
+    }
+}
+
+impl<T: Copy + Ord> From<Range<T>> for IntervalSet<T> {
+    fn from(range: Range<T>) -> Self {
+        let mut set = Self::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl<T: Copy + Ord + Add<Output = T> + From<u8>> From<RangeInclusive<T>> for IntervalSet<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        let mut set = Self::new();
+        set.insert_inclusive(range);
+        set
+    }
+}
+
+impl<T: Copy + Ord> Extend<Range<T>> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = Range<T>>>(&mut self, iter: I) {
+        for range in iter {
+            self.insert(range);
+        }
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+/// A map that moves whole ranges of values along by a fixed amount and leaves
+/// every other value where it is.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PiecewiseMap<T> {
+    /// Source ranges in order, each with where its start ends up.
+    pieces: Vec<(Range<T>, T)>,
+}
+
+impl<T> Default for PiecewiseMap<T> {
+    fn default() -> Self {
+        Self { pieces: Vec::new() }
+    }
+}
+
+impl<T> PiecewiseMap<T>
+where
+    T: Copy + Ord + Add<Output = T> + Sub<Output = T>,
+{
+    /// The identity map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sends `source` to the range starting at `dest`. Panics if it overlaps a
+    /// range already in the map.
+    pub fn insert(&mut self, source: Range<T>, dest: T) {
+        if source.is_empty() {
+            return;
+        }
+        let at = self.pieces.partition_point(|(r, _)| r.end <= source.start);
+        if let Some((next, _)) = self.pieces.get(at) {
+            assert!(next.start >= source.end, "pieces of a map must not overlap");
+        }
+        self.pieces.insert(at, (source, dest));
+    }
+
+    /// The moved ranges with where each one's start ends up.
+    pub fn pieces(&self) -> &[(Range<T>, T)] {
+        &self.pieces
+    }
+
+    pub fn get(&self, value: T) -> T {
+        let at = self.pieces.partition_point(|(r, _)| r.end <= value);
+        match self.pieces.get(at) {
+            Some((range, dest)) if range.start <= value => value - range.start + *dest,
+            _ => value,
+        }
+    }
+
+    /// Cuts `range` where the pieces start and end, giving each part with
+    /// where its start ends up.
+    fn split(&self, range: Range<T>) -> Vec<(Range<T>, T)> {
+        let mut parts = Vec::new();
+        let mut at = range.start;
+        let first = self.pieces.partition_point(|(r, _)| r.end <= range.start);
+        for (piece, dest) in &self.pieces[first..] {
+            if piece.start >= range.end {
+                break;
+            }
+            if at < piece.start {
+                parts.push((at..piece.start, at));
+                at = piece.start;
+            }
+            let end = min(piece.end, range.end);
+            parts.push((at..end, at - piece.start + *dest));
+            at = end;
+        }
+        if at < range.end {
+            parts.push((at..range.end, at));
+        }
+        parts
+    }
+
+    /// Where every value in `set` ends up.
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        set.ranges()
+            .iter()
+            .flat_map(|range| self.split(range.clone()))
+            .map(|(part, dest)| dest..part.end - part.start + dest)
+            .collect()
+    }
+
+    /// A single map that does this one and then `next`.
+    pub fn then(&self, next: &Self) -> Self {
+        let mut pieces = Vec::new();
+        for (source, dest) in &self.pieces {
+            let image = *dest..source.end - source.start + *dest;
+            for (part, to) in next.split(image) {
+                let start = part.start - *dest + source.start;
+                pieces.push((start..part.end - part.start + start, to));
+            }
+        }
+        // Values this map leaves alone only go through `next`.
+        let moved: IntervalSet<T> = self.pieces.iter().map(|(r, _)| r.clone()).collect();
+        for (source, dest) in &next.pieces {
+            let untouched = IntervalSet::from(source.clone()).difference(&moved);
+            for part in untouched.ranges() {
+                pieces.push((part.clone(), part.start - source.start + *dest));
+            }
+        }
+        pieces.sort_by_key(|(r, _)| r.start);
+        Self {
+            pieces: tidy(pieces),
+        }
+    }
+}
+
+/// Drops pieces that do not move anything and joins neighbours that move by
+/// the same amount.
+fn tidy<T>(pieces: Vec<(Range<T>, T)>) -> Vec<(Range<T>, T)>
+where
+    T: Copy + Ord + Add<Output = T> + Sub<Output = T>,
+{
+    let mut tidy: Vec<(Range<T>, T)> = Vec::with_capacity(pieces.len());
+    for (range, dest) in pieces {
+        if range.start == dest {
+            continue;
+        }
+        if let Some((last, last_dest)) = tidy.last_mut() {
+            if last.end == range.start && last.end - last.start + *last_dest == dest {
+                last.end = range.end;
+                continue;
+            }
+        }
+        tidy.push((range, dest));
+    }
+    tidy
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod test {
+    use super::*;
+
+    fn set(ranges: &[Range<u32>]) -> IntervalSet<u32> {
+        ranges.iter().cloned().collect()
+    }
+
+    #[test]
+    fn merging() {
+        let s = set(&[5..8, 1..3, 3..4, 10..12, 7..11]);
+        assert_eq!(s.ranges(), &[1..4, 5..12]);
+        assert!(s.contains(&11) && !s.contains(&4) && !s.contains(&12));
+        assert_eq!((s.min(), s.end()), (Some(1), Some(12)));
+        let mut inclusive = IntervalSet::new();
+        inclusive.insert_inclusive(2..=4);
+        assert_eq!(inclusive.ranges(), &[2..5]);
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = set(&[0..10, 20..30]);
+        let b = set(&[5..25, 28..40]);
+        assert_eq!(a.union(&b).ranges(), &[0..40]);
+        assert_eq!(a.intersection(&b).ranges(), &[5..10, 20..25, 28..30]);
+        assert_eq!(a.difference(&b).ranges(), &[0..5, 25..28]);
+        assert_eq!(b.difference(&a).ranges(), &[10..20, 30..40]);
+        assert!(set(&[2..4]).is_subset(&a));
+        assert!(!set(&[8..12]).is_subset(&a));
+        assert!(set(&[10..20]).is_disjoint(&a));
+    }
+
+    fn map(pieces: &[(Range<u32>, u32)]) -> PiecewiseMap<u32> {
+        let mut map = PiecewiseMap::new();
+        for (source, dest) in pieces {
+            map.insert(source.clone(), *dest);
+        }
+        map
+    }
+
+    #[test]
+    fn mapping() {
+        // The almanac's seed-to-soil map.
+        let soil = map(&[(98..100, 50), (50..98, 52)]);
+        assert_eq!(
+            [79, 14, 55, 13, 99].map(|v| soil.get(v)),
+            [81, 14, 57, 13, 51]
+        );
+        assert_eq!(
+            soil.map_set(&set(&[45..55, 97..100])).ranges(),
+            &[45..57, 99..100]
+        );
+    }
+
+    #[test]
+    fn composing() {
+        let f = map(&[(0..10, 100), (20..30, 5)]);
+        let g = map(&[(100..105, 0), (8..12, 50)]);
+        let both = f.then(&g);
+        for v in 0..40 {
+            assert_eq!(both.get(v), g.get(f.get(v)), "{v}");
+        }
+        // Going there and back cancels out, leaving only where `back` moves
+        // values the first map did not touch.
+        let back = map(&[(100..110, 0)]);
+        assert_eq!(map(&[(0..10, 100)]).then(&back), back);
+    }
+}
@@ -0,0 +1,421 @@
+//! Putting an image back together from tiles that have been shuffled, turned
+//! and flipped, where neighbouring tiles share the cells along their edges.
+
+use crate::{
+    d4::D4,
+    grid::{Grid, Pos},
+};
+use std::{collections::HashMap, hash::Hash, str::FromStr};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum JigsawError {
+    #[error("there are no tiles")]
+    Empty,
+    /// No layout has every pair of neighbouring edges the same.
+    #[error("the tiles do not fit together")]
+    NoFit,
+    #[error("a border of {border} leaves nothing of the tiles")]
+    Border { border: usize },
+    #[error("the pattern has no cells")]
+    EmptyPattern,
+}
+
+/// A tile as it lies in the finished image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Piece<T> {
+    pub id: usize,
+    pub orientation: D4,
+    pub grid: Grid<T>,
+}
+
+struct Edges<T> {
+    top: Vec<T>,
+    right: Vec<T>,
+    bottom: Vec<T>,
+    left: Vec<T>,
+}
+
+impl<T: Clone> Edges<T> {
+    fn of(grid: &Grid<T>) -> Self {
+        Self {
+            top: grid.row(0).to_vec(),
+            right: grid.column(grid.width() - 1).cloned().collect(),
+            bottom: grid.row(grid.height() - 1).to_vec(),
+            left: grid.column(0).cloned().collect(),
+        }
+    }
+}
+
+/// The tiles of a puzzle, with every orientation of every tile indexed by
+/// its left and top edges so finding a neighbour is a single lookup.
+pub struct Jigsaw<T> {
+    /// Each tile in all eight orientations, so tile `i` is `8 * i..8 * i + 8`.
+    variants: Vec<Piece<T>>,
+    edges: Vec<Edges<T>>,
+    by_left: HashMap<Vec<T>, Vec<usize>>,
+    by_top: HashMap<Vec<T>, Vec<usize>>,
+}
+
+impl<T: Clone + Eq + Hash> Jigsaw<T> {
+    /// Takes each tile's id and cells. Tiles need not be square or all the
+    /// same size, as long as touching edges are the same length.
+    pub fn new(tiles: impl IntoIterator<Item = (usize, Grid<T>)>) -> Self {
+        let mut jigsaw = Self {
+            variants: Vec::new(),
+            edges: Vec::new(),
+            by_left: HashMap::new(),
+            by_top: HashMap::new(),
+        };
+        for (id, grid) in tiles {
+            for orientation in D4::ALL {
+                let grid = orientation.apply(&grid);
+                let edges = Edges::of(&grid);
+                let v = jigsaw.variants.len();
+                jigsaw
+                    .by_left
+                    .entry(edges.left.clone())
+                    .or_default()
+                    .push(v);
+                jigsaw.by_top.entry(edges.top.clone()).or_default().push(v);
+                jigsaw.edges.push(edges);
+                jigsaw.variants.push(Piece {
+                    id,
+                    orientation,
+                    grid,
+                });
+            }
+        }
+        jigsaw
+    }
+
+    /// How many tiles there are.
+    pub fn len(&self) -> usize {
+        self.variants.len() / 8
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.variants.is_empty()
+    }
+
+    /// Lays the tiles out in any rectangle they fill, trying the squarest
+    /// shapes first.
+    pub fn assemble(&self) -> Result<Assembly<T>, JigsawError> {
+        if self.is_empty() {
+            return Err(JigsawError::Empty);
+        }
+        let n = self.len();
+        let mut widths: Vec<usize> = (1..=n).filter(|&w| n.is_multiple_of(w)).collect();
+        widths.sort_by_key(|&w| w.abs_diff(n / w));
+        for width in widths {
+            match self.assemble_with_width(width) {
+                Err(JigsawError::NoFit) => continue,
+                assembled => return assembled,
+            }
+        }
+        Err(JigsawError::NoFit)
+    }
+
+    /// Lays the tiles out `width` tiles across, filling rows from the top
+    /// left and backing up whenever a spot has nothing left that fits.
+    pub fn assemble_with_width(&self, width: usize) -> Result<Assembly<T>, JigsawError> {
+        if self.is_empty() {
+            return Err(JigsawError::Empty);
+        }
+        if width == 0 || !self.len().is_multiple_of(width) {
+            return Err(JigsawError::NoFit);
+        }
+        let mut used = vec![false; self.len()];
+        let mut chosen = Vec::with_capacity(self.len());
+        if !self.fill(width, &mut used, &mut chosen) {
+            return Err(JigsawError::NoFit);
+        }
+        let pieces = chosen.into_iter().map(|v| self.variants[v].clone());
+        Ok(Assembly {
+            pieces: Grid::from_vec(width, self.len() / width, pieces.collect())
+                .expect("the width divides the number of tiles"),
+        })
+    }
+
+    fn fill(&self, width: usize, used: &mut [bool], chosen: &mut Vec<usize>) -> bool {
+        let at = chosen.len();
+        if at == used.len() {
+            return true;
+        }
+        let left = (!at.is_multiple_of(width)).then(|| &self.edges[chosen[at - 1]]);
+        let above = (at >= width).then(|| &self.edges[chosen[at - width]]);
+        let everything: Vec<usize>;
+        let candidates = match (left, above) {
+            (Some(left), _) => self.by_left.get(&left.right).map_or(&[][..], Vec::as_slice),
+            (None, Some(above)) => self
+                .by_top
+                .get(&above.bottom)
+                .map_or(&[][..], Vec::as_slice),
+            (None, None) => {
+                everything = (0..self.variants.len()).collect();
+                &everything
+            }
+        };
+        for &v in candidates {
+            if used[v / 8] || above.is_some_and(|above| above.bottom != self.edges[v].top) {
+                continue;
+            }
+            used[v / 8] = true;
+            chosen.push(v);
+            if self.fill(width, used, chosen) {
+                return true;
+            }
+            chosen.pop();
+            used[v / 8] = false;
+        }
+        false
+    }
+}
+
+/// The tiles laid out so that every pair of neighbours share an edge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assembly<T> {
+    pieces: Grid<Piece<T>>,
+}
+
+impl<T: Clone> Assembly<T> {
+    pub fn pieces(&self) -> &Grid<Piece<T>> {
+        &self.pieces
+    }
+
+    /// The ids of the corner tiles, clockwise from the top left.
+    pub fn corners(&self) -> [usize; 4] {
+        let (right, bottom) = (self.pieces.width() - 1, self.pieces.height() - 1);
+        [(0, 0), (right, 0), (right, bottom), (0, bottom)].map(|pos| self.pieces[pos].id)
+    }
+
+    /// The whole image, with `border` cells cut off every side of every tile
+    /// first.
+    pub fn render(&self, border: usize) -> Result<Grid<T>, JigsawError> {
+        if self
+            .pieces
+            .cells()
+            .iter()
+            .any(|piece| 2 * border >= piece.grid.width().min(piece.grid.height()))
+        {
+            return Err(JigsawError::Border { border });
+        }
+        let mut rows = Vec::new();
+        for pieces in self.pieces.rows() {
+            for y in border..pieces[0].grid.height() - border {
+                let mut row = Vec::new();
+                for piece in pieces {
+                    let cells = piece.grid.row(y);
+                    row.extend_from_slice(&cells[border..cells.len() - border]);
+                }
+                rows.push(row);
+            }
+        }
+        Ok(Grid::from_rows(rows).expect("tiles in a row or column line up"))
+    }
+}
+
+/// A shape to look for in a grid, drawn in ASCII where spaces are cells the
+/// shape doesn't cover.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    width: usize,
+    height: usize,
+    cells: Vec<(Pos, char)>,
+}
+
+/// Where a pattern was found, and which way round it was.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PatternMatch {
+    /// The top left corner of the pattern as laid down.
+    pub pos: Pos,
+    pub orientation: D4,
+}
+
+impl FromStr for Pattern {
+    type Err = JigsawError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cells = Vec::new();
+        for (y, line) in s.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                if c != ' ' {
+                    cells.push(((x, y), c));
+                }
+            }
+        }
+        if cells.is_empty() {
+            return Err(JigsawError::EmptyPattern);
+        }
+        Ok(Self {
+            width: cells.iter().map(|&((x, _), _)| x + 1).max().unwrap_or(0),
+            height: cells.iter().map(|&((_, y), _)| y + 1).max().unwrap_or(0),
+            cells,
+        })
+    }
+}
+
+impl Pattern {
+    fn laid(&self, orientation: D4) -> Vec<(Pos, char)> {
+        let mut cells: Vec<_> = self
+            .cells
+            .iter()
+            .map(|&(pos, c)| (orientation.apply_pos(pos, self.width, self.height), c))
+            .collect();
+        cells.sort_unstable();
+        cells
+    }
+
+    /// Every place the pattern turns up in `grid` in any orientation, where
+    /// `matches` says whether a cell fits a character of the pattern. A
+    /// pattern that looks the same after turning is only reported once.
+    pub fn find<T>(&self, grid: &Grid<T>, matches: impl Fn(char, &T) -> bool) -> Vec<PatternMatch> {
+        let mut tried: Vec<Vec<(Pos, char)>> = Vec::new();
+        let mut found = Vec::new();
+        for orientation in D4::ALL {
+            let cells = self.laid(orientation);
+            if tried.contains(&cells) {
+                continue;
+            }
+            let (width, height) = orientation.size(self.width, self.height);
+            for y in 0..(grid.height() + 1).saturating_sub(height) {
+                for x in 0..(grid.width() + 1).saturating_sub(width) {
+                    if cells
+                        .iter()
+                        .all(|&((dx, dy), c)| matches(c, &grid[(x + dx, y + dy)]))
+                    {
+                        found.push(PatternMatch {
+                            pos: (x, y),
+                            orientation,
+                        });
+                    }
+                }
+            }
+            tried.push(cells);
+        }
+        found
+    }
+
+    /// The cells of the grid a match covers.
+    pub fn covers(&self, found: PatternMatch) -> impl Iterator<Item = Pos> {
+        let (x, y) = found.pos;
+        self.laid(found.orientation)
+            .into_iter()
+            .map(move |((dx, dy), _)| (x + dx, y + dy))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn grid(map: &str) -> Grid<char> {
+        map.parse().unwrap()
+    }
+
+    /// Cuts `image` into tiles `size` cells square that share their edges,
+    /// turning each one a different way, with ids laid out row by row.
+    fn cut(image: &Grid<char>, size: usize) -> Vec<(usize, Grid<char>)> {
+        let across = (image.width() - 1) / (size - 1);
+        let down = (image.height() - 1) / (size - 1);
+        (0..across * down)
+            .map(|id| {
+                let (tx, ty) = (id % across * (size - 1), id / across * (size - 1));
+                let rows = (ty..ty + size).map(|y| (tx..tx + size).map(move |x| image[(x, y)]));
+                let tile = Grid::from_rows(rows).unwrap();
+                (id, D4::ALL[id * 3 % 8].apply(&tile))
+            })
+            .collect()
+    }
+
+    /// A scrambled looking image from a linear congruential generator.
+    fn noise(width: usize, height: usize, seed: u64) -> Grid<char> {
+        let mut state = seed;
+        let cells = (0..width * height)
+            .map(|_| {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+                if state >> 63 == 0 {
+                    '#'
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        Grid::from_vec(width, height, cells).unwrap()
+    }
+
+    #[test]
+    fn assembling() {
+        // Six tiles of 5x5, three across and two down.
+        let image = noise(13, 9, 7);
+        let jigsaw = Jigsaw::new(cut(&image, 5));
+        assert_eq!(jigsaw.len(), 6);
+        let assembly = jigsaw.assemble().unwrap();
+        let ids = Grid::from_vec(3, 2, (0..6).collect()).unwrap();
+        let laid = assembly.pieces().map(|piece| piece.id);
+        assert!(D4::iter().any(|d4| d4.apply(&ids) == laid));
+        let mut corners = assembly.corners();
+        corners.sort_unstable();
+        assert_eq!(corners, [0, 2, 3, 5]);
+        let rendered = assembly.render(0).unwrap();
+        assert_eq!(rendered.width() * rendered.height(), 6 * 25);
+        assert_eq!(assembly.render(3), Err(JigsawError::Border { border: 3 }));
+        assert_eq!(jigsaw.assemble_with_width(4), Err(JigsawError::NoFit));
+    }
+
+    #[test]
+    fn not_fitting() {
+        // No edge of one tile matches any edge of the other, whichever way
+        // round they are laid.
+        let tiles = [(1, grid("##\n##")), (2, grid("..\n.."))];
+        assert_eq!(Jigsaw::new(tiles).assemble(), Err(JigsawError::NoFit));
+        let none: Vec<(usize, Grid<char>)> = Vec::new();
+        assert_eq!(Jigsaw::new(none).assemble(), Err(JigsawError::Empty));
+    }
+
+    #[test]
+    fn backtracking() {
+        // Tiles of 3x3 have edges of only three cells, so plenty fit next to
+        // the wrong neighbour and a greedy layout gets stuck.
+        let image = noise(9, 9, 11);
+        let tiles = cut(&image, 3);
+        let assembly = Jigsaw::new(tiles.clone()).assemble().unwrap();
+        let pieces = assembly.pieces();
+        let mut ids: Vec<_> = pieces.cells().iter().map(|piece| piece.id).collect();
+        ids.sort_unstable();
+        assert_eq!(ids, (0..16).collect::<Vec<_>>());
+        for ((x, y), piece) in pieces.iter() {
+            let edges = Edges::of(&piece.grid);
+            if x > 0 {
+                assert_eq!(Edges::of(&pieces[(x - 1, y)].grid).right, edges.left);
+            }
+            if y > 0 {
+                assert_eq!(Edges::of(&pieces[(x, y - 1)].grid).bottom, edges.top);
+            }
+        }
+    }
+
+    #[test]
+    fn finding_patterns() {
+        let pattern: Pattern = "#.\n ##".parse().unwrap();
+        let image = grid("...#..\n..#...\n..#...\n#.....\n.##...");
+        let found = pattern.find(&image, |p, &c| p == c);
+        assert_eq!(
+            found,
+            vec![
+                PatternMatch {
+                    pos: (0, 3),
+                    orientation: D4::IDENTITY
+                },
+                PatternMatch {
+                    pos: (2, 0),
+                    orientation: D4::rotation(1)
+                }
+            ]
+        );
+        let mut covered: Vec<_> = pattern.covers(found[1]).collect();
+        covered.sort_unstable();
+        assert_eq!(covered, vec![(2, 1), (2, 2), (3, 0), (3, 1)]);
+        assert_eq!("  \n".parse::<Pattern>(), Err(JigsawError::EmptyPattern));
+    }
+}
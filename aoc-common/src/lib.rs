@@ -0,0 +1,20 @@
+//! Data structures and algorithms that keep turning up across the years.
+
+pub mod assignment;
+pub mod automaton;
+pub mod d4;
+pub mod expr;
+pub mod grammar;
+pub mod graph;
+pub mod grid;
+pub mod hex;
+pub mod interval;
+pub mod jigsaw;
+pub mod math;
+pub mod vm;
+
+pub use d4::{Transform, D4};
+pub use grammar::{Grammar, GrammarError};
+pub use graph::{Graph, GraphError, NodeId};
+pub use grid::{Grid, GridError, Pos, Wrap, ALL_DIRECTIONS, ORTHOGONAL};
+pub use interval::{IntervalSet, PiecewiseMap};
@@ -0,0 +1,245 @@
+//! Number theory over the primitive integers up to 64 bits.
+//!
+//! Working is done in 128 bits, so nothing here overflows part way through.
+//! Results that do not fit back in the caller's type come back as `None`.
+
+use std::{collections::HashMap, fmt::Debug};
+
+/// A primitive integer that fits in an `i128` with room to multiply.
+pub trait Int: Copy + Ord + Debug {
+    fn to_i128(self) -> i128;
+    fn from_i128(value: i128) -> Option<Self>;
+}
+
+macro_rules! int {
+    ($($t:ty),*) => {
+        $(
+            impl Int for $t {
+                fn to_i128(self) -> i128 {
+                    self as i128
+                }
+
+                fn from_i128(value: i128) -> Option<Self> {
+                    Self::try_from(value).ok()
+                }
+            }
+        )*
+    };
+}
+
+int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// Converts a result that is known to fit, like anything reduced by a
+/// modulus of the same type.
+fn back<T: Int>(value: i128) -> T {
+    T::from_i128(value).expect("a reduced value fits its modulus' type")
+}
+
+fn modulus<T: Int>(m: T) -> i128 {
+    let m = m.to_i128();
+    assert!(m > 0, "modulus must be positive, got {m}");
+    m
+}
+
+/// `value` reduced into `0..m`.
+fn reduce(value: i128, m: i128) -> i128 {
+    value.rem_euclid(m)
+}
+
+/// `a * b mod m` for `a` and `b` already reduced by `m`.
+fn mul(a: i128, b: i128, m: i128) -> i128 {
+    ((a as u128 * b as u128) % m as u128) as i128
+}
+
+fn gcd_wide(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.abs()
+}
+
+fn egcd_wide(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut r0, mut r1) = (a, b);
+    let (mut s0, mut s1) = (1, 0);
+    let (mut t0, mut t1) = (0, 1);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (s0, s1) = (s1, s0 - q * s1);
+        (t0, t1) = (t1, t0 - q * t1);
+    }
+    if r0 < 0 {
+        (-r0, -s0, -t0)
+    } else {
+        (r0, s0, t0)
+    }
+}
+
+fn inv_wide(a: i128, m: i128) -> Option<i128> {
+    let (g, x, _) = egcd_wide(reduce(a, m), m);
+    (g == 1).then(|| reduce(x, m))
+}
+
+fn pow_wide(base: i128, mut exp: u64, m: i128) -> i128 {
+    let mut base = reduce(base, m);
+    let mut result = reduce(1, m);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul(result, base, m);
+        }
+        base = mul(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+pub fn gcd<T: Int>(a: T, b: T) -> T {
+    back(gcd_wide(a.to_i128(), b.to_i128()))
+}
+
+/// The least common multiple, or `None` if it does not fit.
+pub fn lcm<T: Int>(a: T, b: T) -> Option<T> {
+    let (a, b) = (a.to_i128().abs(), b.to_i128().abs());
+    if a == 0 || b == 0 {
+        return T::from_i128(0);
+    }
+    (a / gcd_wide(a, b)).checked_mul(b).and_then(T::from_i128)
+}
+
+/// The least common multiple of every value, or `None` if there are none or
+/// it does not fit.
+pub fn lcm_all<T: Int>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    let mut values = values.into_iter();
+    let first = values.next()?;
+    values.try_fold(first, lcm)
+}
+
+/// The extended Euclidean algorithm, giving `gcd(a, b)` along with `x` and `y`
+/// where `a * x + b * y = gcd(a, b)`.
+///
+/// <https://brilliant.org/wiki/extended-euclidean-algorithm/>
+pub fn egcd<T: Int>(a: T, b: T) -> (T, i128, i128) {
+    let (g, x, y) = egcd_wide(a.to_i128(), b.to_i128());
+    (back(g), x, y)
+}
+
+/// The `x` in `0..m` where `a * x ≡ 1 (mod m)`, if `a` and `m` are coprime.
+pub fn mod_inv<T: Int>(a: T, m: T) -> Option<T> {
+    inv_wide(a.to_i128(), modulus(m)).map(back)
+}
+
+/// `base` to the power of `exp`, mod `m`, by repeated squaring.
+pub fn mod_pow<T: Int>(base: T, exp: u64, m: T) -> T {
+    back(pow_wide(base.to_i128(), exp, modulus(m)))
+}
+
+/// Solves a system of congruences `x ≡ residue (mod modulus)`, giving the
+/// smallest non-negative `x` along with the modulus it repeats at.
+///
+/// The moduli do not have to be coprime. `None` means the congruences
+/// contradict each other or the combined modulus does not fit in `T`.
+pub fn crt<T: Int>(congruences: impl IntoIterator<Item = (T, T)>) -> Option<(T, T)> {
+    let (mut x, mut l) = (0, 1);
+    for (residue, m) in congruences {
+        let m = modulus(m);
+        // x + l * k ≡ residue (mod m) only has a solution when gcd(l, m)
+        // divides the difference.
+        let g = gcd_wide(l, m);
+        let diff = reduce(residue.to_i128(), m) - x;
+        if diff % g != 0 {
+            return None;
+        }
+        let step = m / g;
+        let inv = inv_wide(l / g, step)?;
+        let k = mul(reduce(diff / g, step), inv, step);
+        let next = (l / g).checked_mul(m)?;
+        T::from_i128(next)?;
+        x = reduce(x + l * k, next);
+        l = next;
+    }
+    Some((back(x), back(l)))
+}
+
+/// The smallest `x` where `base^x ≡ target (mod m)`, found with the
+/// baby-step giant-step algorithm in about `sqrt(m)` steps. `base` must be
+/// coprime to `m`.
+pub fn discrete_log<T: Int>(base: T, target: T, m: T) -> Option<T> {
+    let m = modulus(m);
+    let (base, target) = (reduce(base.to_i128(), m), reduce(target.to_i128(), m));
+    let n = (m as f64).sqrt().ceil() as i128;
+    let mut baby = HashMap::with_capacity(n as usize);
+    let mut value = reduce(1, m);
+    for j in 0..n {
+        baby.entry(value).or_insert(j);
+        value = mul(value, base, m);
+    }
+    // Each giant step divides by base^n.
+    let giant = pow_wide(inv_wide(base, m)?, n as u64, m);
+    let mut gamma = target;
+    for i in 0..n {
+        if let Some(j) = baby.get(&gamma) {
+            return T::from_i128(i * n + j);
+        }
+        gamma = mul(gamma, giant, m);
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn gcds() {
+        assert_eq!(gcd(1914u32, 899), 29);
+        assert_eq!(gcd(-12i32, 18), 6);
+        assert_eq!(lcm(4u8, 6), Some(12));
+        assert_eq!(lcm(200u8, 3), None);
+        assert_eq!(lcm(u64::MAX, u64::MAX - 1), None);
+        assert_eq!(lcm(u64::MAX, u64::MAX), Some(u64::MAX));
+        assert_eq!(lcm_all([2u64, 3, 4, 5]), Some(60));
+        assert_eq!(lcm_all(Vec::<u64>::new()), None);
+    }
+
+    #[test]
+    fn extended_gcd() {
+        assert_eq!(egcd(1914, 899), (29, 8, -17));
+        assert_eq!(egcd(899, 1914), (29, -17, 8));
+        assert_eq!(egcd(102, 38), (2, 3, -8));
+        assert_eq!(egcd(352, 168), (8, -10, 21));
+        assert_eq!(egcd(3458, 4864), (38, -45, 32));
+        assert_eq!(egcd(1432, 123211), (1, -22973, 267));
+    }
+
+    #[test]
+    fn inverses_and_powers() {
+        assert_eq!(mod_inv(3u32, 11), Some(4));
+        assert_eq!(mod_inv(-3i32, 11), Some(7));
+        assert_eq!(mod_inv(6u32, 9), None);
+        assert_eq!(mod_pow(7u64, 8, 20201227), 5764801);
+        assert_eq!(mod_pow(2u64, 0, 1), 0);
+        // Would overflow a u64 if multiplied directly.
+        let big = u64::MAX - 58;
+        assert_eq!(mod_pow(big - 1, 2, big), 1);
+    }
+
+    #[test]
+    fn chinese_remainders() {
+        assert_eq!(crt([(3u32, 5), (1, 7), (6, 8)]), Some((78, 280)));
+        assert_eq!(crt([(2u32, 3), (2, 4), (1, 5)]), Some((26, 60)));
+        // Moduli sharing a factor.
+        assert_eq!(crt([(2u32, 6), (8, 10)]), Some((8, 30)));
+        assert_eq!(crt([(1u32, 6), (2, 10)]), None);
+        assert_eq!(crt([(-1i64, 7)]), Some((6, 7)));
+        assert_eq!(crt([(0u8, 251), (1, 241)]), None, "60491 does not fit");
+        assert_eq!(crt([(0u64, u64::MAX), (1, u64::MAX - 1)]), None);
+    }
+
+    #[test]
+    fn discrete_logs() {
+        assert_eq!(discrete_log(7u64, 5764801, 20201227), Some(8));
+        assert_eq!(discrete_log(7u64, 17807724, 20201227), Some(11));
+        assert_eq!(discrete_log(3u32, 1, 7), Some(0));
+        assert_eq!(discrete_log(2u32, 3, 7), None);
+    }
+}
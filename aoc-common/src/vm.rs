@@ -0,0 +1,392 @@
+//! A small interpreter for programs that are a list of instructions, like the
+//! handheld console's boot code from 2020 day 8.
+
+use std::{
+    collections::HashSet,
+    fmt::{self, Display},
+    hash::Hash,
+    str::FromStr,
+};
+use thiserror::Error;
+
+/// An instruction a [`Machine`] can run.
+pub trait Instruction: Clone {
+    /// Whatever the machine keeps besides the instruction pointer.
+    type State: Clone + Default + Eq + Hash;
+
+    /// Whether where an instruction jumps never depends on the state. A
+    /// machine can then call it a loop as soon as an instruction comes round
+    /// again, rather than waiting for the same instruction in the same state.
+    const JUMPS_IGNORE_STATE: bool = false;
+
+    /// Carries out the instruction, giving how far to move the instruction
+    /// pointer afterwards.
+    fn execute(&self, state: &mut Self::State) -> isize;
+}
+
+/// The handheld console's instructions. The state is the accumulator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Handheld {
+    Acc(i64),
+    Jmp(isize),
+    Nop(isize),
+}
+
+impl Instruction for Handheld {
+    type State = i64;
+    const JUMPS_IGNORE_STATE: bool = true;
+
+    fn execute(&self, acc: &mut i64) -> isize {
+        match *self {
+            Handheld::Acc(n) => {
+                *acc += n;
+                1
+            }
+            Handheld::Jmp(offset) => offset,
+            Handheld::Nop(_) => 1,
+        }
+    }
+}
+
+impl FromStr for Handheld {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (op, arg) = s
+            .split_once(' ')
+            .ok_or_else(|| format!("`{s}` has no argument"))?;
+        let bad = |e| format!("`{arg}`: {e}");
+        Ok(match op {
+            "acc" => Handheld::Acc(arg.parse().map_err(bad)?),
+            "jmp" => Handheld::Jmp(arg.parse().map_err(bad)?),
+            "nop" => Handheld::Nop(arg.parse().map_err(bad)?),
+            _ => return Err(format!("unknown operation `{op}`")),
+        })
+    }
+}
+
+impl Display for Handheld {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Handheld::Acc(n) => write!(f, "acc {n:+}"),
+            Handheld::Jmp(n) => write!(f, "jmp {n:+}"),
+            Handheld::Nop(n) => write!(f, "nop {n:+}"),
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("line {line}: {reason}")]
+pub struct AsmError {
+    pub line: usize,
+    pub reason: String,
+}
+
+/// Reads a program with one instruction per line.
+pub fn assemble<I>(source: &str) -> Result<Vec<I>, AsmError>
+where
+    I: FromStr,
+    I::Err: Display,
+{
+    source
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            line.trim().parse().map_err(|e: I::Err| AsmError {
+                line: i + 1,
+                reason: e.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Writes a program back out with one instruction per line.
+pub fn disassemble<I: Display>(program: &[I]) -> String {
+    program
+        .iter()
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Why a machine stopped running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    /// It moved to just past the last instruction.
+    Halted,
+    /// It jumped somewhere other than an instruction or just past the end.
+    OutOfBounds {
+        pc: isize,
+    },
+    /// It was about to run an instruction it has already run in the same
+    /// state, or at all when [`Instruction::JUMPS_IGNORE_STATE`] is set, so it
+    /// would go round forever. Instructions run with [`Machine::step`] count
+    /// too.
+    Loop {
+        pc: usize,
+    },
+    Breakpoint {
+        pc: usize,
+    },
+}
+
+/// What ran at one step of a program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEntry<S> {
+    pub pc: usize,
+    /// The state before the instruction ran.
+    pub state: S,
+}
+
+/// Runs a program, one instruction at a time or until it stops.
+#[derive(Debug, Clone)]
+pub struct Machine<I: Instruction> {
+    program: Vec<I>,
+    pc: isize,
+    state: I::State,
+    steps: usize,
+    breakpoints: Vec<bool>,
+    visited: Vec<bool>,
+    seen: HashSet<(usize, I::State)>,
+    trace: Option<Vec<TraceEntry<I::State>>>,
+}
+
+impl<I: Instruction> Machine<I> {
+    pub fn new(program: Vec<I>) -> Self {
+        Self {
+            breakpoints: vec![false; program.len()],
+            visited: vec![false; program.len()],
+            seen: HashSet::new(),
+            program,
+            pc: 0,
+            state: I::State::default(),
+            steps: 0,
+            trace: None,
+        }
+    }
+
+    pub fn program(&self) -> &[I] {
+        &self.program
+    }
+
+    pub fn pc(&self) -> isize {
+        self.pc
+    }
+
+    pub fn state(&self) -> &I::State {
+        &self.state
+    }
+
+    pub fn state_mut(&mut self) -> &mut I::State {
+        &mut self.state
+    }
+
+    /// How many instructions have run.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Stops [`Machine::run`] before it runs the instruction at `pc`.
+    pub fn set_breakpoint(&mut self, pc: usize, on: bool) {
+        self.breakpoints[pc] = on;
+    }
+
+    /// Keeps a record of every instruction run from now on.
+    pub fn record_trace(&mut self) {
+        self.trace.get_or_insert_with(Vec::new);
+    }
+
+    /// Everything run since [`Machine::record_trace`] was called.
+    pub fn trace(&self) -> Option<&[TraceEntry<I::State>]> {
+        self.trace.as_deref()
+    }
+
+    /// Where the next instruction is, or why there isn't one.
+    fn next_pc(&self) -> Result<usize, Stop> {
+        match usize::try_from(self.pc) {
+            Ok(pc) if pc < self.program.len() => Ok(pc),
+            Ok(pc) if pc == self.program.len() => Err(Stop::Halted),
+            _ => Err(Stop::OutOfBounds { pc: self.pc }),
+        }
+    }
+
+    /// Whether running the instruction at `pc` now repeats an earlier step.
+    fn has_run(&self, pc: usize) -> bool {
+        if I::JUMPS_IGNORE_STATE {
+            self.visited[pc]
+        } else {
+            self.seen.contains(&(pc, self.state.clone()))
+        }
+    }
+
+    /// Runs one instruction.
+    pub fn step(&mut self) -> Result<(), Stop> {
+        let pc = self.next_pc()?;
+        if let Some(trace) = &mut self.trace {
+            trace.push(TraceEntry {
+                pc,
+                state: self.state.clone(),
+            });
+        }
+        if I::JUMPS_IGNORE_STATE {
+            self.visited[pc] = true;
+        } else {
+            self.seen.insert((pc, self.state.clone()));
+        }
+        self.pc += self.program[pc].execute(&mut self.state);
+        self.steps += 1;
+        Ok(())
+    }
+
+    /// Runs until the program stops.
+    pub fn run(&mut self) -> Stop {
+        self.run_with(|_, _, _| {})
+    }
+
+    /// Runs until the program stops, calling `hook` with each instruction
+    /// and the state before it runs. A breakpoint where the machine starts
+    /// does not stop it, so calling this again carries on past it.
+    pub fn run_with(&mut self, mut hook: impl FnMut(usize, &I, &I::State)) -> Stop {
+        let mut first = true;
+        loop {
+            let pc = match self.next_pc() {
+                Ok(pc) => pc,
+                Err(stop) => return stop,
+            };
+            if self.breakpoints[pc] && !first {
+                return Stop::Breakpoint { pc };
+            }
+            if self.has_run(pc) {
+                return Stop::Loop { pc };
+            }
+            first = false;
+            hook(pc, &self.program[pc], &self.state);
+            // Can't fail, the instruction pointer was just checked.
+            let _ = self.step();
+        }
+    }
+}
+
+/// Finds a change to a single instruction that makes the program halt.
+/// `patches` gives what each instruction could be swapped for. Returns where
+/// the change is, what it is, and the machine after it halts.
+pub fn repair<I: Instruction>(
+    program: &[I],
+    patches: impl Fn(&I) -> Vec<I>,
+) -> Option<(usize, I, Machine<I>)> {
+    for (at, instruction) in program.iter().enumerate() {
+        for patch in patches(instruction) {
+            let mut patched = program.to_vec();
+            patched[at] = patch.clone();
+            let mut machine = Machine::new(patched);
+            if machine.run() == Stop::Halted {
+                return Some((at, patch, machine));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    static BOOT: &str = "nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6";
+
+    #[test]
+    fn assembling() {
+        let program: Vec<Handheld> = assemble(BOOT).unwrap();
+        assert_eq!(program[2], Handheld::Jmp(4));
+        assert_eq!(disassemble(&program), BOOT);
+        assert_eq!(
+            assemble::<Handheld>("acc +1\nmul 2"),
+            Err(AsmError {
+                line: 2,
+                reason: "unknown operation `mul`".into()
+            })
+        );
+    }
+
+    #[test]
+    fn running() {
+        let mut machine = Machine::new(assemble::<Handheld>(BOOT).unwrap());
+        machine.set_breakpoint(3, true);
+        assert_eq!(machine.run(), Stop::Breakpoint { pc: 3 });
+        assert_eq!(*machine.state(), 2);
+        machine.record_trace();
+        let mut ran = Vec::new();
+        assert_eq!(
+            machine.run_with(|pc, _, _| ran.push(pc)),
+            Stop::Loop { pc: 1 }
+        );
+        assert_eq!(ran, vec![3, 4]);
+        assert_eq!(*machine.state(), 5);
+        assert_eq!(
+            machine.trace().unwrap(),
+            [
+                TraceEntry { pc: 3, state: 2 },
+                TraceEntry { pc: 4, state: 5 }
+            ]
+        );
+        assert_eq!(machine.steps(), 7);
+        assert_eq!(machine.step(), Ok(()));
+        assert_eq!(machine.pc(), 2);
+    }
+
+    /// Counts the state down to zero, jumping only while it is not.
+    #[derive(Debug, Clone)]
+    enum Countdown {
+        Dec,
+        Jnz(isize),
+    }
+
+    impl Instruction for Countdown {
+        type State = i64;
+
+        fn execute(&self, n: &mut i64) -> isize {
+            match *self {
+                Countdown::Dec => {
+                    *n -= 1;
+                    1
+                }
+                Countdown::Jnz(offset) if *n != 0 => offset,
+                Countdown::Jnz(_) => 1,
+            }
+        }
+    }
+
+    #[test]
+    fn state_dependent_jumps() {
+        let mut machine = Machine::new(vec![Countdown::Dec, Countdown::Jnz(-1)]);
+        *machine.state_mut() = 3;
+        assert_eq!(machine.run(), Stop::Halted);
+        assert_eq!((*machine.state(), machine.steps()), (0, 6));
+        let mut stuck = Machine::new(vec![Countdown::Jnz(0)]);
+        *stuck.state_mut() = 1;
+        assert_eq!(stuck.run(), Stop::Loop { pc: 0 });
+        assert_eq!(stuck.steps(), 1);
+    }
+
+    #[test]
+    fn repairing() {
+        let program = assemble::<Handheld>(BOOT).unwrap();
+        let (at, patch, machine) = repair(&program, |i| match *i {
+            Handheld::Jmp(n) => vec![Handheld::Nop(n)],
+            Handheld::Nop(n) => vec![Handheld::Jmp(n)],
+            Handheld::Acc(_) => vec![],
+        })
+        .unwrap();
+        assert_eq!((at, patch), (7, Handheld::Nop(-4)));
+        assert_eq!(*machine.state(), 8);
+        let mut stuck = Machine::new(vec![Handheld::Jmp(-2)]);
+        assert_eq!(stuck.run(), Stop::OutOfBounds { pc: -2 });
+    }
+}
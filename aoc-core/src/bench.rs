@@ -0,0 +1,161 @@
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// How long to warm up and how many samples to take when timing a step.
+#[derive(Debug, Clone, Copy)]
+pub struct Sampler {
+    /// Keep running untimed until this much time has passed, always running
+    /// at least once so caches and allocators are warm.
+    pub warmup: Duration,
+    pub samples: usize,
+}
+
+impl Default for Sampler {
+    fn default() -> Self {
+        Self {
+            warmup: Duration::from_millis(100),
+            samples: 20,
+        }
+    }
+}
+
+impl Sampler {
+    /// Times `step` until enough samples are collected. `step` returns how
+    /// long the interesting part of it took so setup and drops can be left
+    /// out of the measurement.
+    pub fn measure(&self, mut step: impl FnMut() -> Result<Duration>) -> Result<Stats> {
+        let start = Instant::now();
+        step()?;
+        while start.elapsed() < self.warmup {
+            step()?;
+        }
+        let samples = (0..self.samples.max(1))
+            .map(|_| step())
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Stats::from_samples(samples))
+    }
+
+    /// Times a single call of `f`, hiding its result from the optimiser.
+    pub fn time<T>(f: impl FnOnce() -> T) -> (Duration, T) {
+        let start = Instant::now();
+        let out = std::hint::black_box(f());
+        (start.elapsed(), out)
+    }
+}
+
+/// Summary of a set of timing samples, in nanoseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    /// Samples kept after outliers were rejected.
+    pub samples: usize,
+    pub outliers: usize,
+    pub min: u64,
+    pub median: u64,
+    pub p95: u64,
+    pub mean: u64,
+    pub max: u64,
+}
+
+impl Stats {
+    /// Summarises `samples` after dropping anything outside Tukey's fences,
+    /// 1.5 times the interquartile range beyond the first and third quartile.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `samples` is empty.
+    pub fn from_samples(samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "no samples to summarise");
+        let mut all: Vec<u64> = samples
+            .into_iter()
+            .map(|d| d.as_nanos().try_into().unwrap_or(u64::MAX))
+            .collect();
+        all.sort_unstable();
+        let q1 = percentile(&all, 0.25);
+        let q3 = percentile(&all, 0.75);
+        let fence = (q3 - q1) * 3 / 2;
+        let low = q1.saturating_sub(fence);
+        let high = q3.saturating_add(fence);
+        let kept: Vec<u64> = all
+            .iter()
+            .copied()
+            .filter(|&s| (low..=high).contains(&s))
+            .collect();
+        let mid = kept.len() / 2;
+        let median = if kept.len().is_multiple_of(2) {
+            (kept[mid - 1] + kept[mid]) / 2
+        } else {
+            kept[mid]
+        };
+        Self {
+            samples: kept.len(),
+            outliers: all.len() - kept.len(),
+            min: kept[0],
+            median,
+            p95: percentile(&kept, 0.95),
+            mean: (kept.iter().map(|&s| s as u128).sum::<u128>() / kept.len() as u128) as u64,
+            max: kept[kept.len() - 1],
+        }
+    }
+}
+
+/// Nearest rank percentile of already sorted samples.
+fn percentile(sorted: &[u64], p: f64) -> u64 {
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn nanos(samples: &[u64]) -> Vec<Duration> {
+        samples.iter().map(|&n| Duration::from_nanos(n)).collect()
+    }
+
+    #[test]
+    fn summarising_samples() {
+        let stats = Stats::from_samples(nanos(&[10, 12, 11, 13, 10, 12, 11, 500]));
+        assert_eq!(
+            stats,
+            Stats {
+                samples: 7,
+                outliers: 1,
+                min: 10,
+                median: 11,
+                p95: 13,
+                mean: 11,
+                max: 13,
+            }
+        );
+    }
+
+    #[test]
+    fn single_sample() {
+        let stats = Stats::from_samples(nanos(&[42]));
+        assert_eq!(stats.samples, 1);
+        assert_eq!(stats.outliers, 0);
+        assert_eq!(
+            (stats.min, stats.median, stats.p95, stats.max),
+            (42, 42, 42, 42)
+        );
+    }
+
+    #[test]
+    fn measuring_runs_warmup_then_samples() {
+        let sampler = Sampler {
+            warmup: Duration::ZERO,
+            samples: 5,
+        };
+        let mut calls = 0;
+        let stats = sampler
+            .measure(|| {
+                calls += 1;
+                Ok(Duration::from_nanos(calls))
+            })
+            .unwrap();
+        assert_eq!(calls, 6);
+        assert_eq!(stats.samples, 5);
+        assert_eq!(stats.min, 2);
+    }
+}
@@ -0,0 +1,42 @@
+use crate::Part;
+use std::{fmt::Display, path::PathBuf};
+use thiserror::Error;
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The puzzle input is not in the shape the parser expects.
+    #[error("could not parse input: {0}")]
+    Parse(String),
+    /// The solver failed part way through.
+    #[error("could not solve: {0}")]
+    Solve(String),
+    /// The solver finished without finding an answer.
+    #[error("no answer found")]
+    NoAnswer,
+    /// The day does not have a solution for this part yet.
+    #[error("part {0} has not been solved")]
+    Unsolved(Part),
+    /// No parser or solver is registered under the name.
+    #[error("no variant named `{0}`")]
+    UnknownVariant(String),
+    /// Nothing is registered for the year and day.
+    #[error("{year} day {day} has not been solved")]
+    UnknownDay { year: u16, day: u8 },
+    /// The puzzle input file for the day does not exist.
+    #[error("no input for {year} day {day} at {}", path.display())]
+    MissingInput { year: u16, day: u8, path: PathBuf },
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+impl Error {
+    pub fn parse(err: impl Display) -> Self {
+        Error::Parse(err.to_string())
+    }
+
+    pub fn solve(err: impl Display) -> Self {
+        Error::Solve(err.to_string())
+    }
+}
@@ -0,0 +1,537 @@
+use crate::{Error, Part, Result, Solution};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// An example input from the puzzle description and the answer it gives.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Example {
+    pub input: String,
+    pub answer: Option<String>,
+}
+
+/// The examples for both parts of a day, stored as `YEAR/dayNN.toml` in a
+/// fixtures directory.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fixture {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part_one: Option<Example>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part_two: Option<Example>,
+}
+
+impl Fixture {
+    /// Pulls the examples out of a puzzle description, either the markdown
+    /// aoc-cli downloads or the raw HTML.
+    ///
+    /// A code block is an example input when the sentence before it offers
+    /// one, or when it repeats an earlier example word for word. A block whose
+    /// sentence points back at an example only counts if it repeats an
+    /// earlier block, and one that redraws most of an earlier block is
+    /// working and is skipped. Each example's answer
+    /// is the emphasised code, the way the puzzles highlight a result, in the
+    /// prose up to the next example: the paragraph quoting a one line input,
+    /// else the last one that talks about the example or its answer, else the
+    /// last one with any. The last example in a part that has an answer is
+    /// kept, and part two reuses part one's when it gives none of its own.
+    /// This is a best guess, so check what it finds.
+    pub fn extract(description: &str) -> Self {
+        let html = description.contains("<code>");
+        let (one, two) = split_parts(description);
+        let mut seen = Vec::new();
+        let part_one = example(one, html, None, &mut seen);
+        let part_two = two.and_then(|two| example(two, html, part_one.as_ref(), &mut seen));
+        Self { part_one, part_two }
+    }
+
+    pub fn path(dir: &Path, year: u16, day: u8) -> PathBuf {
+        dir.join(year.to_string()).join(format!("day{day:02}.toml"))
+    }
+
+    pub fn load(dir: &Path, year: u16, day: u8) -> Result<Self> {
+        let path = Self::path(dir, year, day);
+        let fixture = fs::read_to_string(&path)?;
+        toml::from_str(&fixture).map_err(|err| Error::parse(format!("{}: {err}", path.display())))
+    }
+
+    pub fn save(&self, dir: &Path, year: u16, day: u8) -> Result<()> {
+        let path = Self::path(dir, year, day);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let fixture = toml::to_string(self).map_err(Error::parse)?;
+        Ok(fs::write(path, fixture)?)
+    }
+
+    pub fn example(&self, part: Part) -> Option<&Example> {
+        match part {
+            Part::One => self.part_one.as_ref(),
+            Part::Two => self.part_two.as_ref(),
+        }
+    }
+}
+
+/// Runs a part of `S` against its example in `dir` and panics unless it
+/// gives the recorded answer. Used by [`derive_tests`](crate::derive_tests).
+pub fn check_example<S: Solution>(dir: &Path, part: Part) {
+    let fixture = Fixture::load(dir, S::YEAR, S::DAY)
+        .unwrap_or_else(|err| panic!("no fixture for {} day {}: {err}", S::YEAR, S::DAY));
+    let example = fixture
+        .example(part)
+        .unwrap_or_else(|| panic!("no example for part {part}"));
+    let expected = example
+        .answer
+        .as_deref()
+        .unwrap_or_else(|| panic!("no answer for the part {part} example"));
+    let input = S::parse(&example.input).unwrap();
+    let answer = match part {
+        Part::One => S::part_one(&input),
+        Part::Two => S::part_two(&input),
+    };
+    assert_eq!(answer.unwrap().to_string(), expected);
+}
+
+/// Generates a `part_one` and `part_two` test that checks a [`Solution`]
+/// against its examples in the workspace's `fixtures` directory.
+#[macro_export]
+macro_rules! derive_tests {
+    ($sol:ty) => {
+        #[test]
+        fn part_one() {
+            $crate::check_example::<$sol>(
+                ::std::path::Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/../fixtures")),
+                $crate::Part::One,
+            );
+        }
+
+        #[test]
+        fn part_two() {
+            $crate::check_example::<$sol>(
+                ::std::path::Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/../fixtures")),
+                $crate::Part::Two,
+            );
+        }
+    };
+}
+
+const PART_TWO: &str = "--- Part Two ---";
+
+fn split_parts(description: &str) -> (&str, Option<&str>) {
+    match description.find(PART_TWO) {
+        Some(at) => (&description[..at], Some(&description[at..])),
+        None => (description, None),
+    }
+}
+
+/// Finds the example in one part of a description. `seen` holds every block
+/// of the parts before it, with whether it was an example, and gains this
+/// part's.
+fn example(
+    section: &str,
+    html: bool,
+    fallback: Option<&Example>,
+    seen: &mut Vec<(String, bool)>,
+) -> Option<Example> {
+    let blocks = code_blocks(section, html);
+    let mut examples = Vec::new();
+    for block in &blocks {
+        let is_example = is_example(block, seen);
+        seen.push((block.text.clone(), is_example));
+        if is_example {
+            examples.push(block);
+        }
+    }
+    // Each example's answer is somewhere between its introduction and the
+    // next example's.
+    for (n, block) in examples.iter().enumerate().rev() {
+        let end = examples
+            .get(n + 1)
+            .map_or(section.len(), |next| next.intro_at);
+        if let Some(answer) = answer(&section[block.intro_at..end], &block.text, html) {
+            return Some(Example {
+                input: block.text.clone(),
+                answer: Some(answer),
+            });
+        }
+    }
+    let input = examples
+        .last()
+        .map(|b| b.text.clone())
+        .or_else(|| fallback.map(|e| e.input.clone()))
+        .or_else(|| blocks.first().map(|b| b.text.clone()))?;
+    // Answers that are pictures rather than numbers are drawn in the last
+    // block.
+    let answer = answer(section, &input, html).or_else(|| {
+        blocks
+            .last()
+            .filter(|b| b.text != input)
+            .map(|b| b.text.clone())
+    });
+    Some(Example { input, answer })
+}
+
+/// Words that, just before "example", point back at one already given.
+const BACK_REFERENCES: [&str; 2] = ["the", "this"];
+
+fn is_example(block: &Block, seen: &[(String, bool)]) -> bool {
+    if seen
+        .iter()
+        .any(|(text, example)| *example && *text == block.text)
+    {
+        return true;
+    }
+    let intro = last_sentence(&block.intro).to_lowercase();
+    let Some(at) = intro.find("example") else {
+        return false;
+    };
+    let refers_back = intro[..at]
+        .split_whitespace()
+        .rev()
+        .take(2)
+        .any(|word| BACK_REFERENCES.contains(&word));
+    if refers_back {
+        return seen.iter().any(|(text, _)| *text == block.text);
+    }
+    let lines: Vec<&str> = block
+        .text
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .collect();
+    !seen.iter().any(|(text, _)| {
+        let repeated = lines.iter().filter(|l| text.lines().any(|m| m == **l));
+        2 * repeated.count() > lines.len()
+    })
+}
+
+fn last_sentence(text: &str) -> &str {
+    [". ", "? ", "! "]
+        .iter()
+        .filter_map(|end| text.rfind(end).map(|at| at + end.len()))
+        .max()
+        .map_or(text, |at| &text[at..])
+}
+
+/// Words that mark the paragraph giving the example's result.
+const ANSWER_HINTS: [&str; 3] = ["example", "in this case", "answer"];
+
+fn answer(section: &str, input: &str, html: bool) -> Option<String> {
+    let paragraphs = paragraphs(section, html);
+    let mut answers = paragraphs.iter().filter(|p| !p.emphasised.is_empty());
+    let quoted = match input.trim_end().lines().count() {
+        1 => answers.clone().find(|p| p.text.contains(input.trim_end())),
+        _ => None,
+    };
+    let hinted = || {
+        answers.clone().rev().find(|p| {
+            let text = p.text.to_lowercase();
+            ANSWER_HINTS.iter().any(|hint| text.contains(hint))
+        })
+    };
+    quoted
+        .or_else(hinted)
+        .or_else(|| answers.next_back())
+        .and_then(|p| p.emphasised.last().cloned())
+}
+
+struct Paragraph {
+    text: String,
+    /// Code that is also emphasised, written as `` `*code*` `` or
+    /// `` *`code`* `` in markdown and `<code><em>code</em></code>` or
+    /// `<em><code>code</code></em>` in HTML.
+    emphasised: Vec<String>,
+}
+
+/// The prose of a section, a paragraph or list item at a time, leaving out
+/// the code blocks.
+fn paragraphs(section: &str, html: bool) -> Vec<Paragraph> {
+    if html {
+        let mut prose = String::new();
+        let mut rest = section;
+        while let Some(start) = rest.find("<pre>") {
+            prose.push_str(&rest[..start]);
+            rest = rest[start..]
+                .find("</pre>")
+                .map_or("", |end| &rest[start + end..]);
+        }
+        prose.push_str(rest);
+        return prose
+            .split("</p>")
+            .flat_map(|p| p.split("</li>"))
+            .map(|p| {
+                let emphasised = between(p, "<code><em>", "</em></code>")
+                    .chain(between(p, "<em><code>", "</code></em>"))
+                    .map(|code| unescape(&strip_tags(code)))
+                    .collect();
+                Paragraph {
+                    text: unescape(&strip_tags(p)),
+                    emphasised,
+                }
+            })
+            .collect();
+    }
+    let mut found = Vec::new();
+    let mut fenced = false;
+    for line in section.lines() {
+        if line.trim_start().starts_with("```") {
+            fenced = !fenced;
+            continue;
+        }
+        if fenced || line.trim().is_empty() {
+            continue;
+        }
+        // Every other piece between backticks is a code span, with the text
+        // around it on either side.
+        let pieces: Vec<&str> = line.split('`').collect();
+        let mut emphasised = Vec::new();
+        for i in (1..pieces.len().saturating_sub(1)).step_by(2) {
+            let code = pieces[i];
+            let inner = code.strip_prefix('*').and_then(|c| c.strip_suffix('*'));
+            let wrapped = pieces[i - 1].ends_with('*') && pieces[i + 1].starts_with('*');
+            match inner {
+                Some(inner) if !inner.is_empty() => emphasised.push(inner.to_string()),
+                _ if wrapped && !code.is_empty() => emphasised.push(code.to_string()),
+                _ => {}
+            }
+        }
+        found.push(Paragraph {
+            text: line.replace(['`', '*'], ""),
+            emphasised,
+        });
+    }
+    found
+}
+
+struct Block {
+    text: String,
+    /// The paragraph before the block, as plain text.
+    intro: String,
+    /// Where that paragraph starts in the section, or the block itself when
+    /// nothing comes before it.
+    intro_at: usize,
+}
+
+fn code_blocks(section: &str, html: bool) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    if html {
+        let mut from = 0;
+        while let Some(start) = section[from..].find("<pre><code>").map(|at| from + at) {
+            let Some(block) = between(&section[start..], "<pre><code>", "</code></pre>").next()
+            else {
+                break;
+            };
+            let intro_at = section[from..start]
+                .rfind("<p>")
+                .map_or(start, |at| from + at);
+            blocks.push(Block {
+                text: tidy_block(&unescape(&strip_tags(block))),
+                intro: unescape(&strip_tags(&section[intro_at..start])),
+                intro_at,
+            });
+            from = start + "<pre><code>".len() + block.len();
+        }
+        return blocks;
+    }
+    let mut block: Option<Vec<&str>> = None;
+    let mut intro = ("", None);
+    let mut at = 0;
+    for line in section.split_inclusive('\n') {
+        let start = at;
+        at += line.len();
+        let line = line.trim_end_matches(['\n', '\r']);
+        match (line.trim_start().starts_with("```"), &mut block) {
+            (true, None) => block = Some(Vec::new()),
+            (true, Some(lines)) => {
+                blocks.push(Block {
+                    text: tidy_block(&lines.join("\n")),
+                    intro: intro.0.replace(['`', '*'], ""),
+                    intro_at: intro.1.unwrap_or(start),
+                });
+                block = None;
+                intro = ("", None);
+            }
+            (false, Some(lines)) => lines.push(line),
+            (false, None) if !line.trim().is_empty() => intro = (line, Some(start)),
+            (false, None) => {}
+        }
+    }
+    blocks
+}
+
+fn between<'s>(text: &'s str, open: &'s str, close: &'s str) -> impl Iterator<Item = &'s str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        let start = rest.find(open)? + open.len();
+        let len = rest[start..].find(close)?;
+        let found = &rest[start..start + len];
+        rest = &rest[start + len + close.len()..];
+        Some(found)
+    })
+}
+
+/// Drops the blank lines the page leaves at the end of a block, keeping a
+/// single trailing newline like a real input. Markdown loses the trailing
+/// spaces of some lines in a drawing, so where the other lines of a run keep
+/// theirs, every line of the run is padded back out to the same width.
+fn tidy_block(block: &str) -> String {
+    let mut lines: Vec<String> = block.lines().map(str::to_string).collect();
+    while lines.last().is_some_and(|l| l.trim().is_empty()) {
+        lines.pop();
+    }
+    for run in lines.split_mut(|l| l.trim().is_empty()) {
+        if run.iter().any(|l| l.ends_with(' ')) {
+            let width = run.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+            for line in run.iter_mut() {
+                let short = width - line.chars().count();
+                line.push_str(&" ".repeat(short));
+            }
+        }
+    }
+    let mut block = lines.join("\n");
+    block.push('\n');
+    block
+}
+
+/// Drops every HTML tag from `text`, leaving what is between them.
+pub fn strip_tags(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+impl Display for Example {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lines = self.input.lines().count();
+        match &self.answer {
+            Some(answer) => write!(f, "{lines} line example, answer {answer}"),
+            None => write!(f, "{lines} line example, no answer found"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    static MARKDOWN: &str = "\\--- Day 1: Trebuchet?! ---
+----------
+
+For example:
+
+```
+1abc2
+treb7uchet
+
+```
+
+* The first line gives `*12*`.
+* The last line gives `*77*`.
+
+In this example, adding these together produces *`89`*.
+
+Your puzzle answer was `54708`.
+
+\\--- Part Two ---
+----------
+
+Some digits are *spelled out*: `one`.
+
+In this example, adding these together produces `*281*`.
+";
+
+    static HTML: &str = r#"<article><p>For example:</p>
+<pre><code>a -&gt; b
+<em>c</em>
+</code></pre>
+<p>In this example that gives <code>3</code> in total, so <code><em>4</em></code>.</p>
+<ul><li>Just <code><em>5</em></code> more.</li></ul>
+</article><article><h2>--- Part Two ---</h2>
+<p>Here is another example:</p>
+<pre><code>x &amp; y
+</code></pre>
+<ul><li><code>x &amp; y</code> now gives <em><code>7</code></em>.</li>
+<li><code>z</code> gives <code><em>8</em></code>.</li></ul>
+</article>"#;
+
+    #[test]
+    fn extracting_markdown() {
+        let fixture = Fixture::extract(MARKDOWN);
+        let example = |input: &str, answer: &str| {
+            Some(Example {
+                input: input.to_string(),
+                answer: Some(answer.to_string()),
+            })
+        };
+        assert_eq!(
+            fixture,
+            Fixture {
+                part_one: example("1abc2\ntreb7uchet\n", "89"),
+                part_two: example("1abc2\ntreb7uchet\n", "281"),
+            }
+        );
+    }
+
+    #[test]
+    fn extracting_html() {
+        let fixture = Fixture::extract(HTML);
+        assert_eq!(
+            fixture.part_one.unwrap(),
+            Example {
+                input: "a -> b\nc\n".to_string(),
+                answer: Some("4".to_string()),
+            }
+        );
+        assert_eq!(
+            fixture.part_two.unwrap(),
+            Example {
+                input: "x & y\n".to_string(),
+                answer: Some("7".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn extracting_every_puzzle() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        let mut checked = 0;
+        for year in fs::read_dir(root.join("puzzles")).unwrap() {
+            let year = year.unwrap().path();
+            for puzzle in fs::read_dir(&year).unwrap() {
+                let puzzle = puzzle.unwrap().path();
+                let day = puzzle.file_stem().unwrap().to_str().unwrap();
+                let year: u16 = year.file_name().unwrap().to_str().unwrap().parse().unwrap();
+                let day: u8 = day.trim_start_matches("day").parse().unwrap();
+                let extracted = Fixture::extract(&fs::read_to_string(&puzzle).unwrap());
+                let fixture = Fixture::load(&root.join("fixtures"), year, day).unwrap();
+                assert_eq!(extracted, fixture, "{}", puzzle.display());
+                checked += 1;
+            }
+        }
+        assert!(checked > 0, "no puzzle descriptions to check");
+    }
+
+    #[test]
+    fn round_trip() {
+        let fixture = Fixture::extract(MARKDOWN);
+        let toml = toml::to_string(&fixture).unwrap();
+        assert_eq!(toml::from_str::<Fixture>(&toml).unwrap(), fixture);
+    }
+}
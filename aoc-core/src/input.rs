@@ -0,0 +1,116 @@
+use crate::{Error, Result};
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+/// Environment variable that overrides where puzzle inputs are looked up.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+/// Environment variable naming the input profile to use.
+pub const PROFILE_VAR: &str = "AOC_PROFILE";
+
+/// Where a day's puzzle input comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// A directory laid out as `YEAR/dayNN.txt`.
+    Dir(PathBuf),
+    /// A single file, used whatever the year and day.
+    File(PathBuf),
+    Stdin,
+}
+
+impl InputSource {
+    /// Switches a directory to the named profile inside it, any other source
+    /// is left alone.
+    pub fn profile(self, name: &str) -> Self {
+        match self {
+            InputSource::Dir(dir) => InputSource::Dir(dir.join("profiles").join(name)),
+            source => source,
+        }
+    }
+
+    /// Treats `-` as stdin and anything else as a file.
+    pub fn from_arg(arg: &str) -> Self {
+        match arg {
+            "-" => InputSource::Stdin,
+            path => InputSource::File(path.into()),
+        }
+    }
+
+    /// Path of a day's input inside an input directory.
+    pub fn day_path(dir: &Path, year: u16, day: u8) -> PathBuf {
+        dir.join(year.to_string()).join(format!("day{day:02}.txt"))
+    }
+
+    pub fn read(&self, year: u16, day: u8) -> Result<String> {
+        match self {
+            InputSource::Dir(dir) => read_file(&Self::day_path(dir, year, day), year, day),
+            InputSource::File(path) => read_file(path, year, day),
+            InputSource::Stdin => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input)?;
+                Ok(input)
+            }
+        }
+    }
+}
+
+fn read_file(path: &Path, year: u16, day: u8) -> Result<String> {
+    fs::read_to_string(path).map_err(|err| match err.kind() {
+        io::ErrorKind::NotFound => Error::MissingInput {
+            year,
+            day,
+            path: path.to_path_buf(),
+        },
+        _ => Error::Io(err),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn day_paths_are_zero_padded() {
+        assert_eq!(
+            InputSource::day_path(Path::new("inputs"), 2020, 1),
+            Path::new("inputs/2020/day01.txt")
+        );
+        assert_eq!(
+            InputSource::day_path(Path::new("inputs"), 2023, 11),
+            Path::new("inputs/2023/day11.txt")
+        );
+    }
+
+    #[test]
+    fn missing_input() {
+        let source = InputSource::Dir(PathBuf::from("does/not/exist"));
+        assert!(matches!(
+            source.read(2020, 1),
+            Err(Error::MissingInput {
+                year: 2020,
+                day: 1,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn profiles() {
+        assert_eq!(
+            InputSource::Dir("inputs".into()).profile("alice"),
+            InputSource::Dir("inputs/profiles/alice".into())
+        );
+        assert_eq!(InputSource::Stdin.profile("alice"), InputSource::Stdin);
+    }
+
+    #[test]
+    fn reading_args() {
+        assert_eq!(InputSource::from_arg("-"), InputSource::Stdin);
+        assert_eq!(
+            InputSource::from_arg("day01.txt"),
+            InputSource::File("day01.txt".into())
+        );
+    }
+}
@@ -0,0 +1,22 @@
+//! Shared plumbing for every year's solutions.
+//!
+//! Each day implements [`Solution`] and registers itself with a [`Registry`] so
+//! the CLI and any other tooling can run every year the same way.
+
+mod bench;
+mod error;
+mod fixture;
+mod input;
+mod part;
+mod registry;
+mod solution;
+mod variants;
+
+pub use bench::{Sampler, Stats};
+pub use error::{Error, Result};
+pub use fixture::{check_example, strip_tags, Example, Fixture};
+pub use input::{InputSource, INPUT_DIR_VAR, PROFILE_VAR};
+pub use part::Part;
+pub use registry::{Puzzle, Registry};
+pub use solution::Solution;
+pub use variants::{Parser, Solver, VariantAnswer, Variants, DEFAULT};
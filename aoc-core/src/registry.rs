@@ -0,0 +1,180 @@
+use crate::{Error, Part, Result, Sampler, Solution, Stats, VariantAnswer, Variants, DEFAULT};
+
+/// A [`Solution`] with its types erased so days from every year can be kept
+/// in one list.
+pub trait Puzzle {
+    fn year(&self) -> u16;
+    fn day(&self) -> u8;
+    /// Names of every registered parser, starting with [`DEFAULT`].
+    fn parsers(&self) -> Vec<&'static str>;
+    /// Names of every registered solver for `part`, starting with [`DEFAULT`].
+    fn solvers(&self, part: Part) -> Vec<&'static str>;
+    /// Parses `input` with the named parser then solves `part` with the named
+    /// solver, returning the formatted answer.
+    fn run_variant(&self, input: &str, part: Part, parser: &str, solver: &str) -> Result<String>;
+    /// Runs every parser and solver pairing for `part` against `input`.
+    fn run_all(&self, input: &str, part: Part) -> Result<Vec<VariantAnswer>>;
+    /// Times the named parser on its own.
+    fn bench_parser(&self, input: &str, parser: &str, sampler: &Sampler) -> Result<Stats>;
+    /// Times the named solver against input already read by the named parser.
+    fn bench_solver(
+        &self,
+        input: &str,
+        part: Part,
+        parser: &str,
+        solver: &str,
+        sampler: &Sampler,
+    ) -> Result<Stats>;
+
+    /// Runs the default parser and solver.
+    fn run(&self, input: &str, part: Part) -> Result<String> {
+        self.run_variant(input, part, DEFAULT, DEFAULT)
+    }
+}
+
+/// Every registered day, kept in year and day order.
+#[derive(Default)]
+pub struct Registry {
+    puzzles: Vec<Box<dyn Puzzle>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a day with only its default parser and solvers.
+    pub fn add<S: Solution + 'static>(&mut self) {
+        self.add_variants(Variants::<S>::new());
+    }
+
+    pub fn add_variants<S: Solution + 'static>(&mut self, variants: Variants<S>) {
+        let key = (S::YEAR, S::DAY);
+        let at = self.puzzles.partition_point(|p| (p.year(), p.day()) < key);
+        self.puzzles.insert(at, Box::new(variants));
+    }
+
+    pub fn get(&self, year: u16, day: u8) -> Result<&dyn Puzzle> {
+        self.iter()
+            .find(|p| p.year() == year && p.day() == day)
+            .ok_or(Error::UnknownDay { year, day })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Puzzle> {
+        self.puzzles.iter().map(|p| p.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        const YEAR: u16 = 2000;
+        const DAY: u8 = 2;
+        type Input<'i> = Vec<&'i str>;
+        type Output = usize;
+
+        fn parse(input: &str) -> Result<Self::Input<'_>> {
+            Ok(input.lines().collect())
+        }
+
+        fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+            input
+                .iter()
+                .map(|l| l.parse::<usize>().map_err(Error::parse))
+                .sum()
+        }
+    }
+
+    struct Count;
+
+    impl Solution for Count {
+        const YEAR: u16 = 2000;
+        const DAY: u8 = 1;
+        type Input<'i> = &'i str;
+        type Output = usize;
+
+        fn parse(input: &str) -> Result<Self::Input<'_>> {
+            Ok(input)
+        }
+
+        fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+            Ok(input.lines().count())
+        }
+
+        fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+            Ok(input.len())
+        }
+    }
+
+    #[test]
+    fn keeps_days_in_order() {
+        let mut registry = Registry::new();
+        registry.add::<Sum>();
+        registry.add::<Count>();
+        let days: Vec<_> = registry.iter().map(|p| p.day()).collect();
+        assert_eq!(days, vec![1, 2]);
+    }
+
+    #[test]
+    fn running_parts() {
+        let mut registry = Registry::new();
+        registry.add::<Sum>();
+        let sum = registry.get(2000, 2).unwrap();
+        assert_eq!(sum.run("1\n2\n3", Part::One).unwrap(), "6");
+        assert!(matches!(
+            sum.run("1\n2\n3", Part::Two),
+            Err(Error::Unsolved(Part::Two))
+        ));
+        assert!(matches!(sum.run("a", Part::One), Err(Error::Parse(_))));
+        assert!(matches!(
+            registry.get(2000, 3),
+            Err(Error::UnknownDay { year: 2000, day: 3 })
+        ));
+    }
+
+    #[test]
+    fn running_variants() {
+        let mut registry = Registry::new();
+        registry.add_variants(
+            Variants::<Sum>::new()
+                .parser("commas", |i| Ok(i.split(',').collect()))
+                .part_one("fold", |i| {
+                    i.iter().try_fold(0, |acc, l| {
+                        Ok(acc + l.parse::<usize>().map_err(Error::parse)?)
+                    })
+                }),
+        );
+        let sum = registry.get(2000, 2).unwrap();
+        assert_eq!(sum.parsers(), vec![DEFAULT, "commas"]);
+        assert_eq!(sum.solvers(Part::One), vec![DEFAULT, "fold"]);
+        assert_eq!(sum.solvers(Part::Two), vec![DEFAULT]);
+        assert_eq!(
+            sum.run_variant("1,2,3", Part::One, "commas", "fold")
+                .unwrap(),
+            "6"
+        );
+        assert!(matches!(
+            sum.run_variant("1", Part::One, DEFAULT, "missing"),
+            Err(Error::UnknownVariant(name)) if name == "missing"
+        ));
+        let answers: Vec<_> = sum
+            .run_all("1\n2", Part::One)
+            .unwrap()
+            .into_iter()
+            .map(|v| (v.parser, v.solver, v.answer.ok()))
+            .collect();
+        assert_eq!(
+            answers,
+            vec![
+                (DEFAULT, DEFAULT, Some("3".to_string())),
+                (DEFAULT, "fold", Some("3".to_string())),
+                ("commas", DEFAULT, None),
+                ("commas", "fold", None),
+            ]
+        );
+    }
+}
@@ -0,0 +1,23 @@
+use crate::{Error, Part, Result};
+use std::fmt::Display;
+
+/// A single day's puzzle.
+///
+/// Parsing is kept separate from solving so the parsed input can be shared
+/// between both parts and each step can be timed on its own.
+pub trait Solution {
+    const YEAR: u16;
+    const DAY: u8;
+
+    /// Parsed form of the puzzle input, which may borrow from the raw input.
+    type Input<'i>;
+    type Output: Display;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>>;
+
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output>;
+
+    fn part_two(_input: &Self::Input<'_>) -> Result<Self::Output> {
+        Err(Error::Unsolved(Part::Two))
+    }
+}
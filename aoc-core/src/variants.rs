@@ -0,0 +1,148 @@
+use crate::{Error, Part, Puzzle, Result, Sampler, Solution, Stats};
+
+/// Name given to the [`Solution`]'s own parser and solvers.
+pub const DEFAULT: &str = "default";
+
+pub type Parser<S> = for<'i> fn(&'i str) -> Result<<S as Solution>::Input<'i>>;
+pub type Solver<S> =
+    for<'a, 'i> fn(&'a <S as Solution>::Input<'i>) -> Result<<S as Solution>::Output>;
+
+/// Named alternative parsers and solvers for a single day.
+///
+/// The [`Solution`] impl is always registered as [`DEFAULT`], anything added
+/// through the builder methods sits alongside it so the implementations can
+/// be run by name and compared against each other.
+pub struct Variants<S: Solution> {
+    parsers: Vec<(&'static str, Parser<S>)>,
+    part_one: Vec<(&'static str, Solver<S>)>,
+    part_two: Vec<(&'static str, Solver<S>)>,
+}
+
+impl<S: Solution> Variants<S> {
+    pub fn new() -> Self {
+        Self {
+            parsers: vec![(DEFAULT, S::parse)],
+            part_one: vec![(DEFAULT, S::part_one)],
+            part_two: vec![(DEFAULT, S::part_two)],
+        }
+    }
+
+    pub fn parser(mut self, name: &'static str, parser: Parser<S>) -> Self {
+        self.parsers.push((name, parser));
+        self
+    }
+
+    pub fn part_one(mut self, name: &'static str, solver: Solver<S>) -> Self {
+        self.part_one.push((name, solver));
+        self
+    }
+
+    pub fn part_two(mut self, name: &'static str, solver: Solver<S>) -> Self {
+        self.part_two.push((name, solver));
+        self
+    }
+
+    fn solvers_for(&self, part: Part) -> &[(&'static str, Solver<S>)] {
+        match part {
+            Part::One => &self.part_one,
+            Part::Two => &self.part_two,
+        }
+    }
+
+    fn find_parser(&self, name: &str) -> Result<Parser<S>> {
+        self.parsers
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, p)| *p)
+            .ok_or_else(|| Error::UnknownVariant(name.to_string()))
+    }
+
+    fn find_solver(&self, part: Part, name: &str) -> Result<Solver<S>> {
+        self.solvers_for(part)
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, s)| *s)
+            .ok_or_else(|| Error::UnknownVariant(name.to_string()))
+    }
+}
+
+impl<S: Solution> Default for Variants<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The answer one parser and solver pairing gave.
+#[derive(Debug)]
+pub struct VariantAnswer {
+    pub parser: &'static str,
+    pub solver: &'static str,
+    pub answer: Result<String>,
+}
+
+impl<S: Solution> Puzzle for Variants<S> {
+    fn year(&self) -> u16 {
+        S::YEAR
+    }
+
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn parsers(&self) -> Vec<&'static str> {
+        self.parsers.iter().map(|(n, _)| *n).collect()
+    }
+
+    fn solvers(&self, part: Part) -> Vec<&'static str> {
+        self.solvers_for(part).iter().map(|(n, _)| *n).collect()
+    }
+
+    fn run_variant(&self, input: &str, part: Part, parser: &str, solver: &str) -> Result<String> {
+        let parse = self.find_parser(parser)?;
+        let solve = self.find_solver(part, solver)?;
+        let input = parse(input)?;
+        Ok(solve(&input)?.to_string())
+    }
+
+    fn run_all(&self, input: &str, part: Part) -> Result<Vec<VariantAnswer>> {
+        let mut answers = Vec::new();
+        for (parser, parse) in &self.parsers {
+            let parsed = parse(input)?;
+            for (solver, solve) in self.solvers_for(part) {
+                answers.push(VariantAnswer {
+                    parser,
+                    solver,
+                    answer: solve(&parsed).map(|a| a.to_string()),
+                });
+            }
+        }
+        Ok(answers)
+    }
+
+    fn bench_parser(&self, input: &str, parser: &str, sampler: &Sampler) -> Result<Stats> {
+        let parse = self.find_parser(parser)?;
+        sampler.measure(|| {
+            let (took, parsed) = Sampler::time(|| parse(input));
+            parsed?;
+            Ok(took)
+        })
+    }
+
+    fn bench_solver(
+        &self,
+        input: &str,
+        part: Part,
+        parser: &str,
+        solver: &str,
+        sampler: &Sampler,
+    ) -> Result<Stats> {
+        let parse = self.find_parser(parser)?;
+        let solve = self.find_solver(part, solver)?;
+        let input = parse(input)?;
+        sampler.measure(|| {
+            let (took, answer) = Sampler::time(|| solve(&input));
+            answer?;
+            Ok(took)
+        })
+    }
+}
@@ -0,0 +1,109 @@
+use anyhow::{anyhow, Context, Result};
+use aoc_core::Part;
+use std::{collections::BTreeMap, fmt::Write, fs, path::Path};
+
+/// Known answers to the real puzzle inputs, keyed by year, day and part.
+///
+/// Stored as TOML with a table per year and day:
+///
+/// ```toml
+/// [2020.1]
+/// 1 = "1018944"
+/// 2 = "8446464"
+/// ```
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers(BTreeMap<(u16, u8, Part), String>);
+
+type Tables = BTreeMap<String, BTreeMap<String, BTreeMap<String, String>>>;
+
+impl Answers {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("could not read answers {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("could not parse answers {}", path.display()))
+    }
+
+    fn parse(text: &str) -> Result<Self> {
+        let tables: Tables = toml::from_str(text)?;
+        let mut answers = BTreeMap::new();
+        for (year, days) in tables {
+            let year = key(&year)?;
+            for (day, parts) in days {
+                let day = key(&day)?;
+                for (part, answer) in parts {
+                    let part = Part::try_from(key::<u8>(&part)?).map_err(|e| anyhow!(e))?;
+                    answers.insert((year, day, part), answer);
+                }
+            }
+        }
+        Ok(Self(answers))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_toml())?;
+        Ok(())
+    }
+
+    /// Writes the answers back out sorted by number rather than the
+    /// lexicographic order a map of string keys would give.
+    fn to_toml(&self) -> String {
+        let mut out = String::new();
+        let mut day = None;
+        for (&(y, d, p), answer) in &self.0 {
+            if day != Some((y, d)) {
+                if day.is_some() {
+                    out.push('\n');
+                }
+                writeln!(out, "[{y}.{d}]").unwrap();
+                day = Some((y, d));
+            }
+            writeln!(out, "{p} = {}", toml::Value::from(answer.as_str())).unwrap();
+        }
+        out
+    }
+
+    pub fn get(&self, year: u16, day: u8, part: Part) -> Option<&str> {
+        self.0.get(&(year, day, part)).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, year: u16, day: u8, part: Part, answer: String) {
+        self.0.insert((year, day, part), answer);
+    }
+}
+
+fn key<T: std::str::FromStr>(key: &str) -> Result<T> {
+    key.parse().map_err(|_| anyhow!("`{key}` is not a number"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    static ANSWERS: &str = "[2020.1]
+1 = \"1018944\"
+2 = \"8446464\"
+
+[2020.10]
+1 = \"1885\"
+
+[2020.9]
+2 = \"abc\\\"def\"
+";
+
+    #[test]
+    fn round_trip() {
+        let answers = Answers::parse(ANSWERS).unwrap();
+        assert_eq!(answers.get(2020, 1, Part::Two), Some("8446464"));
+        assert_eq!(answers.get(2020, 9, Part::Two), Some("abc\"def"));
+        assert_eq!(answers.get(2020, 10, Part::Two), None);
+        let toml = answers.to_toml();
+        assert!(toml.find("[2020.9]") < toml.find("[2020.10]"));
+        assert_eq!(Answers::parse(&toml).unwrap(), answers);
+    }
+
+    #[test]
+    fn bad_keys() {
+        assert!(Answers::parse("[2020.1]\n3 = \"1\"").is_err());
+        assert!(Answers::parse("[2020.one]\n1 = \"1\"").is_err());
+    }
+}
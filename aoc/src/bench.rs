@@ -0,0 +1,248 @@
+use crate::Filter;
+use anyhow::{Context, Result};
+use aoc_core::{Error, InputSource, Registry, Sampler, Stats, DEFAULT};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt::Write, fs, path::Path, time::Duration};
+
+/// Timings for one step of a day, either parsing or solving a part.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Row {
+    pub year: u16,
+    pub day: u8,
+    /// `parse`, `part 1` or `part 2`.
+    pub step: String,
+    pub variant: String,
+    #[serde(flatten)]
+    pub stats: Stats,
+}
+
+/// Times every parser of each matching day on its own, then every solver of
+/// each part using the default parser.
+///
+/// Days without an input are skipped, as are unsolved parts. Any other
+/// failure is reported and the rest of the days carry on.
+pub fn run(
+    registry: &Registry,
+    filter: &Filter,
+    sampler: &Sampler,
+    inputs: &InputSource,
+) -> Vec<Row> {
+    let mut rows = Vec::new();
+    for puzzle in registry.iter().filter(|p| filter.matches(*p)) {
+        let (year, day) = (puzzle.year(), puzzle.day());
+        let input = match crate::read_input(inputs, year, day) {
+            Ok(input) => input,
+            Err(err) => {
+                eprintln!("skipping {year} day {day}: {err}");
+                continue;
+            }
+        };
+        let mut push = |step: String, variant: &str, stats: aoc_core::Result<Stats>| match stats {
+            Ok(stats) => rows.push(Row {
+                year,
+                day,
+                step,
+                variant: variant.to_string(),
+                stats,
+            }),
+            Err(Error::Unsolved(_)) => {}
+            Err(err) => eprintln!("{year} day {day} {step} {variant}: {err}"),
+        };
+        for parser in puzzle.parsers() {
+            push(
+                "parse".to_string(),
+                parser,
+                puzzle.bench_parser(&input, parser, sampler),
+            );
+        }
+        for part in filter.parts() {
+            for solver in puzzle.solvers(part) {
+                push(
+                    format!("part {part}"),
+                    solver,
+                    puzzle.bench_solver(&input, part, DEFAULT, solver, sampler),
+                );
+            }
+        }
+    }
+    rows
+}
+
+pub fn table(rows: &[Row]) -> String {
+    let mut out = format!(
+        "{:<4} {:>3}  {:<6}  {:<14} {:>10} {:>10} {:>10} {:>10} {:>10} {:>8}\n",
+        "year", "day", "step", "variant", "median", "p95", "mean", "min", "max", "outliers"
+    );
+    for row in rows {
+        let s = &row.stats;
+        writeln!(
+            out,
+            "{:<4} {:>3}  {:<6}  {:<14} {:>10} {:>10} {:>10} {:>10} {:>10} {:>8}",
+            row.year,
+            row.day,
+            row.step,
+            row.variant,
+            nanos(s.median),
+            nanos(s.p95),
+            nanos(s.mean),
+            nanos(s.min),
+            nanos(s.max),
+            format!("{}/{}", s.outliers, s.samples + s.outliers),
+        )
+        .unwrap();
+    }
+    out
+}
+
+pub fn save(rows: &[Row], path: &Path) -> Result<()> {
+    fs::write(path, serde_json::to_string_pretty(rows)?)?;
+    Ok(())
+}
+
+pub fn load(path: &Path) -> Result<Vec<Row>> {
+    let baseline = fs::read_to_string(path)
+        .with_context(|| format!("could not read baseline {}", path.display()))?;
+    Ok(serde_json::from_str(&baseline)?)
+}
+
+/// How a step's median moved against the baseline.
+#[derive(Debug, PartialEq)]
+pub enum Change {
+    /// Nothing to compare against.
+    New,
+    Faster,
+    Same,
+    Slower,
+}
+
+/// A step's current median next to the one from the baseline.
+pub struct Comparison<'r> {
+    pub row: &'r Row,
+    pub baseline: Option<u64>,
+    pub change: Change,
+}
+
+impl Row {
+    fn key(&self) -> (u16, u8, &str, &str) {
+        (self.year, self.day, &self.step, &self.variant)
+    }
+}
+
+/// Matches each row to the baseline by year, day, step and variant. A median
+/// more than `threshold` percent away from the baseline counts as a change.
+pub fn compare<'r>(baseline: &[Row], rows: &'r [Row], threshold: f64) -> Vec<Comparison<'r>> {
+    let baseline: HashMap<_, _> = baseline.iter().map(|r| (r.key(), r.stats.median)).collect();
+    rows.iter()
+        .map(|row| {
+            let base = baseline.get(&row.key()).copied();
+            let change = match base {
+                None => Change::New,
+                Some(base) => {
+                    let ratio = row.stats.median as f64 / base.max(1) as f64;
+                    if ratio > 1.0 + threshold / 100.0 {
+                        Change::Slower
+                    } else if ratio < 1.0 - threshold / 100.0 {
+                        Change::Faster
+                    } else {
+                        Change::Same
+                    }
+                }
+            };
+            Comparison {
+                row,
+                baseline: base,
+                change,
+            }
+        })
+        .collect()
+}
+
+pub fn comparison_table(comparisons: &[Comparison]) -> String {
+    let mut out = format!(
+        "{:<4} {:>3}  {:<6}  {:<14} {:>10} {:>10} {:>8}  {}\n",
+        "year", "day", "step", "variant", "baseline", "median", "change", "result"
+    );
+    for c in comparisons {
+        let row = c.row;
+        let (baseline, change) = match c.baseline {
+            Some(base) => (
+                nanos(base),
+                format!(
+                    "{:+.1}%",
+                    (row.stats.median as f64 / base.max(1) as f64 - 1.0) * 100.0
+                ),
+            ),
+            None => ("-".to_string(), "-".to_string()),
+        };
+        let result = match c.change {
+            Change::New => "new",
+            Change::Faster => "faster",
+            Change::Same => "ok",
+            Change::Slower => "SLOWER",
+        };
+        writeln!(
+            out,
+            "{:<4} {:>3}  {:<6}  {:<14} {:>10} {:>10} {:>8}  {}",
+            row.year,
+            row.day,
+            row.step,
+            row.variant,
+            baseline,
+            nanos(row.stats.median),
+            change,
+            result,
+        )
+        .unwrap();
+    }
+    out
+}
+
+fn nanos(nanos: u64) -> String {
+    format!("{:.2?}", Duration::from_nanos(nanos))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn row(day: u8, variant: &str, median: u64) -> Row {
+        Row {
+            year: 2020,
+            day,
+            step: "part 1".to_string(),
+            variant: variant.to_string(),
+            stats: Stats {
+                samples: 1,
+                outliers: 0,
+                min: median,
+                median,
+                p95: median,
+                mean: median,
+                max: median,
+            },
+        }
+    }
+
+    #[test]
+    fn comparing_against_baseline() {
+        let baseline = vec![
+            row(1, DEFAULT, 100),
+            row(1, "sorted", 100),
+            row(2, DEFAULT, 100),
+        ];
+        let rows = vec![
+            row(1, DEFAULT, 105),
+            row(1, "sorted", 111),
+            row(2, DEFAULT, 80),
+            row(3, DEFAULT, 100),
+        ];
+        let changes: Vec<_> = compare(&baseline, &rows, 10.0)
+            .into_iter()
+            .map(|c| c.change)
+            .collect();
+        assert_eq!(
+            changes,
+            vec![Change::Same, Change::Slower, Change::Faster, Change::New]
+        );
+    }
+}
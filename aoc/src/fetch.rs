@@ -0,0 +1,291 @@
+use anyhow::{anyhow, bail, Context, Result};
+use aoc_core::{strip_tags, Part};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+pub const BASE_URL: &str = "https://adventofcode.com";
+/// Environment variable holding the `session` cookie of a logged in account.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+
+const USER_AGENT: &str = "github.com/dixonwille/advent-of-code";
+
+/// Talks to the Advent of Code site, keeping every page it downloads on disk
+/// and leaving at least `min_interval` between requests, even across runs.
+pub struct Client {
+    agent: ureq::Agent,
+    base_url: String,
+    session: String,
+    cache: PathBuf,
+    min_interval: Duration,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: String, cache: PathBuf, min_interval: Duration) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new().user_agent(USER_AGENT).build(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session,
+            cache,
+            min_interval,
+        }
+    }
+
+    /// The day's puzzle input, straight from the cache when it has been
+    /// downloaded before since it never changes.
+    pub fn input(&self, year: u16, day: u8) -> Result<String> {
+        self.cached(&format!("{year}/day/{day}/input"), false)
+    }
+
+    /// The day's puzzle description as the HTML of its `<article>`s. Part two
+    /// only shows up once part one is solved, so `refresh` skips the cache.
+    pub fn puzzle(&self, year: u16, day: u8, refresh: bool) -> Result<String> {
+        let page = self.cached(&format!("{year}/day/{day}"), refresh)?;
+        let articles = articles(&page);
+        if articles.is_empty() {
+            bail!("no puzzle description found for {year} day {day}");
+        }
+        Ok(articles.join("\n"))
+    }
+
+    /// Submits an answer, returning the site's reply. Never cached.
+    pub fn submit(&self, year: u16, day: u8, part: Part, answer: &str) -> Result<String> {
+        let url = format!("{}/{year}/day/{day}/answer", self.base_url);
+        self.wait_turn()?;
+        let page = self
+            .agent
+            .post(&url)
+            .set("Cookie", &self.cookie())
+            .send_form(&[("level", &part.to_string()), ("answer", answer)])
+            .map_err(|err| request_error(&url, err))?
+            .into_string()?;
+        Ok(articles(&page)
+            .first()
+            .map(|a| strip_tags(a).trim().to_string())
+            .unwrap_or(page))
+    }
+
+    fn cached(&self, path: &str, refresh: bool) -> Result<String> {
+        let file = self.cache.join(path).with_extension("cache");
+        if !refresh {
+            if let Ok(body) = fs::read_to_string(&file) {
+                return Ok(body);
+            }
+        }
+        let url = format!("{}/{path}", self.base_url);
+        self.wait_turn()?;
+        let body = self
+            .agent
+            .get(&url)
+            .set("Cookie", &self.cookie())
+            .call()
+            .map_err(|err| request_error(&url, err))?
+            .into_string()?;
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&file, &body)?;
+        Ok(body)
+    }
+
+    fn cookie(&self) -> String {
+        format!("session={}", self.session)
+    }
+
+    /// Sleeps until `min_interval` has passed since the last request, which is
+    /// stamped in the cache so separate runs share the limit.
+    fn wait_turn(&self) -> Result<()> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+        let last = self.last_request();
+        if let Some(wait) = last.and_then(|last| (last + self.min_interval).checked_sub(now)) {
+            thread::sleep(wait);
+        }
+        fs::create_dir_all(&self.cache)?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+        fs::write(self.stamp(), now.as_micros().to_string())?;
+        Ok(())
+    }
+
+    /// When the last request went out, since the Unix epoch. Kept in
+    /// microseconds so rounding never shortens the wait.
+    fn last_request(&self) -> Option<Duration> {
+        fs::read_to_string(self.stamp())
+            .ok()
+            .and_then(|s| s.trim().parse().ok())
+            .map(Duration::from_micros)
+    }
+
+    fn stamp(&self) -> PathBuf {
+        self.cache.join("last-request")
+    }
+}
+
+fn request_error(url: &str, err: ureq::Error) -> anyhow::Error {
+    match err {
+        ureq::Error::Status(400 | 401, _) => {
+            anyhow!("{url} rejected the session token, is {SESSION_VAR} up to date?")
+        }
+        ureq::Error::Status(404, _) => anyhow!("{url} does not exist yet"),
+        err => anyhow!(err).context(format!("could not fetch {url}")),
+    }
+}
+
+/// Finds the session token in [`SESSION_VAR`], then the aoc config directory,
+/// then `~/.adventofcode.session` where aoc-cli keeps it.
+pub fn session() -> Result<String> {
+    if let Ok(session) = env::var(SESSION_VAR) {
+        if !session.trim().is_empty() {
+            return Ok(session.trim().to_string());
+        }
+    }
+    let home = env::var_os("HOME").map(PathBuf::from);
+    let config = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home.as_ref().map(|h| h.join(".config")));
+    let files = [
+        config.map(|c| c.join("aoc").join("session")),
+        home.map(|h| h.join(".adventofcode.session")),
+    ];
+    for file in files.into_iter().flatten() {
+        if let Ok(session) = fs::read_to_string(&file) {
+            return Ok(session.trim().to_string());
+        }
+    }
+    bail!("no session token, set {SESSION_VAR} or write it to ~/.config/aoc/session")
+}
+
+/// Writes `contents` to `path` unless it already exists and `force` is off,
+/// returning whether it was written.
+pub fn save(path: &Path, contents: &str, force: bool) -> Result<bool> {
+    if !force && path.exists() {
+        return Ok(false);
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents).with_context(|| format!("could not write {}", path.display()))?;
+    Ok(true)
+}
+
+fn articles(page: &str) -> Vec<&str> {
+    let mut rest = page;
+    let mut found = Vec::new();
+    while let Some(start) = rest.find("<article") {
+        let Some(len) = rest[start..].find("</article>") else {
+            break;
+        };
+        let end = start + len + "</article>".len();
+        found.push(&rest[start..end]);
+        rest = &rest[end..];
+    }
+    found
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        sync::{Arc, Mutex},
+    };
+
+    /// Serves canned pages over plain HTTP, recording the request lines and
+    /// cookies it saw.
+    fn stand_in(pages: Vec<(&'static str, &'static str)>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = String::new();
+                reader.read_line(&mut request).unwrap();
+                let mut cookie = String::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some(c) = line.strip_prefix("Cookie: ") {
+                        cookie = c.trim().to_string();
+                    }
+                }
+                let path = request.split(' ').nth(1).unwrap_or_default().to_string();
+                log.lock().unwrap().push(format!("{path} {cookie}"));
+                let response = match pages.iter().find(|(p, _)| *p == path) {
+                    Some((_, body)) => format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    ),
+                    None => {
+                        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                            .to_string()
+                    }
+                };
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        (url, seen)
+    }
+
+    fn cache_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-fetch-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn fetching_and_caching() {
+        let (url, seen) = stand_in(vec![
+            ("/2023/day/1/input", "1abc2\n"),
+            (
+                "/2023/day/1",
+                "<html><main><article><h2>--- Day 1 ---</h2></article><p>x</p></main></html>",
+            ),
+        ]);
+        let cache = cache_dir("caching");
+        let client = Client::new(&url, "secret".to_string(), cache.clone(), Duration::ZERO);
+        assert_eq!(client.input(2023, 1).unwrap(), "1abc2\n");
+        assert_eq!(client.input(2023, 1).unwrap(), "1abc2\n");
+        assert_eq!(
+            client.puzzle(2023, 1, false).unwrap(),
+            "<article><h2>--- Day 1 ---</h2></article>"
+        );
+        assert!(client.input(2023, 2).is_err());
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                "/2023/day/1/input session=secret",
+                "/2023/day/1 session=secret",
+                "/2023/day/2/input session=secret",
+            ]
+        );
+        fs::remove_dir_all(cache).unwrap();
+    }
+
+    #[test]
+    fn rate_limiting() {
+        let (url, _) = stand_in(vec![("/2023/day/1/input", "1"), ("/2023/day/2/input", "2")]);
+        let cache = cache_dir("rate");
+        let client = Client::new(
+            &url,
+            "secret".to_string(),
+            cache.clone(),
+            Duration::from_millis(300),
+        );
+        client.input(2023, 1).unwrap();
+        let first = client.last_request().unwrap();
+        client.input(2023, 2).unwrap();
+        let second = client.last_request().unwrap();
+        assert!(second - first >= Duration::from_millis(300));
+        fs::remove_dir_all(cache).unwrap();
+    }
+}
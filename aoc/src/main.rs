@@ -0,0 +1,494 @@
+use answers::Answers;
+use anyhow::{bail, Result};
+use aoc_core::{
+    Error, Fixture, InputSource, Part, Puzzle, Registry, Sampler, DEFAULT, INPUT_DIR_VAR,
+    PROFILE_VAR,
+};
+use clap::{Args, Parser, Subcommand};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+mod answers;
+mod bench;
+mod fetch;
+mod verify;
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let registry = registry();
+    let mut inputs = InputSource::Dir(cli.input_dir);
+    if let Some(profile) = &cli.profile {
+        inputs = inputs.profile(profile);
+    }
+    match cli.command {
+        Command::Run {
+            year,
+            day,
+            part,
+            parser,
+            variant,
+            input,
+        } => {
+            let puzzle = registry.get(year, day)?;
+            let input = input.map_or(inputs, |i| InputSource::from_arg(&i));
+            let input = read_input(&input, year, day)?;
+            for part in parts(part) {
+                println!(
+                    "{year} day {day} part {part}: {}",
+                    puzzle.run_variant(&input, part, &parser, &variant)?
+                );
+            }
+        }
+        Command::Passports { rules, input } => {
+            let schema = year2020::day04::Schema::load(&rules)?;
+            let input = input.map_or(inputs, |i| InputSource::from_arg(&i));
+            let input = read_input(&input, 2020, 4)?;
+            let (complete, valid) = year2020::day04::count_valid(&input, schema)?;
+            println!("2020 day 4 part 1: {complete}");
+            println!("2020 day 4 part 2: {valid}");
+        }
+        Command::Variants {
+            year,
+            day,
+            check,
+            input,
+        } => {
+            let puzzle = registry.get(year, day)?;
+            list_variants(puzzle);
+            let input = input.map_or(inputs, |i| InputSource::from_arg(&i));
+            if check && !check_variants(puzzle, &read_input(&input, year, day)?)? {
+                bail!("{year} day {day} variants disagree");
+            }
+        }
+        Command::Bench {
+            year,
+            day,
+            part,
+            warmup,
+            samples,
+            json,
+            compare,
+            threshold,
+        } => {
+            let filter = Filter { year, day, part };
+            let sampler = Sampler {
+                warmup: Duration::from_millis(warmup),
+                samples,
+            };
+            let rows = bench::run(&registry, &filter, &sampler, &inputs);
+            print!("{}", bench::table(&rows));
+            if let Some(path) = json {
+                bench::save(&rows, &path)?;
+            }
+            if let Some(path) = compare {
+                let baseline = bench::load(&path)?;
+                let comparisons = bench::compare(&baseline, &rows, threshold);
+                println!();
+                print!("{}", bench::comparison_table(&comparisons));
+                let slower = comparisons
+                    .iter()
+                    .filter(|c| c.change == bench::Change::Slower)
+                    .count();
+                if slower > 0 {
+                    bail!("{slower} step(s) are more than {threshold}% slower than the baseline");
+                }
+            }
+        }
+        Command::Verify {
+            year,
+            day,
+            part,
+            answers: path,
+            record,
+        } => {
+            let mut answers = match Answers::load(&path) {
+                Ok(answers) => answers,
+                Err(_) if record && !path.exists() => Answers::default(),
+                Err(err) => return Err(err),
+            };
+            let filter = Filter { year, day, part };
+            let checks = verify::run(&registry, &filter, &answers, &inputs);
+            print!("{}", verify::table(&checks));
+            if record {
+                verify::record(&checks, &mut answers);
+                answers.save(&path)?;
+            }
+            let failed = checks.iter().filter(|c| c.failed()).count();
+            if failed > 0 {
+                bail!("{failed} answer(s) did not match");
+            }
+        }
+        Command::Fixtures {
+            year,
+            day,
+            puzzles,
+            fixtures,
+            force,
+        } => {
+            let filter = Filter {
+                year,
+                day,
+                part: None,
+            };
+            write_fixtures(&registry, &filter, &puzzles, &fixtures, force)?;
+        }
+        Command::Fetch {
+            year,
+            day,
+            puzzles,
+            refresh,
+            force,
+            site,
+        } => {
+            let client = site.client()?;
+            let InputSource::Dir(dir) = &inputs else {
+                unreachable!("inputs are always read from a directory outside of run")
+            };
+            let path = InputSource::day_path(dir, year, day);
+            if fetch::save(&path, &client.input(year, day)?, force)? {
+                println!("wrote {}", path.display());
+            } else {
+                println!("{} exists, skipping", path.display());
+            }
+            let path = puzzles
+                .join(year.to_string())
+                .join(format!("day{day:02}.html"));
+            if fetch::save(&path, &client.puzzle(year, day, refresh)?, force || refresh)? {
+                println!("wrote {}", path.display());
+            } else {
+                println!("{} exists, skipping", path.display());
+            }
+        }
+        Command::Submit {
+            year,
+            day,
+            part,
+            answer,
+            site,
+        } => {
+            println!("{}", site.client()?.submit(year, day, part, &answer)?);
+        }
+    }
+    Ok(())
+}
+
+fn parts(part: Option<Part>) -> Vec<Part> {
+    match part {
+        Some(part) => vec![part],
+        None => Part::ALL.to_vec(),
+    }
+}
+
+/// Which days and parts a command covers, `None` meaning all of them.
+pub struct Filter {
+    pub year: Option<u16>,
+    pub day: Option<u8>,
+    pub part: Option<Part>,
+}
+
+impl Filter {
+    fn matches(&self, puzzle: &dyn Puzzle) -> bool {
+        self.year.is_none_or(|y| y == puzzle.year()) && self.day.is_none_or(|d| d == puzzle.day())
+    }
+
+    fn parts(&self) -> Vec<Part> {
+        parts(self.part)
+    }
+}
+
+fn list_variants(puzzle: &dyn Puzzle) {
+    println!("parsers: {}", puzzle.parsers().join(", "));
+    for part in Part::ALL {
+        println!("part {part} solvers: {}", puzzle.solvers(part).join(", "));
+    }
+}
+
+/// Runs every variant against `input`, returning whether they all gave the
+/// same answer for each part.
+fn check_variants(puzzle: &dyn Puzzle, input: &str) -> Result<bool> {
+    let mut agree = true;
+    for part in Part::ALL {
+        let answers = puzzle.run_all(input, part)?;
+        let mut expected = None;
+        for variant in answers {
+            let answer = match variant.answer {
+                Ok(answer) => answer,
+                Err(err) => {
+                    println!("part {part} {}/{}: {err}", variant.parser, variant.solver);
+                    agree = false;
+                    continue;
+                }
+            };
+            println!(
+                "part {part} {}/{}: {answer}",
+                variant.parser, variant.solver
+            );
+            match &expected {
+                None => expected = Some(answer),
+                Some(expected) if *expected != answer => agree = false,
+                Some(_) => {}
+            }
+        }
+    }
+    Ok(agree)
+}
+
+/// Extracts the examples from every matching day's puzzle description,
+/// leaving any fixture that already exists alone unless `force` is set.
+fn write_fixtures(
+    registry: &Registry,
+    filter: &Filter,
+    puzzles: &Path,
+    fixtures: &Path,
+    force: bool,
+) -> Result<()> {
+    for puzzle in registry.iter().filter(|p| filter.matches(*p)) {
+        let (year, day) = (puzzle.year(), puzzle.day());
+        let dir = puzzles.join(year.to_string());
+        let Some(description) = ["md", "html"]
+            .iter()
+            .map(|ext| dir.join(format!("day{day:02}.{ext}")))
+            .find(|path| path.exists())
+        else {
+            continue;
+        };
+        if !force && Fixture::path(fixtures, year, day).exists() {
+            println!("{year} day {day}: fixture exists, skipping");
+            continue;
+        }
+        let fixture = Fixture::extract(&fs::read_to_string(&description)?);
+        fixture.save(fixtures, year, day)?;
+        for part in Part::ALL {
+            match fixture.example(part) {
+                Some(example) => println!("{year} day {day} part {part}: {example}"),
+                None => println!("{year} day {day} part {part}: no example found"),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Reads a day's input from `inputs`, falling back to any input compiled into
+/// the binary when the inputs directory does not have it.
+fn read_input(inputs: &InputSource, year: u16, day: u8) -> aoc_core::Result<String> {
+    let missing = match inputs.read(year, day) {
+        Err(err @ Error::MissingInput { .. }) if matches!(inputs, InputSource::Dir(_)) => err,
+        read => return read,
+    };
+    embedded_input(year, day).map(str::to_string).ok_or(missing)
+}
+
+#[cfg(feature = "embedded-inputs")]
+fn embedded_input(year: u16, day: u8) -> Option<&'static str> {
+    match year {
+        2022 => year2022::embedded_input(day),
+        _ => None,
+    }
+}
+
+#[cfg(not(feature = "embedded-inputs"))]
+fn embedded_input(_year: u16, _day: u8) -> Option<&'static str> {
+    None
+}
+
+fn registry() -> Registry {
+    let mut registry = Registry::new();
+    year2020::register(&mut registry);
+    year2021::register(&mut registry);
+    year2022::register(&mut registry);
+    year2023::register(&mut registry);
+    registry
+}
+
+#[derive(Parser)]
+#[command(about = "Advent of Code solutions for every year")]
+struct Cli {
+    /// Directory of puzzle inputs laid out as `YEAR/dayNN.txt`
+    #[arg(
+        long,
+        global = true,
+        env = INPUT_DIR_VAR,
+        default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/../inputs")
+    )]
+    input_dir: PathBuf,
+    /// Use the inputs in `profiles/NAME` of the input directory, so several
+    /// people's inputs can sit side by side
+    #[arg(long, global = true, env = PROFILE_VAR)]
+    profile: Option<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run a solution against its puzzle input
+    Run {
+        #[arg(short, long)]
+        year: u16,
+        #[arg(short, long)]
+        day: u8,
+        /// Part to run, runs both parts when omitted
+        #[arg(short, long, value_parser = parse_part)]
+        part: Option<Part>,
+        /// Named parser to read the input with
+        #[arg(long, default_value = DEFAULT)]
+        parser: String,
+        /// Named solver to run
+        #[arg(short, long, default_value = DEFAULT)]
+        variant: String,
+        /// Read the input from this file instead, or stdin when `-`
+        #[arg(short, long)]
+        input: Option<String>,
+    },
+    /// Count 2020 day 4's valid passports under a different set of rules
+    Passports {
+        /// File of rules in the format of `year2020/src/schema/day04.txt`
+        rules: PathBuf,
+        /// Read the input from this file instead, or stdin when `-`
+        #[arg(short, long)]
+        input: Option<String>,
+    },
+    /// List a day's alternative parsers and solvers
+    Variants {
+        year: u16,
+        day: u8,
+        /// Run every variant against the puzzle input and fail if any disagree
+        #[arg(long)]
+        check: bool,
+        /// Read the input from this file instead, or stdin when `-`
+        #[arg(short, long)]
+        input: Option<String>,
+    },
+    /// Time parsing and solving every day and variant
+    Bench {
+        /// Only benchmark this year
+        #[arg(short, long)]
+        year: Option<u16>,
+        /// Only benchmark this day
+        #[arg(short, long)]
+        day: Option<u8>,
+        /// Only benchmark this part
+        #[arg(short, long, value_parser = parse_part)]
+        part: Option<Part>,
+        /// Milliseconds to run each step untimed before sampling
+        #[arg(long, default_value_t = 100)]
+        warmup: u64,
+        /// Number of timed runs of each step
+        #[arg(short, long, default_value_t = 20)]
+        samples: usize,
+        /// Save the results as JSON, to use later as a baseline
+        #[arg(long)]
+        json: Option<PathBuf>,
+        /// Compare the results to a baseline saved with `--json`, failing if
+        /// anything got slower
+        #[arg(long)]
+        compare: Option<PathBuf>,
+        /// Percent a median may grow past the baseline before it counts as slower
+        #[arg(long, default_value_t = 10.0)]
+        threshold: f64,
+    },
+    /// Check every solved day against its known answers
+    Verify {
+        /// Only verify this year
+        #[arg(short, long)]
+        year: Option<u16>,
+        /// Only verify this day
+        #[arg(short, long)]
+        day: Option<u8>,
+        /// Only verify this part
+        #[arg(short, long, value_parser = parse_part)]
+        part: Option<Part>,
+        /// File of known answers
+        #[arg(long, default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/../answers.toml"))]
+        answers: PathBuf,
+        /// Save answers for parts that do not have one yet
+        #[arg(long)]
+        record: bool,
+    },
+    /// Download a day's input and puzzle description
+    Fetch {
+        #[arg(short, long)]
+        year: u16,
+        #[arg(short, long)]
+        day: u8,
+        /// Directory of puzzle descriptions laid out as `YEAR/dayNN.html`
+        #[arg(long, default_value = PUZZLES_DIR)]
+        puzzles: PathBuf,
+        /// Download the description again, to pick up part two once part one
+        /// is solved
+        #[arg(long)]
+        refresh: bool,
+        /// Overwrite files that already exist
+        #[arg(long)]
+        force: bool,
+        #[command(flatten)]
+        site: Site,
+    },
+    /// Submit an answer
+    Submit {
+        #[arg(short, long)]
+        year: u16,
+        #[arg(short, long)]
+        day: u8,
+        #[arg(short, long, value_parser = parse_part)]
+        part: Part,
+        answer: String,
+        #[command(flatten)]
+        site: Site,
+    },
+    /// Write test fixtures from the examples in the puzzle descriptions
+    Fixtures {
+        /// Only write fixtures for this year
+        #[arg(short, long)]
+        year: Option<u16>,
+        /// Only write fixtures for this day
+        #[arg(short, long)]
+        day: Option<u8>,
+        /// Directory of puzzle descriptions laid out as `YEAR/dayNN.md`
+        #[arg(long, default_value = PUZZLES_DIR)]
+        puzzles: PathBuf,
+        /// Directory to write fixtures to
+        #[arg(long, default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/../fixtures"))]
+        fixtures: PathBuf,
+        /// Overwrite fixtures that already exist
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+/// Where and how to reach the Advent of Code site.
+#[derive(Args)]
+struct Site {
+    /// Site to talk to, swap out to test against a stand-in server
+    #[arg(long, env = fetch::BASE_URL_VAR, default_value = fetch::BASE_URL)]
+    base_url: String,
+    /// Directory to cache downloaded pages in
+    #[arg(long, default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/../.cache/aoc"))]
+    cache: PathBuf,
+    /// Seconds to leave between requests to the site
+    #[arg(long, default_value_t = 5)]
+    min_interval: u64,
+}
+
+impl Site {
+    fn client(&self) -> Result<fetch::Client> {
+        Ok(fetch::Client::new(
+            &self.base_url,
+            fetch::session()?,
+            self.cache.clone(),
+            Duration::from_secs(self.min_interval),
+        ))
+    }
+}
+
+const PUZZLES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../puzzles");
+
+fn parse_part(part: &str) -> Result<Part, String> {
+    let part: u8 = part
+        .parse()
+        .map_err(|_| format!("`{part}` is not a part"))?;
+    Part::try_from(part)
+}
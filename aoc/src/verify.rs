@@ -0,0 +1,122 @@
+use crate::{answers::Answers, Filter};
+use aoc_core::{Error, InputSource, Part, Registry};
+use std::fmt::Write;
+
+pub enum Status {
+    Pass,
+    Fail {
+        expected: String,
+    },
+    /// Solved but nothing recorded to check it against.
+    Missing,
+    Error(String),
+}
+
+/// Outcome of running one part against its real input.
+pub struct Check {
+    pub year: u16,
+    pub day: u8,
+    pub part: Part,
+    pub answer: Option<String>,
+    pub status: Status,
+}
+
+impl Check {
+    pub fn failed(&self) -> bool {
+        matches!(self.status, Status::Fail { .. } | Status::Error(_))
+    }
+}
+
+/// Runs the default parser and solver of every matching part, skipping parts
+/// that have not been solved.
+pub fn run(
+    registry: &Registry,
+    filter: &Filter,
+    answers: &Answers,
+    inputs: &InputSource,
+) -> Vec<Check> {
+    let mut checks = Vec::new();
+    for puzzle in registry.iter().filter(|p| filter.matches(*p)) {
+        let (year, day) = (puzzle.year(), puzzle.day());
+        let input = crate::read_input(inputs, year, day);
+        for part in filter.parts() {
+            let (answer, status) = match input.as_ref().map(|i| puzzle.run(i, part)) {
+                Err(err) => (None, Status::Error(err.to_string())),
+                Ok(Err(Error::Unsolved(_))) => continue,
+                Ok(Err(err)) => (None, Status::Error(err.to_string())),
+                Ok(Ok(answer)) => {
+                    let status = match answers.get(year, day, part) {
+                        None => Status::Missing,
+                        Some(expected) if expected == answer => Status::Pass,
+                        Some(expected) => Status::Fail {
+                            expected: expected.to_string(),
+                        },
+                    };
+                    (Some(answer), status)
+                }
+            };
+            checks.push(Check {
+                year,
+                day,
+                part,
+                answer,
+                status,
+            });
+        }
+    }
+    checks
+}
+
+/// Saves the answer of every check that had nothing recorded.
+pub fn record(checks: &[Check], answers: &mut Answers) {
+    for check in checks {
+        if let (Status::Missing, Some(answer)) = (&check.status, &check.answer) {
+            answers.insert(check.year, check.day, check.part, answer.clone());
+        }
+    }
+}
+
+pub fn table(checks: &[Check]) -> String {
+    let mut out = format!(
+        "{:<4} {:>3} {:>4}  {:<7}  {}\n",
+        "year", "day", "part", "result", "detail"
+    );
+    let mut counts = [0; 4];
+    for check in checks {
+        let (i, result, detail) = match &check.status {
+            Status::Pass => (0, "pass", String::new()),
+            Status::Fail { expected } => (
+                1,
+                "FAIL",
+                format!(
+                    "got {} expected {expected}",
+                    check.answer.as_deref().unwrap_or_default()
+                ),
+            ),
+            Status::Missing => (
+                2,
+                "missing",
+                format!("got {}", check.answer.as_deref().unwrap_or_default()),
+            ),
+            Status::Error(err) => (3, "ERROR", err.clone()),
+        };
+        counts[i] += 1;
+        writeln!(
+            out,
+            "{:<4} {:>3} {:>4}  {:<7}  {}",
+            check.year,
+            check.day,
+            check.part,
+            result,
+            detail.replace('\n', " ")
+        )
+        .unwrap();
+    }
+    let [pass, fail, missing, error] = counts;
+    writeln!(
+        out,
+        "\n{pass} passed, {fail} failed, {missing} missing, {error} errored"
+    )
+    .unwrap();
+    out
+}
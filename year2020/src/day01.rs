@@ -1,11 +1,9 @@
 /// https://adventofcode.com/2020/day/1
+use aoc_core::{Error, Registry, Result, Solution, Variants};
+use std::num::ParseIntError;
 
-#[aoc_generator(day1)]
-pub fn parse_input(input: &str) -> Vec<usize> {
-    input
-        .lines()
-        .map(|l| l.parse().expect("positive integers only"))
-        .collect()
+pub fn parse_input(input: &str) -> Result<Vec<usize>, ParseIntError> {
+    input.lines().map(|l| l.parse()).collect()
 }
 
 fn find_pair_sum(report: &[usize], sum: usize) -> Option<(usize, usize)> {
@@ -33,35 +31,22 @@ fn find_pair_sum_search(report: &[usize], sum: usize) -> Option<(usize, usize)>
     None
 }
 
-#[aoc(day1, part1, unsorted)]
 fn part1_unsorted(report: &[usize]) -> Option<usize> {
-    match find_pair_sum(report, 2020) {
-        Some((left, right)) => Some(left * right),
-        None => None,
-    }
+    find_pair_sum(report, 2020).map(|(left, right)| left * right)
 }
 
-#[aoc(day1, part1, sorted)]
 fn part1_sorted(report: &[usize]) -> Option<usize> {
     let mut report = report.to_owned();
     report.sort_unstable();
-    match find_pair_sum(&report, 2020) {
-        Some((left, right)) => Some(left * right),
-        None => None,
-    }
+    find_pair_sum(&report, 2020).map(|(left, right)| left * right)
 }
 
-#[aoc(day1, part1, sorted_search)]
 fn part1_sorted_search(report: &[usize]) -> Option<usize> {
     let mut report = report.to_owned();
     report.sort_unstable();
-    match find_pair_sum_search(&report, 2020) {
-        Some((left, right)) => Some(left * right),
-        None => None,
-    }
+    find_pair_sum_search(&report, 2020).map(|(left, right)| left * right)
 }
 
-#[aoc(day1, part1, sorted_ends)]
 fn part1_sorted_ends(report: &[usize]) -> Option<usize> {
     let mut report = report.to_owned();
     report.sort_unstable();
@@ -88,7 +73,6 @@ fn part1_sorted_ends(report: &[usize]) -> Option<usize> {
     }
 }
 
-#[aoc(day1, part2, unsorted)]
 fn part2_unsorted(report: &[usize]) -> Option<usize> {
     for i in 0..(report.len() - 2) {
         for j in (i + 1)..(report.len() - 1) {
@@ -105,7 +89,6 @@ fn part2_unsorted(report: &[usize]) -> Option<usize> {
     None
 }
 
-#[aoc(day1, part2, sorted)]
 fn part2_sorted(report: &[usize]) -> Option<usize> {
     let mut report = report.to_owned();
     report.sort_unstable();
@@ -118,7 +101,6 @@ fn part2_sorted(report: &[usize]) -> Option<usize> {
     None
 }
 
-#[aoc(day1, part2, sorted_search)]
 fn part2_sorted_search(report: &[usize]) -> Option<usize> {
     let mut report = report.to_owned();
     report.sort_unstable();
@@ -131,6 +113,44 @@ fn part2_sorted_search(report: &[usize]) -> Option<usize> {
     None
 }
 
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add_variants(
+        Variants::<Day01>::new()
+            .part_one("sorted", |r| part1_sorted(r).ok_or(Error::NoAnswer))
+            .part_one("sorted_search", |r| {
+                part1_sorted_search(r).ok_or(Error::NoAnswer)
+            })
+            .part_one("sorted_ends", |r| {
+                part1_sorted_ends(r).ok_or(Error::NoAnswer)
+            })
+            .part_two("sorted", |r| part2_sorted(r).ok_or(Error::NoAnswer))
+            .part_two("sorted_search", |r| {
+                part2_sorted_search(r).ok_or(Error::NoAnswer)
+            }),
+    );
+}
+
+struct Day01;
+
+impl Solution for Day01 {
+    const YEAR: u16 = 2020;
+    const DAY: u8 = 1;
+    type Input<'i> = Vec<usize>;
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse_input(input.trim_end_matches('\n')).map_err(Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        part1_unsorted(input).ok_or(Error::NoAnswer)
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        part2_unsorted(input).ok_or(Error::NoAnswer)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -144,12 +164,15 @@ mod test {
 
     #[test]
     fn parsing_input() {
-        assert_eq!(parse_input(REPORT), vec![1721, 979, 366, 299, 675, 1456])
+        assert_eq!(
+            parse_input(REPORT).unwrap(),
+            vec![1721, 979, 366, 299, 675, 1456]
+        )
     }
 
     #[test]
     fn running_part1() {
-        let report = parse_input(REPORT);
+        let report = parse_input(REPORT).unwrap();
         assert_eq!(part1_unsorted(&report), Some(514579));
         assert_eq!(part1_sorted(&report), Some(514579));
         assert_eq!(part1_sorted_search(&report), Some(514579));
@@ -158,7 +181,7 @@ mod test {
 
     #[test]
     fn running_part2() {
-        let report = parse_input(REPORT);
+        let report = parse_input(REPORT).unwrap();
         assert_eq!(part2_sorted(&report), Some(241861950));
         assert_eq!(part2_unsorted(&report), Some(241861950));
         assert_eq!(part2_sorted_search(&report), Some(241861950));
@@ -1,4 +1,5 @@
 /// https://adventofcode.com/2020/day/2
+use aoc_core::{Error, Registry, Result, Solution};
 use pest::Parser;
 use std::ops::Deref;
 
@@ -49,33 +50,25 @@ impl Deref for Password {
 #[grammar = "pest/day02.pest"]
 struct InputParser;
 
-#[aoc_generator(day2)]
-fn parse_input(input: &str) -> Vec<Password> {
+fn parse_input(input: &str) -> Result<Vec<Password>, String> {
     InputParser::parse(Rule::file, input)
-        .expect("could not parse input")
+        .map_err(|e| e.to_string())?
         .filter(|r| r.as_rule() == Rule::validation)
         .map(|v| {
+            // The grammar guarantees every piece is there.
             let mut validation = v.into_inner();
-            let req1 = validation
-                .next()
-                .unwrap()
-                .as_str()
-                .parse::<usize>()
-                .unwrap();
-            let req2 = validation
-                .next()
-                .unwrap()
-                .as_str()
-                .parse::<usize>()
-                .unwrap();
+            let mut number = || {
+                let n = validation.next().unwrap().as_str();
+                n.parse::<usize>().map_err(|e| format!("`{n}`: {e}"))
+            };
+            let (req1, req2) = (number()?, number()?);
             let character = validation.next().unwrap().as_str().chars().next().unwrap();
             let password = validation.next().unwrap().as_str().as_bytes().to_vec();
-            Password::new(req1, req2, character, password)
+            Ok(Password::new(req1, req2, character, password))
         })
         .collect()
 }
 
-#[aoc(day2, part1)]
 fn part1(passwords: &[Password]) -> usize {
     passwords.iter().fold(0, |valid, password| {
         let count = password.iter().fold(0, |count, c| {
@@ -91,7 +84,6 @@ fn part1(passwords: &[Password]) -> usize {
     })
 }
 
-#[aoc(day2, part2)]
 fn part2(passwords: &[Password]) -> usize {
     passwords.iter().fold(0, |valid, password| {
         let v1 = password.get(password.policy.req1 - 1);
@@ -129,6 +121,31 @@ fn part2(passwords: &[Password]) -> usize {
     })
 }
 
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day02>();
+}
+
+struct Day02;
+
+impl Solution for Day02 {
+    const YEAR: u16 = 2020;
+    const DAY: u8 = 2;
+    type Input<'i> = Vec<Password>;
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse_input(input.trim_end_matches('\n')).map_err(Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(part1(input))
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(part2(input))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -139,7 +156,7 @@ mod test {
     #[test]
     fn parsing_input() {
         assert_eq!(
-            parse_input(PASSWORDS),
+            parse_input(PASSWORDS).unwrap(),
             vec![
                 Password::new(1, 3, 'a', b"abcde".to_vec()),
                 Password::new(1, 3, 'b', b"cdefg".to_vec()),
@@ -150,13 +167,13 @@ mod test {
 
     #[test]
     fn running_part1() {
-        let passwords = parse_input(PASSWORDS);
+        let passwords = parse_input(PASSWORDS).unwrap();
         assert_eq!(part1(&passwords), 2);
     }
 
     #[test]
     fn running_part2() {
-        let passwords = parse_input(PASSWORDS);
+        let passwords = parse_input(PASSWORDS).unwrap();
         assert_eq!(part2(&passwords), 1);
     }
 }
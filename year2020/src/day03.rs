@@ -1,96 +1,62 @@
 /// https://adventofcode.com/2020/day/3
+use aoc_common::{Grid, GridError, Wrap};
+use aoc_core::{Error, Registry, Result, Solution};
 
-#[derive(Debug, PartialEq, Eq)]
-struct Map {
-    rows: usize,
-    cols: usize,
-    data: Vec<bool>,
-}
-
-impl Map {
-    fn iter(&self, down: usize, right: usize) -> MapIter<'_> {
-        MapIter::new(self, down, right)
-    }
-    fn get_from_row_col(&self, row: usize, col: usize) -> Option<&bool> {
-        self.data.get(row * self.cols + col)
-    }
+fn parse_input(input: &str) -> Result<Grid<bool>, GridError> {
+    Grid::parse_with(input, |c| match c {
+        '.' => Ok(false),
+        '#' => Ok(true),
+        _ => Err("only . or # are allowed"),
+    })
 }
 
-struct MapIter<'a> {
-    map: &'a Map,
-    down: usize,
-    right: usize,
-    next_row: usize, // zero based
-    next_col: usize, // zero based
+/// Counts the trees hit going from the top left to the bottom, the map
+/// repeating forever to the right.
+fn trees(map: &Grid<bool>, down: usize, right: usize) -> usize {
+    let start = (0, 0);
+    std::iter::once(start)
+        .chain(map.ray(start, (right as isize, down as isize), Wrap::Horizontal))
+        .filter(|&pos| map[pos])
+        .count()
 }
 
-impl<'a> MapIter<'a> {
-    fn new(map: &'a Map, down: usize, right: usize) -> Self {
-        MapIter {
-            map,
-            down,
-            right,
-            next_row: 0,
-            next_col: 0,
-        }
-    }
+fn part1(map: &Grid<bool>) -> usize {
+    trees(map, 1, 3)
 }
 
-impl<'a> Iterator for MapIter<'a> {
-    type Item = &'a bool;
+// (down, right)
+static SLOPES: [(usize, usize); 5] = [(1, 1), (1, 3), (1, 5), (1, 7), (2, 1)];
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.next_row > self.map.rows - 1 {
-            return None;
-        }
-        let tree = self.map.get_from_row_col(self.next_row, self.next_col);
-        self.next_row += self.down;
-        self.next_col = (self.next_col + self.right) % self.map.cols; // map is infinite to the right repeating
-        tree
-    }
+fn part2(map: &Grid<bool>) -> usize {
+    SLOPES
+        .iter()
+        .map(|&(down, right)| trees(map, down, right))
+        .product()
 }
 
-#[aoc_generator(day3)]
-fn parse_input(input: &str) -> Map {
-    let data = input
-        .lines()
-        .map(|l| {
-            l.as_bytes()
-                .iter()
-                .map(|b| match b {
-                    b'.' => false,
-                    b'#' => true,
-                    _ => unreachable!("only matches on . or #"),
-                })
-                .collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>();
-    Map {
-        rows: data.len(),
-        cols: data[0].len(),
-        data: data.into_iter().flatten().collect(),
-    }
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day03>();
 }
 
-#[aoc(day3, part1)]
-fn part1(map: &Map) -> usize {
-    map.iter(1, 3).fold(0, |count, tree| match tree {
-        true => count + 1,
-        false => count,
-    })
-}
+struct Day03;
 
-// (down, right)
-static SLOPES: [(usize, usize); 5] = [(1, 1), (1, 3), (1, 5), (1, 7), (2, 1)];
+impl Solution for Day03 {
+    const YEAR: u16 = 2020;
+    const DAY: u8 = 3;
+    type Input<'i> = Grid<bool>;
+    type Output = usize;
 
-#[aoc(day3, part2)]
-fn part2(map: &Map) -> usize {
-    SLOPES.iter().fold(1, |mult, (down, right)| {
-        mult * map.iter(*down, *right).fold(0, |count, tree| match tree {
-            true => count + 1,
-            false => count,
-        })
-    })
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse_input(input).map_err(Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(part1(input))
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(part2(input))
+    }
 }
 
 #[cfg(test)]
@@ -111,11 +77,11 @@ mod test {
     #[test]
     fn parsing_input() {
         assert_eq!(
-            parse_input(MAP),
-            Map {
-                rows: 11,
-                cols: 11,
-                data: vec![
+            parse_input(MAP).unwrap(),
+            Grid::from_vec(
+                11,
+                11,
+                vec![
                     false, false, true, true, false, false, false, false, false, false, false,
                     true, false, false, false, true, false, false, false, true, false, false,
                     false, true, false, false, false, false, true, false, false, true, false,
@@ -128,19 +94,20 @@ mod test {
                     false, false, false, false, true, false, true, false, false, true, false,
                     false, false, true, false, true
                 ],
-            }
+            )
+            .unwrap()
         );
     }
 
     #[test]
     fn running_part1() {
-        let map = parse_input(MAP);
+        let map = parse_input(MAP).unwrap();
         assert_eq!(part1(&map), 7);
     }
 
     #[test]
     fn running_part2() {
-        let map = parse_input(MAP);
+        let map = parse_input(MAP).unwrap();
         assert_eq!(part2(&map), 336);
     }
 }
@@ -1,9 +1,20 @@
 /// https://adventofcode.com/2020/day/4
+use aoc_core::{Error, Registry, Result, Solution};
 use pest::Parser;
-use std::{collections::HashMap, str::FromStr};
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    fs,
+    ops::RangeInclusive,
+    path::Path,
+    str::FromStr,
+};
 
-#[derive(Eq, PartialEq, Hash)]
-enum PassportField {
+/// The rules the puzzle gives for part 2.
+static RULES: &str = include_str!("schema/day04.txt");
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum PassportField {
     BirthYear,
     IssueYear,
     ExpirationYear,
@@ -32,106 +43,341 @@ impl FromStr for PassportField {
     }
 }
 
+impl Display for PassportField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let key = match self {
+            PassportField::BirthYear => "byr",
+            PassportField::IssueYear => "iyr",
+            PassportField::ExpirationYear => "eyr",
+            PassportField::Height => "hgt",
+            PassportField::HairColor => "hcl",
+            PassportField::EyeColor => "ecl",
+            PassportField::PassportId => "pid",
+            PassportField::CountryId => "cid",
+        };
+        write!(f, "{key}")
+    }
+}
+
+/// The fields a passport has, as they were written.
 #[derive(Debug, PartialEq, Eq)]
-struct Passport {
-    birth_year: Option<usize>,
-    issue_year: Option<usize>,
-    expiration_year: Option<usize>,
-    height: Option<String>,
-    hair_color: Option<String>,
-    eye_color: Option<String>,
-    passport_id: Option<String>,
-    country_id: Option<usize>,
+pub struct Passport(HashMap<PassportField, String>);
+
+/// What is wrong with a field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reason {
+    Missing,
+    NotANumber,
+    OutOfRange { min: u64, max: u64 },
+    UnknownUnit,
+    NoMatch,
+    NotListed,
 }
 
-impl Passport {
-    fn is_valid(&self) -> bool {
-        // Must have all these fields
-        if self.birth_year.is_none()
-            || self.issue_year.is_none()
-            || self.expiration_year.is_none()
-            || self.height.is_none()
-            || self.hair_color.is_none()
-            || self.eye_color.is_none()
-            || self.passport_id.is_none()
-        {
-            return false;
+impl Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reason::Missing => write!(f, "is missing"),
+            Reason::NotANumber => write!(f, "is not a whole number"),
+            Reason::OutOfRange { min, max } => write!(f, "is not from {min} to {max}"),
+            Reason::UnknownUnit => write!(f, "does not end in a known unit"),
+            Reason::NoMatch => write!(f, "does not match the pattern"),
+            Reason::NotListed => write!(f, "is not one of the allowed values"),
         }
+    }
+}
 
-        let birth_year = self.birth_year.unwrap();
-        if birth_year < 1920 || birth_year > 2002 {
-            return false;
-        }
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub field: PassportField,
+    pub reason: Reason,
+}
 
-        let issue_year = self.issue_year.unwrap();
-        if issue_year < 2010 || issue_year > 2020 {
-            return false;
-        }
+impl Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.field, self.reason)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Atom {
+    Char(char),
+    Any,
+    Class(Vec<RangeInclusive<char>>),
+}
 
-        let expiration_year = self.expiration_year.unwrap();
-        if expiration_year < 2020 || expiration_year > 2030 {
-            return false;
+impl Atom {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Atom::Char(want) => c == *want,
+            Atom::Any => true,
+            Atom::Class(ranges) => ranges.iter().any(|r| r.contains(&c)),
         }
+    }
+}
+
+/// A cut down regular expression that has to match the whole value: single
+/// characters, `.` for any character, `[a-z_]` classes, `\d` for a digit and
+/// `\w` for a word character, each of which may repeat with `?`, `*`, `+`,
+/// `{n}` or `{n,m}`. Any other character that is not a letter or digit can
+/// be escaped with `\` to match it as it is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern(Vec<(Atom, usize, usize)>);
+
+impl FromStr for Pattern {
+    type Err = String;
 
-        let height = self.height.as_ref().unwrap();
-        if height.ends_with("in") {
-            if let Ok(h) = height.replace("in", "").parse::<usize>() {
-                if h < 59 || h > 76 {
-                    return false;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut items = Vec::new();
+        let mut chars = s.chars().peekable();
+        while let Some(c) = chars.next() {
+            let atom = match c {
+                '.' => Atom::Any,
+                '\\' => match chars.next().ok_or("nothing to escape at the end")? {
+                    'd' => Atom::Class(vec!['0'..='9']),
+                    'w' => Atom::Class(vec!['a'..='z', 'A'..='Z', '0'..='9', '_'..='_']),
+                    c if c.is_alphanumeric() => return Err(format!("`\\{c}` is not an escape")),
+                    c => Atom::Char(c),
+                },
+                '[' => {
+                    let mut ranges = Vec::new();
+                    loop {
+                        let start = match chars.next() {
+                            Some(']') => break,
+                            Some(c) => c,
+                            None => return Err("`[` is never closed".into()),
+                        };
+                        let end = match chars.next_if_eq(&'-') {
+                            Some(_) => match chars.next() {
+                                Some(']') => {
+                                    return Err(format!("`{start}-` has no end to its range"))
+                                }
+                                Some(end) if end < start => {
+                                    return Err(format!("`{start}-{end}` is a backwards range"))
+                                }
+                                Some(end) => end,
+                                None => return Err("`[` is never closed".into()),
+                            },
+                            None => start,
+                        };
+                        ranges.push(start..=end);
+                    }
+                    Atom::Class(ranges)
                 }
-            } else {
-                return false;
-            }
-        } else if height.ends_with("cm") {
-            if let Ok(h) = height.replace("cm", "").parse::<usize>() {
-                if h < 150 || h > 193 {
-                    return false;
+                '?' | '*' | '+' | '{' | ']' | '}' => {
+                    return Err(format!("`{c}` does not follow anything"))
                 }
-            } else {
-                return false;
-            }
-        } else {
-            return false;
+                c => Atom::Char(c),
+            };
+            let (min, max) = match chars.next_if(|c| "?*+{".contains(*c)) {
+                Some('?') => (0, 1),
+                Some('*') => (0, usize::MAX),
+                Some('+') => (1, usize::MAX),
+                Some(_) => {
+                    let mut counts = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => counts.push(c),
+                            None => return Err("`{` is never closed".into()),
+                        }
+                    }
+                    let count = |n: &str| {
+                        n.trim()
+                            .parse::<usize>()
+                            .map_err(|_| format!("`{{{counts}}}` is not a repeat count"))
+                    };
+                    let (min, max) = match counts.split_once(',') {
+                        Some((min, max)) => (count(min)?, count(max)?),
+                        None => (count(&counts)?, count(&counts)?),
+                    };
+                    if min > max {
+                        return Err(format!("`{{{counts}}}` allows fewer repeats than it needs"));
+                    }
+                    (min, max)
+                }
+                None => (1, 1),
+            };
+            items.push((atom, min, max));
         }
+        Ok(Pattern(items))
+    }
+}
 
-        let hair_color = self.hair_color.as_ref().unwrap();
-        if hair_color.starts_with('#') {
-            let hair_color = hair_color.replace("#", "");
-            if hair_color.len() != 6 {
-                return false;
-            }
-            if u8::from_str_radix(&hair_color[0..2], 16).is_err()
-                || u8::from_str_radix(&hair_color[2..4], 16).is_err()
-                || u8::from_str_radix(&hair_color[4..6], 16).is_err()
-            {
-                return false;
+impl Pattern {
+    pub fn matches(&self, value: &str) -> bool {
+        self.match_from(0, &value.chars().collect::<Vec<_>>())
+    }
+
+    fn match_from(&self, item: usize, rest: &[char]) -> bool {
+        let (atom, min, max) = match self.0.get(item) {
+            Some(item) => item,
+            None => return rest.is_empty(),
+        };
+        let run = rest
+            .iter()
+            .take(*max)
+            .take_while(|&&c| atom.matches(c))
+            .count();
+        (*min..=run)
+            .rev()
+            .any(|n| self.match_from(item + 1, &rest[n..]))
+    }
+}
+
+/// One thing a field's value has to satisfy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Check {
+    /// A whole number in the range.
+    Range(RangeInclusive<u64>),
+    /// A whole number followed by one of the units, in that unit's range.
+    Units(Vec<(String, RangeInclusive<u64>)>),
+    Pattern(Pattern),
+    OneOf(Vec<String>),
+}
+
+impl Check {
+    fn check(&self, value: &str) -> Result<(), Reason> {
+        let in_range = |number: &str, range: &RangeInclusive<u64>| {
+            let number: u64 = number.parse().map_err(|_| Reason::NotANumber)?;
+            match range.contains(&number) {
+                true => Ok(()),
+                false => Err(Reason::OutOfRange {
+                    min: *range.start(),
+                    max: *range.end(),
+                }),
             }
-        } else {
-            return false;
+        };
+        match self {
+            Check::Range(range) => in_range(value, range),
+            Check::Units(units) => units
+                .iter()
+                .find_map(|(unit, range)| {
+                    value
+                        .strip_suffix(unit.as_str())
+                        .map(|number| in_range(number, range))
+                })
+                .unwrap_or(Err(Reason::UnknownUnit)),
+            Check::Pattern(pattern) if pattern.matches(value) => Ok(()),
+            Check::Pattern(_) => Err(Reason::NoMatch),
+            Check::OneOf(words) if words.iter().any(|w| w == value) => Ok(()),
+            Check::OneOf(_) => Err(Reason::NotListed),
         }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct FieldRules {
+    required: bool,
+    checks: Vec<Check>,
+}
 
-        let eye_color = self.eye_color.as_ref().unwrap();
-        if eye_color.len() == 3 {
-            let valid_eye = matches!(
-                eye_color.as_ref(),
-                "amb" | "blu" | "brn" | "gry" | "grn" | "hzl" | "oth"
-            );
-            if !valid_eye {
-                return false;
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaError {
+    pub line: usize,
+    pub reason: String,
+}
+
+impl Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+/// The rules for each passport field, written one field to a line. See
+/// `schema/day04.txt` for the format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schema(Vec<(PassportField, FieldRules)>);
+
+fn parse_range(range: &str) -> Result<RangeInclusive<u64>, String> {
+    let bad = || format!("`{range}` is not a range like `1..=9`");
+    let (min, max) = range.trim().split_once("..=").ok_or_else(bad)?;
+    Ok(min.parse().map_err(|_| bad())?..=max.parse().map_err(|_| bad())?)
+}
+
+fn parse_check(rule: &str) -> Result<Option<Check>, String> {
+    let (name, args) = rule.split_once(' ').unwrap_or((rule, ""));
+    Ok(Some(match name {
+        "required" | "optional" => return Ok(None),
+        "range" => Check::Range(parse_range(args)?),
+        "units" => Check::Units(
+            args.split(',')
+                .map(|unit| {
+                    let (unit, range) = unit.trim().split_once(' ').unwrap_or((unit, ""));
+                    Ok((unit.to_string(), parse_range(range)?))
+                })
+                .collect::<Result<_, String>>()?,
+        ),
+        "pattern" => Check::Pattern(args.parse()?),
+        "one" => match args.strip_prefix("of ") {
+            Some(words) => Check::OneOf(words.split_whitespace().map(String::from).collect()),
+            None => return Err(format!("`{rule}` should be `one of ...`")),
+        },
+        _ => return Err(format!("`{name}` is not a rule")),
+    }))
+}
+
+impl FromStr for Schema {
+    type Err = SchemaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields: Vec<(PassportField, FieldRules)> = Vec::new();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
             }
-        } else {
-            return false;
+            let error = |reason: String| SchemaError {
+                line: i + 1,
+                reason,
+            };
+            let (key, rules) = line
+                .split_once(':')
+                .ok_or_else(|| error(format!("`{line}` has no `:` after the key")))?;
+            let field: PassportField = key.trim().parse().map_err(|e: &str| error(e.into()))?;
+            if fields.iter().any(|(f, _)| *f == field) {
+                return Err(error(format!("`{field}` has rules already")));
+            }
+            let mut field_rules = FieldRules::default();
+            for rule in rules.split(';').map(str::trim).filter(|r| !r.is_empty()) {
+                field_rules.required |= rule == "required";
+                field_rules.checks.extend(parse_check(rule).map_err(error)?);
+            }
+            fields.push((field, field_rules));
         }
+        Ok(Schema(fields))
+    }
+}
+
+impl Schema {
+    /// Reads a different set of rules from a file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let failed = |err: &dyn Display| Error::parse(format!("{}: {err}", path.display()));
+        let rules = fs::read_to_string(path).map_err(|err| failed(&err))?;
+        rules.parse().map_err(|err: SchemaError| failed(&err))
+    }
 
-        let passport_id = self.passport_id.as_ref().unwrap();
-        if passport_id.len() == 9 {
-            if passport_id.parse::<usize>().is_err() {
-                return false;
+    /// Everything wrong with the passport, in the order the rules list the
+    /// fields.
+    pub fn violations(&self, passport: &Passport) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        for (field, rules) in &self.0 {
+            match passport.0.get(field) {
+                None if rules.required => violations.push(Violation {
+                    field: *field,
+                    reason: Reason::Missing,
+                }),
+                None => {}
+                Some(value) => violations.extend(rules.checks.iter().filter_map(|check| {
+                    check.check(value).err().map(|reason| Violation {
+                        field: *field,
+                        reason,
+                    })
+                })),
             }
-        } else {
-            return false;
         }
-        true
+        violations
     }
 }
 
@@ -139,77 +385,80 @@ impl Passport {
 #[grammar = "pest/day04.pest"]
 struct InputParser;
 
-#[aoc_generator(day4, nom)]
-fn parse_input(input: &str) -> Vec<Passport> {
+fn parse_input(input: &str) -> Result<Vec<Passport>, String> {
     InputParser::parse(Rule::file, input)
-        .expect("could not parse input")
+        .map_err(|e| e.to_string())?
         .filter(|r| r.as_rule() == Rule::passport)
         .map(|p| {
-            let fields: HashMap<_, _> = p
+            let fields = p
                 .into_inner()
                 .map(|f| {
+                    // The grammar guarantees every field has a key and a value.
                     let mut field = f.into_inner();
-                    let key = field
-                        .next()
-                        .unwrap()
-                        .as_str()
-                        .parse::<PassportField>()
-                        .unwrap();
-                    let value = field.next().unwrap().as_str();
-                    (key, value)
+                    let key = field.next().unwrap().as_str().parse::<PassportField>()?;
+                    let value = field.next().unwrap().as_str().to_string();
+                    Ok((key, value))
                 })
-                .collect();
-            Passport {
-                birth_year: fields
-                    .get(&PassportField::BirthYear)
-                    .map(|s| s.parse().unwrap()),
-                issue_year: fields
-                    .get(&PassportField::IssueYear)
-                    .map(|s| s.parse().unwrap()),
-                expiration_year: fields
-                    .get(&PassportField::ExpirationYear)
-                    .map(|s| s.parse().unwrap()),
-                height: fields.get(&PassportField::Height).map(|s| s.to_string()),
-                hair_color: fields.get(&PassportField::HairColor).map(|s| s.to_string()),
-                eye_color: fields.get(&PassportField::EyeColor).map(|s| s.to_string()),
-                passport_id: fields
-                    .get(&PassportField::PassportId)
-                    .map(|s| s.to_string()),
-                country_id: fields
-                    .get(&PassportField::CountryId)
-                    .map(|s| s.parse().unwrap()),
-            }
+                .collect::<Result<_, String>>()?;
+            Ok(Passport(fields))
         })
         .collect()
 }
 
-#[aoc(day4, part1)]
-fn part1(passports: &[Passport]) -> usize {
-    passports.iter().fold(0, |valid, passport| {
-        if passport.birth_year.is_some()
-            && passport.issue_year.is_some()
-            && passport.expiration_year.is_some()
-            && passport.height.is_some()
-            && passport.hair_color.is_some()
-            && passport.eye_color.is_some()
-            && passport.passport_id.is_some()
-        {
-            valid + 1
-        } else {
-            valid
-        }
-    })
+/// Passports with every required field, whatever is in them.
+fn part1((schema, passports): &(Schema, Vec<Passport>)) -> usize {
+    passports
+        .iter()
+        .filter(|passport| {
+            schema
+                .violations(passport)
+                .iter()
+                .all(|v| v.reason != Reason::Missing)
+        })
+        .count()
 }
 
-#[aoc(day4, part2)]
-fn part2(passports: &[Passport]) -> usize {
-    passports.iter().fold(0, |valid, passport| {
-        if passport.is_valid() {
-            valid + 1
-        } else {
-            valid
-        }
-    })
+fn part2((schema, passports): &(Schema, Vec<Passport>)) -> usize {
+    passports
+        .iter()
+        .filter(|passport| schema.violations(passport).is_empty())
+        .count()
+}
+
+/// Counts the passports with every required field and the passports that
+/// break none of the rules, checking them against `schema` rather than the
+/// puzzle's rules.
+pub fn count_valid(input: &str, schema: Schema) -> Result<(usize, usize)> {
+    let passports = parse_input(input.trim_end_matches('\n')).map_err(Error::parse)?;
+    let input = (schema, passports);
+    Ok((part1(&input), part2(&input)))
+}
+
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day04>();
+}
+
+struct Day04;
+
+impl Solution for Day04 {
+    const YEAR: u16 = 2020;
+    const DAY: u8 = 4;
+    type Input<'i> = (Schema, Vec<Passport>);
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        let schema = RULES.parse().map_err(Error::parse)?;
+        let passports = parse_input(input.trim_end_matches('\n')).map_err(Error::parse)?;
+        Ok((schema, passports))
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(part1(input))
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(part2(input))
+    }
 }
 
 #[cfg(test)]
@@ -256,64 +505,204 @@ eyr:2022
 
 iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719";
 
+    fn passport(fields: &[(&str, &str)]) -> Passport {
+        Passport(
+            fields
+                .iter()
+                .map(|(key, value)| (key.parse().unwrap(), value.to_string()))
+                .collect(),
+        )
+    }
+
+    fn with_rules(passports: &str) -> (Schema, Vec<Passport>) {
+        (RULES.parse().unwrap(), parse_input(passports).unwrap())
+    }
+
     #[test]
     fn parsing_input() {
         assert_eq!(
-            parse_input(PASSPORTS),
+            parse_input(PASSPORTS).unwrap(),
             vec![
-                Passport {
-                    birth_year: Some(1937),
-                    issue_year: Some(2017),
-                    expiration_year: Some(2020),
-                    height: Some("183cm".to_owned()),
-                    hair_color: Some("#fffffd".to_owned()),
-                    eye_color: Some("gry".to_owned()),
-                    passport_id: Some("860033327".to_owned()),
-                    country_id: Some(147)
-                },
-                Passport {
-                    birth_year: Some(1929),
-                    issue_year: Some(2013),
-                    expiration_year: Some(2023),
-                    height: None,
-                    hair_color: Some("#cfa07d".to_owned()),
-                    eye_color: Some("amb".to_owned()),
-                    passport_id: Some("028048884".to_owned()),
-                    country_id: Some(350)
-                },
-                Passport {
-                    birth_year: Some(1931),
-                    issue_year: Some(2013),
-                    expiration_year: Some(2024),
-                    height: Some("179cm".to_owned()),
-                    hair_color: Some("#ae17e1".to_owned()),
-                    eye_color: Some("brn".to_owned()),
-                    passport_id: Some("760753108".to_owned()),
-                    country_id: None
+                passport(&[
+                    ("byr", "1937"),
+                    ("iyr", "2017"),
+                    ("eyr", "2020"),
+                    ("hgt", "183cm"),
+                    ("hcl", "#fffffd"),
+                    ("ecl", "gry"),
+                    ("pid", "860033327"),
+                    ("cid", "147"),
+                ]),
+                passport(&[
+                    ("byr", "1929"),
+                    ("iyr", "2013"),
+                    ("eyr", "2023"),
+                    ("hcl", "#cfa07d"),
+                    ("ecl", "amb"),
+                    ("pid", "028048884"),
+                    ("cid", "350"),
+                ]),
+                passport(&[
+                    ("byr", "1931"),
+                    ("iyr", "2013"),
+                    ("eyr", "2024"),
+                    ("hgt", "179cm"),
+                    ("hcl", "#ae17e1"),
+                    ("ecl", "brn"),
+                    ("pid", "760753108"),
+                ]),
+                passport(&[
+                    ("iyr", "2011"),
+                    ("eyr", "2025"),
+                    ("hgt", "59in"),
+                    ("hcl", "#cfa07d"),
+                    ("ecl", "brn"),
+                    ("pid", "166559648"),
+                ]),
+            ]
+        )
+    }
+
+    #[test]
+    fn finding_violations() {
+        let schema: Schema = RULES.parse().unwrap();
+        let violation = |field: &str, reason| Violation {
+            field: field.parse().unwrap(),
+            reason,
+        };
+        let passports = parse_input(MORE_PASSPORTS).unwrap();
+        assert_eq!(
+            schema.violations(&passports[3]),
+            vec![
+                violation(
+                    "byr",
+                    Reason::OutOfRange {
+                        min: 1920,
+                        max: 2002
+                    }
+                ),
+                violation(
+                    "iyr",
+                    Reason::OutOfRange {
+                        min: 2010,
+                        max: 2020
+                    }
+                ),
+                violation(
+                    "eyr",
+                    Reason::OutOfRange {
+                        min: 2020,
+                        max: 2030
+                    }
+                ),
+                violation("hgt", Reason::OutOfRange { min: 150, max: 193 }),
+                violation("hcl", Reason::NoMatch),
+                violation("ecl", Reason::NotListed),
+                violation("pid", Reason::NoMatch),
+            ]
+        );
+        assert_eq!(
+            schema.violations(&passport(&[("hgt", "170"), ("pid", "0123456789")])),
+            vec![
+                violation("byr", Reason::Missing),
+                violation("iyr", Reason::Missing),
+                violation("eyr", Reason::Missing),
+                violation("hgt", Reason::UnknownUnit),
+                violation("hcl", Reason::Missing),
+                violation("ecl", Reason::Missing),
+                violation("pid", Reason::NoMatch),
+            ]
+        );
+        assert_eq!(
+            violation("hgt", Reason::OutOfRange { min: 59, max: 76 }).to_string(),
+            "hgt is not from 59 to 76"
+        );
+    }
+
+    #[test]
+    fn reading_schemas() {
+        let schema: Schema = "
+            # Only a short passport id is needed.
+            pid: required; pattern P\\-\\d{2,3}\\w?
+            hgt: units m 1..=3
+        "
+        .parse()
+        .unwrap();
+        assert!(schema.violations(&passport(&[("pid", "P-123")])).is_empty());
+        assert!(schema
+            .violations(&passport(&[("pid", "P-12_"), ("hgt", "2m")]))
+            .is_empty());
+        assert_eq!(
+            schema.violations(&passport(&[("pid", "P-d12"), ("hgt", "4m")])),
+            vec![
+                Violation {
+                    field: PassportField::PassportId,
+                    reason: Reason::NoMatch
                 },
-                Passport {
-                    birth_year: None,
-                    issue_year: Some(2011),
-                    expiration_year: Some(2025),
-                    height: Some("59in".to_owned()),
-                    hair_color: Some("#cfa07d".to_owned()),
-                    eye_color: Some("brn".to_owned()),
-                    passport_id: Some("166559648".to_owned()),
-                    country_id: None
+                Violation {
+                    field: PassportField::Height,
+                    reason: Reason::OutOfRange { min: 1, max: 3 }
                 },
             ]
-        )
+        );
+        assert_eq!(
+            "byr: range 1..=2\niyr: within 3".parse::<Schema>(),
+            Err(SchemaError {
+                line: 2,
+                reason: "`within` is not a rule".into()
+            })
+        );
+        assert_eq!(
+            "byr: pattern [0-9".parse::<Schema>(),
+            Err(SchemaError {
+                line: 1,
+                reason: "`[` is never closed".into()
+            })
+        );
+        assert_eq!(
+            "pid: pattern \\p{9}".parse::<Schema>(),
+            Err(SchemaError {
+                line: 1,
+                reason: "`\\p` is not an escape".into()
+            })
+        );
+        for (pattern, reason) in [
+            ("[a-]", "`a-` has no end to its range"),
+            ("[z-a]", "`z-a` is a backwards range"),
+            ("\\d{3,1}", "`{3,1}` allows fewer repeats than it needs"),
+            ("\\d{3", "`{` is never closed"),
+        ] {
+            assert_eq!(pattern.parse::<Pattern>(), Err(reason.to_string()));
+        }
+    }
+
+    #[test]
+    fn loading_schemas() {
+        let path = std::env::temp_dir().join(format!("aoc-2020-day04-{}.txt", std::process::id()));
+        fs::write(&path, "ecl: required; one of red\n").unwrap();
+        let schema = Schema::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        let passports = parse_input(PASSPORTS).unwrap();
+        assert_eq!(
+            schema.violations(&passports[0]),
+            vec![Violation {
+                field: PassportField::EyeColor,
+                reason: Reason::NotListed
+            }]
+        );
+        assert_eq!(count_valid(PASSPORTS, schema).unwrap(), (4, 0));
+        assert!(Schema::load(&path).is_err());
     }
 
     #[test]
     fn running_part1() {
-        let passports = parse_input(PASSPORTS);
+        let passports = with_rules(PASSPORTS);
         assert_eq!(part1(&passports), 2)
     }
 
     #[test]
     fn running_part2() {
-        let passports = parse_input(MORE_PASSPORTS);
+        let passports = with_rules(MORE_PASSPORTS);
         assert_eq!(part2(&passports), 4);
     }
 }
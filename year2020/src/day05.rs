@@ -1,4 +1,5 @@
 /// https://adventofcode.com/2020/day/5
+use aoc_core::{Error, Registry, Result, Solution};
 
 #[derive(Debug, Eq, PartialEq)]
 struct Seat {
@@ -16,32 +17,36 @@ impl Seat {
     }
 }
 
-#[aoc_generator(day5)]
-fn parse_input(input: &str) -> Vec<Seat> {
-    input.lines().map(|l| {
-        let line = l.as_bytes();
-        let row = line.iter().take(7).map(|b| match b {
-            b'B' => "1",
-            b'F' => "0",
-            _ => unreachable!()
-        }).collect::<String>();
-        let row = u8::from_str_radix(&row, 2).unwrap();
-        let col = line.iter().skip(7).map(|b| match b {
-            b'R' => "1",
-            b'L' => "0",
-            _ => unreachable!()
-        }).collect::<String>();
-        let col = u8::from_str_radix(&col, 2).unwrap();
-        Seat::new(row, col)
-    }).collect()
+fn parse_input(input: &str) -> Result<Vec<Seat>, String> {
+    input
+        .lines()
+        .map(|l| {
+            let line = l.as_bytes();
+            if line.len() != 10 {
+                return Err(format!("`{l}` is not 10 characters"));
+            }
+            let binary = |half: &[u8], one: u8, zero: u8| {
+                half.iter().try_fold(0, |n, &b| match b {
+                    _ if b == one => Ok(n << 1 | 1),
+                    _ if b == zero => Ok(n << 1),
+                    _ => Err(format!(
+                        "`{l}` has `{}` where it should have {} or {}",
+                        b as char, one as char, zero as char
+                    )),
+                })
+            };
+            Ok(Seat::new(
+                binary(&line[..7], b'B', b'F')?,
+                binary(&line[7..], b'R', b'L')?,
+            ))
+        })
+        .collect()
 }
 
-#[aoc(day5, part1)]
 fn part1(seats: &[Seat]) -> Option<usize> {
     seats.iter().map(|seat| seat.id()).max()
 }
 
-#[aoc(day5, part2)]
 fn part2(seats: &[Seat]) -> usize {
     let seats: Vec<usize> = seats.iter().map(|seat| seat.id()).collect();
     let min = seats.iter().min().unwrap().to_owned();
@@ -51,6 +56,31 @@ fn part2(seats: &[Seat]) -> usize {
     want - have
 }
 
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day05>();
+}
+
+struct Day05;
+
+impl Solution for Day05 {
+    const YEAR: u16 = 2020;
+    const DAY: u8 = 5;
+    type Input<'i> = Vec<Seat>;
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse_input(input.trim_end_matches('\n')).map_err(Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        part1(input).ok_or(Error::NoAnswer)
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(part2(input))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -62,7 +92,7 @@ BBFFBBFRLL";
     #[test]
     fn parsing_input() {
         assert_eq!(
-            parse_input(SEATS),
+            parse_input(SEATS).unwrap(),
             vec![Seat::new(70, 7), Seat::new(14, 7), Seat::new(102, 4)]
         );
     }
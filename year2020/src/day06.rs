@@ -1,4 +1,5 @@
 /// https://adventofcode.com/2020/day/6
+use aoc_core::{Error, Registry, Result, Solution};
 use pest::Parser;
 use std::collections::HashSet;
 
@@ -14,7 +15,7 @@ struct DeclarationGroup {
 
 impl DeclarationGroup {
     fn condense(&self) -> HashSet<char> {
-        self.group.iter().map(|g| g.yes.clone()).flatten().collect()
+        self.group.iter().flat_map(|g| g.yes.clone()).collect()
     }
 
     fn condense_all(&self) -> HashSet<char> {
@@ -39,10 +40,8 @@ impl DeclarationGroup {
 #[grammar = "pest/day06.pest"]
 struct InputParser;
 
-#[aoc_generator(day6)]
-fn parse_input(input: &str) -> Vec<DeclarationGroup> {
-    InputParser::parse(Rule::file, input)
-        .expect("could not parse input")
+fn parse_input(input: &str) -> Result<Vec<DeclarationGroup>, pest::error::Error<Rule>> {
+    Ok(InputParser::parse(Rule::file, input)?
         .filter(|r| r.as_rule() == Rule::group)
         .map(|g| DeclarationGroup {
             group: g
@@ -52,19 +51,42 @@ fn parse_input(input: &str) -> Vec<DeclarationGroup> {
                 })
                 .collect(),
         })
-        .collect()
+        .collect())
 }
 
-#[aoc(day6, part1)]
 fn part1(declarations: &[DeclarationGroup]) -> usize {
     declarations.iter().map(|g| g.condense().len()).sum()
 }
 
-#[aoc(day6, part2)]
 fn part2(declarations: &[DeclarationGroup]) -> usize {
     declarations.iter().map(|g| g.condense_all().len()).sum()
 }
 
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day06>();
+}
+
+struct Day06;
+
+impl Solution for Day06 {
+    const YEAR: u16 = 2020;
+    const DAY: u8 = 6;
+    type Input<'i> = Vec<DeclarationGroup>;
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse_input(input.trim_end_matches('\n')).map_err(Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(part1(input))
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(part2(input))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -119,7 +141,7 @@ b";
         d1g5.insert('b');
 
         assert_eq!(
-            parse_input(DECLARATIONS),
+            parse_input(DECLARATIONS).unwrap(),
             vec![
                 DeclarationGroup {
                     group: vec![Declaration { yes: d1g1 }]
@@ -151,13 +173,13 @@ b";
 
     #[test]
     fn running_part1() {
-        let declarations = parse_input(DECLARATIONS);
+        let declarations = parse_input(DECLARATIONS).unwrap();
         assert_eq!(part1(&declarations), 11);
     }
 
     #[test]
     fn running_part2() {
-        let declarations = parse_input(DECLARATIONS);
+        let declarations = parse_input(DECLARATIONS).unwrap();
         assert_eq!(part2(&declarations), 6);
     }
 }
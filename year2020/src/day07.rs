@@ -1,11 +1,13 @@
 /// https://adventofcode.com/2020/day/7
-use std::collections::{HashMap, HashSet};
+use aoc_common::Graph;
+use aoc_core::{Error, Registry, Result, Solution};
+use std::collections::HashMap;
 
 use nom::{
     branch::alt,
     bytes::complete::tag,
     character::complete::{alpha1, char as c, digit1},
-    combinator::{all_consuming, map, opt, value},
+    combinator::{all_consuming, map, map_res, opt, value},
     multi::{fold_many1, separated_list1},
     sequence::{delimited, separated_pair, terminated, tuple},
     IResult,
@@ -23,10 +25,9 @@ struct Rule {
     description: String, // Don't want to box a Bag on the heap as I can just lookup a Bag by the description (ID)
 }
 
-#[aoc_generator(day7)]
-fn parse_input(input: &str) -> HashMap<String, Bag> {
-    let (_, rules) = parse_input_nom(input).unwrap();
-    rules
+fn parse_input(input: &str) -> Result<HashMap<String, Bag>, nom::Err<nom::error::Error<&str>>> {
+    let (_, rules) = parse_input_nom(input)?;
+    Ok(rules)
 }
 
 fn parse_description(input: &str) -> IResult<&str, String> {
@@ -44,11 +45,11 @@ fn parse_rules(input: &str) -> IResult<&str, Option<Vec<Rule>>> {
                 tag(", "),
                 map(
                     terminated(
-                        separated_pair(digit1, c(' '), parse_description),
+                        separated_pair(map_res(digit1, str::parse), c(' '), parse_description),
                         tuple((tag(" bag"), opt(c('s')))),
                     ),
                     |(count, desc)| Rule {
-                        count: count.parse().unwrap(),
+                        count,
                         description: desc,
                     },
                 ),
@@ -87,74 +88,71 @@ fn parse_input_nom(input: &str) -> IResult<&str, HashMap<String, Bag>> {
     ))(input)
 }
 
-#[aoc(day7, part1)]
-fn part1(rules: &HashMap<String, Bag>) -> usize {
-    let inverse = reverse(rules);
-    let mut can_be_in = HashSet::new();
-    can_be_contained_in("shiny gold", &inverse, &mut can_be_in);
-    can_be_in.len()
+/// Each bag colour with an edge to every colour it holds, weighted by how
+/// many it holds.
+fn build_graph(rules: &HashMap<String, Bag>) -> Graph<&str, usize> {
+    let mut graph = Graph::new();
+    for bag in rules.values() {
+        graph.intern(bag.description.as_str());
+        for rule in bag.can_contain.iter().flatten() {
+            graph.add_edge(
+                bag.description.as_str(),
+                rule.description.as_str(),
+                rule.count,
+            );
+        }
+    }
+    graph
 }
 
-fn can_be_contained_in<'a>(
-    description: &str,
-    map: &HashMap<&str, HashSet<&'a str>>,
-    can_be_in: &mut HashSet<&'a str>,
-) {
-    if can_be_in.contains(description) {
-        return;
+fn part1(rules: &HashMap<String, Bag>) -> usize {
+    let holders = build_graph(rules).reversed();
+    match holders.id("shiny gold") {
+        // Don't count the shiny gold bag itself
+        Some(gold) => holders.reachable(gold).len() - 1,
+        None => 0,
     }
-    match map.get(description) {
-        None => {}
-        Some(set) => {
-            for s in set {
-                can_be_contained_in(s, map, can_be_in);
-                can_be_in.insert(s);
-            }
-        }
-    };
 }
 
-fn reverse<'a>(original: &'a HashMap<String, Bag>) -> HashMap<&'a str, HashSet<&'a str>> {
-    original.iter().fold(HashMap::new(), |mut map, (_, bag)| {
-        if let Some(can_contain) = &bag.can_contain {
-            for rule in can_contain {
-                match map.get_mut(rule.description.as_str()) {
-                    None => {
-                        let mut set = HashSet::new();
-                        set.insert(bag.description.as_str());
-                        map.insert(&rule.description, set);
-                    }
-                    Some(cur) => {
-                        (*cur).insert(bag.description.as_str());
-                    }
-                };
-            }
-        }
-        map
-    })
+fn part2(rules: &HashMap<String, Bag>) -> Result<usize> {
+    let graph = build_graph(rules);
+    let gold = match graph.id("shiny gold") {
+        Some(gold) => gold,
+        None => return Ok(0),
+    };
+    graph
+        .fold_dag(gold, |_, held| {
+            // count the bags themself and everything inside each of them
+            held.iter()
+                .map(|(inside, count)| *count * (1 + inside))
+                .sum()
+        })
+        .map_err(Error::solve)
 }
 
-#[aoc(day7, part2)]
-fn part2(rules: &HashMap<String, Bag>) -> usize {
-    bags_inside("shiny gold", rules)
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day07>();
 }
 
-fn bags_inside(description: &str, map: &HashMap<String, Bag>) -> usize {
-    let out = match map.get(description) {
-        None => 0,
-        Some(bag) => match &bag.can_contain {
-            None => 0,
-            Some(rules) => {
-                rules.iter().fold(0, |mut count, rule| {
-                    count += rule.count; // count the bags themself
-                    let inside = bags_inside(rule.description.as_str(), map); // get how many bags are inside this one
-                    count += rule.count * inside;
-                    count
-                })
-            }
-        },
-    };
-    out
+struct Day07;
+
+impl Solution for Day07 {
+    const YEAR: u16 = 2020;
+    const DAY: u8 = 7;
+    type Input<'i> = HashMap<String, Bag>;
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse_input(input.trim_end_matches('\n')).map_err(Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(part1(input))
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
@@ -303,21 +301,21 @@ dark violet bags contain no other bags.";
                 can_contain: None,
             },
         );
-        assert_eq!(parse_input(&RULES), map);
+        assert_eq!(parse_input(RULES).unwrap(), map);
     }
 
     #[test]
     fn running_part1() {
-        let rules = parse_input(RULES);
+        let rules = parse_input(RULES).unwrap();
         assert_eq!(part1(&rules), 4)
     }
 
     #[test]
     fn running_part2() {
-        let rules = parse_input(RULES);
-        assert_eq!(part2(&rules), 32);
+        let rules = parse_input(RULES).unwrap();
+        assert_eq!(part2(&rules).unwrap(), 32);
 
-        let rules = parse_input(RULES2);
-        assert_eq!(part2(&rules), 126);
+        let rules = parse_input(RULES2).unwrap();
+        assert_eq!(part2(&rules).unwrap(), 126);
     }
 }
@@ -1,11 +1,11 @@
 /// https://adventofcode.com/2020/day/9
+use aoc_core::{Error, Registry, Result, Solution};
+use std::num::ParseIntError;
 
-#[aoc_generator(day9)]
-fn parse_input(input: &str) -> Vec<usize> {
-    input.lines().map(|l| l.parse().unwrap()).collect()
+fn parse_input(input: &str) -> Result<Vec<usize>, ParseIntError> {
+    input.lines().map(|l| l.parse()).collect()
 }
 
-#[aoc(day9, part1)]
 fn part1(xmas: &[usize]) -> Option<usize> {
     first_invalid(xmas, 25)
 }
@@ -42,7 +42,6 @@ fn find_pair_sum(preamble: &[usize], sum: usize) -> Option<(usize, usize)> {
     None
 }
 
-#[aoc(day9, part2)]
 fn part2(xmas: &[usize]) -> Option<usize> {
     find_weakness(xmas, 25)
 }
@@ -78,6 +77,31 @@ fn find_contiguous_sum(xmas: &[usize], sum: usize) -> Option<&[usize]> {
     None
 }
 
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day09>();
+}
+
+struct Day09;
+
+impl Solution for Day09 {
+    const YEAR: u16 = 2020;
+    const DAY: u8 = 9;
+    type Input<'i> = Vec<usize>;
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse_input(input.trim_end_matches('\n')).map_err(Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        part1(input).ok_or(Error::NoAnswer)
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        part2(input).ok_or(Error::NoAnswer)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -106,7 +130,7 @@ mod test {
     #[test]
     fn prasing_input() {
         assert_eq!(
-            parse_input(XMAS),
+            parse_input(XMAS).unwrap(),
             vec![
                 35, 20, 15, 25, 47, 40, 62, 55, 65, 95, 102, 117, 150, 182, 127, 219, 299, 277,
                 309, 576
@@ -116,13 +140,13 @@ mod test {
 
     #[test]
     fn running_part1() {
-        let xmas = parse_input(XMAS);
+        let xmas = parse_input(XMAS).unwrap();
         assert_eq!(first_invalid(&xmas, 5), Some(127));
     }
 
     #[test]
     fn running_part2() {
-        let xmas = parse_input(XMAS);
+        let xmas = parse_input(XMAS).unwrap();
         assert_eq!(find_weakness(&xmas, 5), Some(62));
     }
 }
@@ -1,14 +1,17 @@
 /// https://adventofcode.com/2020/day/10
-use std::collections::HashMap;
+use aoc_common::Graph;
+use aoc_core::{Error, Registry, Result, Solution};
+use std::num::ParseIntError;
 
-#[aoc_generator(day10)]
-fn parse_input(input: &str) -> Vec<usize> {
-    let mut out: Vec<usize> = input.lines().map(|l| l.parse().unwrap()).collect();
+fn parse_input(input: &str) -> Result<Vec<usize>, ParseIntError> {
+    let mut out = input
+        .lines()
+        .map(|l| l.parse())
+        .collect::<Result<Vec<usize>, _>>()?;
     out.sort_unstable();
-    out
+    Ok(out)
 }
 
-#[aoc(day10, part1)]
 fn part1(adapters: &[usize]) -> usize {
     let (_, diff1, diff3) = adapters
         .iter()
@@ -20,71 +23,52 @@ fn part1(adapters: &[usize]) -> usize {
     diff1 * diff3
 }
 
-#[aoc(day10, part2)]
-fn part2(adapters: &[usize]) -> usize {
+fn part2(adapters: &[usize]) -> Result<usize> {
     let device_jolts = adapters.iter().max().unwrap() + 3;
-    let graph = build_graph(adapters, &device_jolts);
-    let mut cache = HashMap::new();
-    path_count(&graph, &0, &device_jolts, &mut cache)
+    let graph = build_graph(adapters, device_jolts);
+    let (wall, device) = (graph.id(&0).unwrap(), graph.id(&device_jolts).unwrap());
+    graph.count_paths(wall, device).map_err(Error::solve)
 }
 
-// Can make assumptions like a node will never fall back to itself so don't need to keep track of visited
-fn path_count<'a>(
-    graph: &'a HashMap<&'a usize, Vec<&'a usize>>,
-    start: &'a usize,
-    end: &'a usize,
-    cache: &mut HashMap<&'a usize, usize>,
-) -> usize {
-    if start == end {
-        1
-    } else {
-        graph
-            .get(start)
-            .unwrap()
-            .iter()
-            .fold(0, |count, adj| match cache.get(adj) {
-                Some(c) => count + c,
-                None => {
-                    let c = path_count(graph, adj, end, cache);
-                    cache.insert(adj, c);
-                    count + c
-                }
-            })
+/// Joins every joltage, from the wall's 0 up to the device, to each of the
+/// adapters that can plug into it.
+fn build_graph(adapters: &[usize], device_jolts: usize) -> Graph<usize> {
+    let mut graph = Graph::new();
+    let mut all = vec![0];
+    all.extend_from_slice(adapters);
+    all.push(device_jolts);
+    for (i, jolts) in all.iter().enumerate() {
+        graph.intern(*jolts);
+        for next in all[i + 1..].iter().take_while(|&&next| next <= jolts + 3) {
+            graph.add_edge(*jolts, *next, ());
+        }
     }
+    graph
 }
 
-fn build_graph<'a>(
-    adapters: &'a [usize],
-    device_jolts: &'a usize,
-) -> HashMap<&'a usize, Vec<&'a usize>> {
-    let mut graph: HashMap<_, Vec<_>> = HashMap::new();
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day10>();
+}
 
-    //Add from wall to first adapter
-    for i in 1 as usize..4 {
-        if let Ok(idx) = adapters.binary_search(&i) {
-            graph
-                .entry(&0)
-                .or_default()
-                .push(adapters.get(idx).unwrap());
-        }
+struct Day10;
+
+impl Solution for Day10 {
+    const YEAR: u16 = 2020;
+    const DAY: u8 = 10;
+    type Input<'i> = Vec<usize>;
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse_input(input.trim_end_matches('\n')).map_err(Error::parse)
     }
 
-    //Add from last adapter to device
-    graph
-        .entry(adapters.iter().max().unwrap())
-        .or_default()
-        .push(device_jolts);
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(part1(input))
+    }
 
-    adapters.iter().fold(graph, |mut g, adapter| {
-        for i in 1 as usize..4 {
-            if let Ok(idx) = adapters.binary_search(&(adapter + i)) {
-                g.entry(adapter)
-                    .or_default()
-                    .push(adapters.get(idx).unwrap());
-            }
-        }
-        g
-    })
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
@@ -126,7 +110,7 @@ mod test {
     #[test]
     fn parsing_input() {
         assert_eq!(
-            parse_input(ADAPTERS),
+            parse_input(ADAPTERS).unwrap(),
             vec![
                 1, 2, 3, 4, 7, 8, 9, 10, 11, 14, 17, 18, 19, 20, 23, 24, 25, 28, 31, 32, 33, 34,
                 35, 38, 39, 42, 45, 46, 47, 48, 49
@@ -136,13 +120,13 @@ mod test {
 
     #[test]
     fn running_part1() {
-        let input = parse_input(ADAPTERS);
+        let input = parse_input(ADAPTERS).unwrap();
         assert_eq!(part1(&input), 220)
     }
 
     #[test]
     fn running_part2() {
-        let input = parse_input(ADAPTERS);
-        assert_eq!(part2(&input), 19208)
+        let input = parse_input(ADAPTERS).unwrap();
+        assert_eq!(part2(&input).unwrap(), 19208)
     }
 }
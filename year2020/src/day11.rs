@@ -1,7 +1,10 @@
 /// https://adventofcode.com/2020/day/11
-use std::{
-    hash::{Hash},
+use aoc_common::{
+    automaton::{Automaton, Dense, State},
+    Grid, GridError, Pos, Wrap, ALL_DIRECTIONS,
 };
+use aoc_core::{Error, Registry, Result, Solution};
+use std::{convert::TryFrom, hash::Hash};
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
 enum Position {
     Empty,
@@ -9,189 +12,102 @@ enum Position {
     Floor,
 }
 
-#[derive(Debug, Eq, PartialEq, Hash, Clone)]
-struct FloorPlan {
-    rows: usize,
-    cols: usize,
-    plan: Vec<Position>,
-}
+impl TryFrom<char> for Position {
+    type Error = &'static str;
 
-impl FloorPlan {
-    fn new(raw: Vec<Vec<Position>>) -> Self {
-        FloorPlan {
-            rows: raw.len(),
-            cols: raw[0].len(),
-            plan: raw.into_iter().flatten().collect(),
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            '.' => Ok(Position::Floor),
+            'L' => Ok(Position::Empty),
+            '#' => Ok(Position::Occupied),
+            _ => Err("not a seat or floor"),
         }
     }
 }
 
-#[aoc_generator(day11)]
-fn parse_input(input: &str) -> FloorPlan {
-    FloorPlan::new(
-        input
-            .lines()
-            .map(|l| {
-                l.as_bytes()
-                    .iter()
-                    .map(|b| match b {
-                        b'.' => Position::Floor,
-                        b'L' => Position::Empty,
-                        _ => unreachable!(),
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>(),
-    )
+fn parse_input(input: &str) -> Result<Grid<Position>, GridError> {
+    Grid::parse(input)
 }
 
-// Works better since we find the indexes we want to look for before playing simulation
-// https://github.com/timvisee/advent-of-code-2020/blob/master/day11a/src/main.rs
-#[aoc(day11, part1)]
-fn part1(floor_plan: &FloorPlan) -> usize {
-    let neighbors = get_neighbors(floor_plan);
-    let (mut cur, mut prev) = (floor_plan.clone(), floor_plan.clone());
-
-    loop {
-        for (i, visible) in &neighbors {
-            let occup = visible
-                .iter()
-                .filter(|o| prev.plan[**o] == Position::Occupied)
-                .count();
-            let (cur_seat, prev_seat) = (&mut cur.plan[*i], &prev.plan[*i]);
-
-            if prev_seat == &Position::Empty && occup == 0 {
-                *cur_seat = Position::Occupied;
-            } else if prev_seat == &Position::Occupied && occup >= 4 {
-                *cur_seat = Position::Empty;
-            } else {
-                *cur_seat = prev_seat.clone()
-            }
-        }
-        // current iteration is now previous
-        // we can use what is in previous as next current as we are always overwritting it's values
-        std::mem::swap(&mut cur, &mut prev);
-        if cur == prev {
-            break;
-        }
+impl State for Position {
+    fn is_alive(&self) -> bool {
+        self == &Position::Occupied
     }
-    cur.plan
-        .iter()
-        .filter(|p| p == &&Position::Occupied)
-        .count()
 }
 
-// Works better since we find the indexes we want to look for before playing simulation
-// https://github.com/timvisee/advent-of-code-2020/blob/master/day11b/src/main.rs
-#[aoc(day11, part2)]
-fn part2(floor_plan: &FloorPlan) -> usize {
-    let aof = get_aof(floor_plan);
-    let (mut cur, mut prev) = (floor_plan.clone(), floor_plan.clone());
-
-    loop {
-        for (i, visible) in &aof {
-            let occup = visible
-                .iter()
-                .filter(|o| prev.plan[**o] == Position::Occupied)
-                .count();
-            let (cur_seat, prev_seat) = (&mut cur.plan[*i], &prev.plan[*i]);
-
-            if prev_seat == &Position::Empty && occup == 0 {
-                *cur_seat = Position::Occupied;
-            } else if prev_seat == &Position::Occupied && occup >= 5 {
-                *cur_seat = Position::Empty;
-            } else {
-                *cur_seat = prev_seat.clone()
-            }
-        }
-        // current iteration is now previous
-        // we can use what is in previous as next current as we are always overwritting it's values
-        std::mem::swap(&mut cur, &mut prev);
-        if cur == prev {
-            break;
-        }
-    }
-    cur.plan
-        .iter()
-        .filter(|p| p == &&Position::Occupied)
-        .count()
+/// Fills and empties seats until nothing changes. A seat is taken when none
+/// of the seats it can see are, and given up when at least `tolerance` are.
+fn settle<I>(
+    floor_plan: &Grid<Position>,
+    visible: impl Fn(&Grid<Position>, Pos) -> I,
+    tolerance: usize,
+) -> usize
+where
+    I: IntoIterator<Item = Pos>,
+{
+    let rule = |seat: &Position, occupied: usize| match seat {
+        Position::Empty if occupied == 0 => Position::Occupied,
+        Position::Occupied if occupied >= tolerance => Position::Empty,
+        seat => seat.clone(),
+    };
+    let mut seats = Dense::new(floor_plan.clone(), visible, rule);
+    seats.settle(usize::MAX);
+    seats.population()
 }
 
-fn get_neighbors(floor_plan: &FloorPlan) -> Vec<(usize, Vec<usize>)> {
+fn part1(floor_plan: &Grid<Position>) -> usize {
+    settle(floor_plan, get_neighbors, 4)
+}
+
+fn part2(floor_plan: &Grid<Position>) -> usize {
+    settle(floor_plan, get_aof, 5)
+}
+
+fn get_neighbors(floor_plan: &Grid<Position>, pos: Pos) -> Vec<Pos> {
     floor_plan
-        .plan
-        .iter()
-        .enumerate()
-        .filter(|(_, p)| p != &&Position::Floor)
-        .map(|(i, _)| {
-            (
-                i,
-                (0..9)
-                    .filter(|p| p != &4) // exclude middle
-                    // generate x and y neighbors for i
-                    .map(|p| {
-                        (
-                            (i % floor_plan.cols) as isize + p as isize % 3 - 1,
-                            (i / floor_plan.cols) as isize + p as isize / 3 - 1,
-                        )
-                    })
-                    // make sure we don't breach boundaries
-                    .filter(|(x, y)| {
-                        *x >= 0
-                            && *y >= 0
-                            && *x < floor_plan.cols as isize
-                            && *y < floor_plan.rows as isize
-                    })
-                    // turn back into an index on array
-                    .map(|(x, y)| (y * floor_plan.cols as isize + x) as usize)
-                    // Only want neighbors that are seats (not a floor)
-                    .filter(|i| floor_plan.plan[*i] != Position::Floor)
-                    .collect(),
-            )
-        })
+        .neighbours8(pos)
+        // Only want neighbors that are seats (not a floor)
+        .filter(|&n| floor_plan[n] != Position::Floor)
         .collect()
 }
 
-fn get_aof(floor_plan: &FloorPlan) -> Vec<(usize, Vec<usize>)> {
-    floor_plan
-        .plan
+fn get_aof(floor_plan: &Grid<Position>, pos: Pos) -> Vec<Pos> {
+    ALL_DIRECTIONS
         .iter()
-        .enumerate()
-        .filter(|(_, p)| p != &&Position::Floor)
-        .map(|(i, _)| {
-            (
-                i,
-                (0..9)
-                    .filter(|p| p != &4) // exclude middle
-                    // generate relative x and y to i for neighbors
-                    .map(|p| (p as isize % 3 - 1, p as isize / 3 - 1))
-                    .filter_map(|(rx, ry)| {
-                        (1..)
-                            // get x and y from index and apply offsets with multiples (f) for scanning out
-                            .map(|f| {
-                                (
-                                    (i % floor_plan.cols) as isize + rx * f,
-                                    (i / floor_plan.cols) as isize + ry * f,
-                                )
-                            })
-                            // Make sure we don't breach boundaries
-                            .take_while(|(x, y)| {
-                                *x >= 0
-                                    && *y >= 0
-                                    && *x < floor_plan.cols as isize
-                                    && *y < floor_plan.rows as isize
-                            })
-                            // Turn the x and y into an index
-                            .map(|(x, y)| (y * floor_plan.cols as isize + x) as usize)
-                            // only want the first seat (not a floor)
-                            .find(|i| floor_plan.plan[*i] != Position::Floor)
-                    })
-                    .collect(),
-            )
+        .filter_map(|&dir| {
+            floor_plan
+                .ray(pos, dir, Wrap::None)
+                // only want the first seat (not a floor)
+                .find(|&p| floor_plan[p] != Position::Floor)
         })
         .collect()
 }
 
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day11>();
+}
+
+struct Day11;
+
+impl Solution for Day11 {
+    const YEAR: u16 = 2020;
+    const DAY: u8 = 11;
+    type Input<'i> = Grid<Position>;
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse_input(input).map_err(Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(part1(input))
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(part2(input))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -211,11 +127,11 @@ L.LLLLL.LL";
     fn parsing_input() {
         use Position::*;
         assert_eq!(
-            parse_input(INPUT),
-            FloorPlan {
-                rows: 10,
-                cols: 10,
-                plan: vec![
+            parse_input(INPUT).unwrap(),
+            Grid::from_vec(
+                10,
+                10,
+                vec![
                     Empty, Floor, Empty, Empty, Floor, Empty, Empty, Floor, Empty, Empty, Empty,
                     Empty, Empty, Empty, Empty, Empty, Empty, Floor, Empty, Empty, Empty, Floor,
                     Empty, Floor, Empty, Floor, Floor, Empty, Floor, Floor, Empty, Empty, Empty,
@@ -227,19 +143,20 @@ L.LLLLL.LL";
                     Floor, Empty, Empty, Floor, Empty, Empty, Empty, Empty, Empty, Floor, Empty,
                     Empty
                 ]
-            }
+            )
+            .unwrap()
         );
     }
 
     #[test]
     fn running_part1() {
-        let input = parse_input(INPUT);
+        let input = parse_input(INPUT).unwrap();
         assert_eq!(part1(&input), 37);
     }
 
     #[test]
     fn running_part2() {
-        let input = parse_input(INPUT);
+        let input = parse_input(INPUT).unwrap();
         assert_eq!(part2(&input), 26);
     }
 }
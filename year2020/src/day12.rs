@@ -1,8 +1,10 @@
 /// https://adventofcode.com/2020/day/12
+use aoc_common::D4;
+use aoc_core::{Error, Registry, Result, Solution};
 use nom::{
     bytes::complete::take,
     character::complete::{char as c, digit1},
-    combinator::{all_consuming, map},
+    combinator::{all_consuming, map_res},
     multi::separated_list1,
     sequence::tuple,
     IResult,
@@ -19,124 +21,104 @@ enum Direction {
     Forward(isize),
 }
 
-#[aoc_generator(day12)]
-fn parse_input(input: &str) -> Vec<Direction> {
-    let (_, directions) = parse_input_nom(input).unwrap();
-    directions
+fn parse_input(input: &str) -> Result<Vec<Direction>, nom::Err<nom::error::Error<&str>>> {
+    let (_, directions) = parse_input_nom(input)?;
+    Ok(directions)
 }
 
 fn parse_input_nom(input: &str) -> IResult<&str, Vec<Direction>> {
     all_consuming(separated_list1(
         c('\n'),
-        map(tuple((take(1 as usize), digit1)), |(dir, num)| match dir {
-            "N" => Direction::North(num.parse().unwrap()),
-            "S" => Direction::South(num.parse().unwrap()),
-            "E" => Direction::East(num.parse().unwrap()),
-            "W" => Direction::West(num.parse().unwrap()),
-            "L" => Direction::Left(num.parse().unwrap()),
-            "R" => Direction::Right(num.parse().unwrap()),
-            "F" => Direction::Forward(num.parse().unwrap()),
-            _ => unreachable!(),
-        }),
+        map_res(
+            tuple((take(1_usize), map_res(digit1, str::parse))),
+            |(dir, num)| match dir {
+                "N" => Ok(Direction::North(num)),
+                "S" => Ok(Direction::South(num)),
+                "E" => Ok(Direction::East(num)),
+                "W" => Ok(Direction::West(num)),
+                "L" => Ok(Direction::Left(num)),
+                "R" => Ok(Direction::Right(num)),
+                "F" => Ok(Direction::Forward(num)),
+                _ => Err(()),
+            },
+        ),
     ))(input)
 }
 
-#[aoc(day12, part1)]
-fn part1(directions: &[Direction]) -> isize {
+/// Turning right by `degrees`, or left when it is negative. North is up, so
+/// positions count south and east.
+fn turn(degrees: isize) -> Result<D4> {
+    if degrees % 90 != 0 {
+        return Err(Error::solve(format!(
+            "ships only turn by right angles, not {degrees} degrees"
+        )));
+    }
+    Ok(D4::rotation((degrees / 90) as i32))
+}
+
+fn part1(directions: &[Direction]) -> Result<isize> {
     let mut pos = (0, 0);
     let mut dir = (1, 0);
     for d in directions {
         match d {
-            Direction::North(num) => pos.1 += num,
-            Direction::South(num) => pos.1 -= num,
+            Direction::North(num) => pos.1 -= num,
+            Direction::South(num) => pos.1 += num,
             Direction::East(num) => pos.0 += num,
             Direction::West(num) => pos.0 -= num,
-            Direction::Left(num) => match num {
-                90 => {
-                    std::mem::swap(&mut dir.0, &mut dir.1);
-                    dir.0 *= -1;
-                }
-                180 => {
-                    dir.0 *= -1;
-                    dir.1 *= -1;
-                }
-                270 => {
-                    std::mem::swap(&mut dir.0, &mut dir.1);
-                    dir.1 *= -1;
-                }
-                _ => unreachable!(),
-            },
-            Direction::Right(num) => match num {
-                90 => {
-                    std::mem::swap(&mut dir.0, &mut dir.1);
-                    dir.1 *= -1;
-                }
-                180 => {
-                    dir.0 *= -1;
-                    dir.1 *= -1;
-                }
-                270 => {
-                    std::mem::swap(&mut dir.0, &mut dir.1);
-                    dir.0 *= -1;
-                }
-                _ => unreachable!(),
-            },
+            Direction::Left(num) => dir = turn(-num)?.apply(&dir),
+            Direction::Right(num) => dir = turn(*num)?.apply(&dir),
             Direction::Forward(num) => {
                 pos.0 += dir.0 * *num;
                 pos.1 += dir.1 * *num;
             }
         }
     }
-    pos.0.abs() + pos.1.abs()
+    Ok(pos.0.abs() + pos.1.abs())
 }
 
-#[aoc(day12, part2)]
-fn part2(directions: &[Direction]) -> isize {
+fn part2(directions: &[Direction]) -> Result<isize> {
     let mut ship = (0, 0);
-    let mut way = (10, 1);
+    let mut way = (10, -1);
     for d in directions {
         match d {
-            Direction::North(num) => way.1 += num,
-            Direction::South(num) => way.1 -= num,
+            Direction::North(num) => way.1 -= num,
+            Direction::South(num) => way.1 += num,
             Direction::East(num) => way.0 += num,
             Direction::West(num) => way.0 -= num,
-            Direction::Left(num) => match num {
-                90 => {
-                    std::mem::swap(&mut way.0, &mut way.1);
-                    way.0 *= -1;
-                }
-                180 => {
-                    way.0 *= -1;
-                    way.1 *= -1;
-                }
-                270 => {
-                    std::mem::swap(&mut way.0, &mut way.1);
-                    way.1 *= -1;
-                }
-                _ => unreachable!(),
-            },
-            Direction::Right(num) => match num {
-                90 => {
-                    std::mem::swap(&mut way.0, &mut way.1);
-                    way.1 *= -1;
-                }
-                180 => {
-                    way.0 *= -1;
-                    way.1 *= -1;
-                }
-                270 => {
-                    std::mem::swap(&mut way.0, &mut way.1);
-                    way.0 *= -1;
-                }
-                _ => unreachable!(),
-            },
+            Direction::Left(num) => way = turn(-num)?.apply(&way),
+            Direction::Right(num) => way = turn(*num)?.apply(&way),
             Direction::Forward(num) => {
                 ship.0 += way.0 * num;
                 ship.1 += way.1 * num;
             }
         }
     }
-    ship.0.abs() + ship.1.abs()
+    Ok(ship.0.abs() + ship.1.abs())
+}
+
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day12>();
+}
+
+struct Day12;
+
+impl Solution for Day12 {
+    const YEAR: u16 = 2020;
+    const DAY: u8 = 12;
+    type Input<'i> = Vec<Direction>;
+    type Output = isize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse_input(input.trim_end_matches('\n')).map_err(Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        part1(input)
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
@@ -152,7 +134,7 @@ F11";
     #[test]
     fn parsing_input() {
         assert_eq!(
-            parse_input(DIRECTIONS),
+            parse_input(DIRECTIONS).unwrap(),
             vec![
                 Direction::Forward(10),
                 Direction::North(3),
@@ -165,13 +147,20 @@ F11";
 
     #[test]
     fn running_part1() {
-        let input = parse_input(DIRECTIONS);
-        assert_eq!(part1(&input), 25);
+        let input = parse_input(DIRECTIONS).unwrap();
+        assert_eq!(part1(&input).unwrap(), 25);
     }
 
     #[test]
     fn running_part2() {
-        let input = parse_input(DIRECTIONS);
-        assert_eq!(part2(&input), 286);
+        let input = parse_input(DIRECTIONS).unwrap();
+        assert_eq!(part2(&input).unwrap(), 286);
+    }
+
+    #[test]
+    fn turning_off_a_right_angle() {
+        let input = parse_input("F10\nL45\nF3").unwrap();
+        assert!(matches!(part1(&input), Err(Error::Solve(_))));
+        assert!(matches!(part2(&input), Err(Error::Solve(_))));
     }
 }
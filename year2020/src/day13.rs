@@ -1,22 +1,27 @@
 /// https://adventofcode.com/2020/day/13
-
-#[aoc_generator(day13)]
-fn parse_input(input: &str) -> (usize, Vec<(usize, usize)>) {
-    let parts: Vec<_> = input.split('\n').collect();
-    if parts.len() < 2 {
-        unreachable!()
-    }
-    let buses: Vec<(usize, usize)> = parts[1]
+use aoc_common::math;
+use aoc_core::{Error, Registry, Result, Solution};
+
+fn parse_input(input: &str) -> Result<(usize, Vec<(usize, usize)>), String> {
+    let (earliest, buses) = input
+        .split_once('\n')
+        .ok_or("the bus ids should be on the second line")?;
+    let buses = buses
         .split(',')
         .enumerate()
         .filter(|(_, s)| s != &"x")
-        .map(|(i, bus)| (i, bus.parse().unwrap()))
-        .collect();
-
-    (parts[0].parse().unwrap(), buses)
+        .map(|(i, bus)| match bus.parse() {
+            Ok(0) => Err("a bus cannot leave every 0 minutes".to_string()),
+            Ok(bus) => Ok((i, bus)),
+            Err(e) => Err(format!("bus `{bus}`: {e}")),
+        })
+        .collect::<Result<_, _>>()?;
+    let earliest = earliest
+        .parse()
+        .map_err(|e| format!("timestamp `{earliest}`: {e}"))?;
+    Ok((earliest, buses))
 }
 
-#[aoc(day13, part1)]
 fn part1(notes: &(usize, Vec<(usize, usize)>)) -> usize {
     let (earliest, buses) = notes;
     let (bus, early) = buses
@@ -27,62 +32,34 @@ fn part1(notes: &(usize, Vec<(usize, usize)>)) -> usize {
     bus * early
 }
 
-#[aoc(day13, part2)]
-fn part2(notes: &(usize, Vec<(usize, usize)>)) -> usize {
-    chinese_remainder(
-        notes
-            .1
-            .iter()
-            .map(|(off, bus)| (simplify_mod(*bus as isize - *off as isize, *bus), *bus))
-            .collect(),
-    )
+fn part2(notes: &(usize, Vec<(usize, usize)>)) -> Option<usize> {
+    // Bus `bus` leaves `off` minutes after t, so t ≡ -off (mod bus).
+    math::crt(notes.1.iter().map(|&(off, bus)| (bus - off % bus, bus))).map(|(t, _)| t)
 }
 
-// Chinese remainder theorem
-// https://www.youtube.com/watch?v=zIFehsBHB8o
-fn chinese_remainder(mods: Vec<(usize, usize)>) -> usize {
-    let prod: usize = mods.iter().map(|m| m.1).product();
-    mods.iter()
-        .map(|m| {
-            let p = prod / m.1;
-            m.0 * p * mod_inv(p, m.1).unwrap()
-        })
-        .sum::<usize>()
-        % prod
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day13>();
 }
 
-// https://brilliant.org/wiki/extended-euclidean-algorithm/
-// returns gcd, and x, y where xa + yb = gcd(a, b)
-fn egcd(a: usize, b: usize) -> (usize, isize, isize) {
-    let (mut r0, mut r1) = (a, b); // don't want input to be captured
+struct Day13;
+
+impl Solution for Day13 {
+    const YEAR: u16 = 2020;
+    const DAY: u8 = 13;
+    type Input<'i> = (usize, Vec<(usize, usize)>);
+    type Output = usize;
 
-    // Initilize
-    let (mut s0, mut t0, mut s1, mut t1) = (1, 0, 0, 1);
-    while r1 != 0 {
-        let (q2, r2) = (r0 / r1, r0 % r1);
-        let (s2, t2) = (s0 - s1 * (q2 as isize), t0 - t1 * (q2 as isize));
-        // Shift
-        r0 = r1;
-        r1 = r2;
-        s0 = s1;
-        s1 = s2;
-        t0 = t1;
-        t1 = t2;
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse_input(input.trim_end_matches('\n')).map_err(Error::parse)
     }
-    (r0, s0, t0)
-}
 
-fn mod_inv(res: usize, m: usize) -> Option<usize> {
-    let (g, x, _) = egcd(res, m);
-    if g == 1 {
-        Some(simplify_mod(x, m))
-    } else {
-        None
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(part1(input))
     }
-}
 
-fn simplify_mod(res: isize, m: usize) -> usize {
-    ((res % m as isize + m as isize) % m as isize) as usize
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        part2(input).ok_or(Error::NoAnswer)
+    }
 }
 
 #[cfg(test)]
@@ -95,14 +72,14 @@ mod test {
     #[test]
     fn parsing_input() {
         assert_eq!(
-            parse_input(NOTES),
+            parse_input(NOTES).unwrap(),
             (939, vec![(0, 7), (1, 13), (4, 59), (6, 31), (7, 19)])
         );
     }
 
     #[test]
     fn running_part1() {
-        let notes = parse_input(NOTES);
+        let notes = parse_input(NOTES).unwrap();
         assert_eq!(part1(&notes), 295);
     }
 
@@ -119,33 +96,17 @@ mod test {
 
     #[test]
     fn running_part2() {
-        let notes = parse_input(NOTES);
-        assert_eq!(part2(&notes), 1068781);
-        let notes = parse_input(NOTES2);
-        assert_eq!(part2(&notes), 3417);
-        let notes = parse_input(NOTES3);
-        assert_eq!(part2(&notes), 754018);
-        let notes = parse_input(NOTES4);
-        assert_eq!(part2(&notes), 779210);
-        let notes = parse_input(NOTES5);
-        assert_eq!(part2(&notes), 1261476);
-        let notes = parse_input(NOTES6);
-        assert_eq!(part2(&notes), 1202161486);
-    }
-
-    #[test]
-    fn test_gcd() {
-        assert_eq!(egcd(1914, 899), (29, 8, -17));
-        assert_eq!(egcd(899, 1914), (29, -17, 8));
-        assert_eq!(egcd(102, 38), (2, 3, -8));
-        assert_eq!(egcd(352, 168), (8, -10, 21));
-        assert_eq!(egcd(3458, 4864), (38, -45, 32));
-        assert_eq!(egcd(1432, 123211), (1, -22973, 267));
-    }
-
-    #[test]
-    fn test_chinese() {
-        assert_eq!(chinese_remainder(vec![(3, 5), (1, 7), (6, 8)]), 78);
-        assert_eq!(chinese_remainder(vec![(2, 3), (2, 4), (1, 5)]), 26);
+        let notes = parse_input(NOTES).unwrap();
+        assert_eq!(part2(&notes), Some(1068781));
+        let notes = parse_input(NOTES2).unwrap();
+        assert_eq!(part2(&notes), Some(3417));
+        let notes = parse_input(NOTES3).unwrap();
+        assert_eq!(part2(&notes), Some(754018));
+        let notes = parse_input(NOTES4).unwrap();
+        assert_eq!(part2(&notes), Some(779210));
+        let notes = parse_input(NOTES5).unwrap();
+        assert_eq!(part2(&notes), Some(1261476));
+        let notes = parse_input(NOTES6).unwrap();
+        assert_eq!(part2(&notes), Some(1202161486));
     }
 }
@@ -1,4 +1,5 @@
 /// https://adventofcode.com/2020/day/14
+use aoc_core::{Error, Registry, Result, Solution};
 use std::collections::HashMap;
 
 use bit::BitIndex;
@@ -6,7 +7,7 @@ use nom::{
     branch::alt,
     bytes::complete::{tag, take},
     character::complete::digit1,
-    combinator::map,
+    combinator::{all_consuming, map, map_res},
     multi::separated_list1,
     sequence::{delimited, preceded, tuple},
     IResult,
@@ -54,7 +55,7 @@ impl Mask {
 
         // gives me all possible values from all 0s to all 1s for the floating mask
         // size = 2 ^ num of 'X's
-        for i in 0..(2 as u64).pow(self.floating.len() as u32) {
+        for i in 0..2_u64.pow(self.floating.len() as u32) {
             let mut orig_copy = orig;
             for (j, f) in self.floating.iter().enumerate() {
                 orig_copy.set_bit(*f as usize, i.bit(j));
@@ -94,33 +95,31 @@ fn mask_or_and(mask: &str) -> (u64, u64) {
     (or, and)
 }
 
-#[aoc_generator(day14)]
-fn parse_input(input: &str) -> Vec<Operation> {
-    let (_, out) = separated_list1(
+fn parse_input(input: &str) -> Result<Vec<Operation>, nom::Err<nom::error::Error<&str>>> {
+    let (_, out) = all_consuming(separated_list1(
         tag("\n"),
         alt((
             preceded(tag("mem"), parse_mem),
             preceded(tag("mask"), parse_mask),
         )),
-    )(input)
-    .unwrap();
-    out
+    ))(input)?;
+    Ok(out)
 }
 
 fn parse_mask(input: &str) -> IResult<&str, Operation> {
-    map(preceded(tag(" = "), take(36 as usize)), |s: &str| {
+    map(preceded(tag(" = "), take(36_usize)), |s: &str| {
         Operation::ChnageMask(Mask::new(s.to_owned()))
     })(input)
 }
 
 fn parse_mem(input: &str) -> IResult<&str, Operation> {
+    let number = || map_res(digit1, str::parse::<u64>);
     map(
-        tuple::<_, (&str, &str), _, _>((delimited(tag("["), digit1, tag("] = ")), digit1)),
-        |n| Operation::WriteMem((n.0.parse().unwrap(), n.1.parse().unwrap())),
+        tuple((delimited(tag("["), number(), tag("] = ")), number())),
+        Operation::WriteMem,
     )(input)
 }
 
-#[aoc(day14, part1)]
 fn part1(data: &[Operation]) -> u64 {
     let mut mem = HashMap::new();
     let mut mask = &Mask::new(String::new());
@@ -133,10 +132,9 @@ fn part1(data: &[Operation]) -> u64 {
             }
         }
     }
-    mem.iter().map(|(_, i)| i).sum()
+    mem.values().sum()
 }
 
-#[aoc(day14, part2)]
 fn part2(data: &[Operation]) -> u64 {
     let mut mem = HashMap::new();
     let mut mask = &Mask::new(String::new());
@@ -151,7 +149,32 @@ fn part2(data: &[Operation]) -> u64 {
             }
         }
     }
-    mem.iter().map(|(_, i)| i).sum()
+    mem.values().sum()
+}
+
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day14>();
+}
+
+struct Day14;
+
+impl Solution for Day14 {
+    const YEAR: u16 = 2020;
+    const DAY: u8 = 14;
+    type Input<'i> = Vec<Operation>;
+    type Output = u64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse_input(input.trim_end_matches('\n')).map_err(Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(part1(input))
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(part2(input))
+    }
 }
 
 #[cfg(test)]
@@ -171,7 +194,7 @@ mem[26] = 1";
     #[test]
     fn parsing_input() {
         assert_eq!(
-            parse_input(DATA),
+            parse_input(DATA).unwrap(),
             vec![
                 Operation::ChnageMask(Mask::new(
                     "XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X".to_string()
@@ -193,13 +216,13 @@ mem[26] = 1";
 
     #[test]
     fn running_part1() {
-        let data = parse_input(DATA);
+        let data = parse_input(DATA).unwrap();
         assert_eq!(part1(&data), 165)
     }
 
     #[test]
     fn running_part2() {
-        let data = parse_input(DATA2);
+        let data = parse_input(DATA2).unwrap();
         assert_eq!(part2(&data), 208)
     }
 }
@@ -1,9 +1,9 @@
 /// https://adventofcode.com/2020/day/15
-use std::{collections::HashMap, hash::Hash};
+use aoc_core::{Error, Registry, Result, Solution, Variants};
+use std::{collections::HashMap, hash::Hash, num::ParseIntError};
 
-#[aoc_generator(day15)]
-fn parse_input(input: &str) -> Vec<usize> {
-    input.split(',').map(|i| i.parse().unwrap()).collect()
+fn parse_input(input: &str) -> Result<Vec<usize>, ParseIntError> {
+    input.split(',').map(|i| i.parse()).collect()
 }
 
 struct Mem<V> {
@@ -73,26 +73,51 @@ fn nth_word_spoken2(starting: &[usize], nth: usize) -> usize {
     })
 }
 
-#[aoc(day15, part1)]
 fn part1(starting: &[usize]) -> usize {
     nth_word_spoken(starting, 2020)
 }
 
-#[aoc(day15, part1, folding)]
 fn part1_folding(starting: &[usize]) -> usize {
     nth_word_spoken2(starting, 2020)
 }
 
-#[aoc(day15, part2)]
 fn part2(starting: &[usize]) -> usize {
     nth_word_spoken(starting, 30_000_000)
 }
 
-#[aoc(day15, part2, folding)]
 fn part2_folding(starting: &[usize]) -> usize {
     nth_word_spoken2(starting, 30_000_000)
 }
 
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add_variants(
+        Variants::<Day15>::new()
+            .part_one("folding", |s| Ok(part1_folding(s)))
+            .part_two("folding", |s| Ok(part2_folding(s))),
+    );
+}
+
+struct Day15;
+
+impl Solution for Day15 {
+    const YEAR: u16 = 2020;
+    const DAY: u8 = 15;
+    type Input<'i> = Vec<usize>;
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse_input(input.trim_end_matches('\n')).map_err(Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(part1(input))
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(part2(input))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -103,35 +128,35 @@ mod test {
 
     #[test]
     fn parsing_input() {
-        assert_eq!(parse_input(STARTING1), vec![0, 3, 6]);
-        assert_eq!(parse_input(STARTING2), vec![1, 3, 2]);
-        assert_eq!(parse_input(STARTING3), vec![2, 1, 3]);
+        assert_eq!(parse_input(STARTING1).unwrap(), vec![0, 3, 6]);
+        assert_eq!(parse_input(STARTING2).unwrap(), vec![1, 3, 2]);
+        assert_eq!(parse_input(STARTING3).unwrap(), vec![2, 1, 3]);
     }
 
     #[test]
     fn running_part1() {
-        let starting = parse_input(STARTING1);
+        let starting = parse_input(STARTING1).unwrap();
         assert_eq!(part1(&starting), 436);
-        let starting = parse_input(STARTING2);
+        let starting = parse_input(STARTING2).unwrap();
         assert_eq!(part1(&starting), 1);
-        let starting = parse_input(STARTING3);
+        let starting = parse_input(STARTING3).unwrap();
         assert_eq!(part1(&starting), 10);
 
-        let starting = parse_input(STARTING1);
+        let starting = parse_input(STARTING1).unwrap();
         assert_eq!(part1_folding(&starting), 436);
-        let starting = parse_input(STARTING2);
+        let starting = parse_input(STARTING2).unwrap();
         assert_eq!(part1_folding(&starting), 1);
-        let starting = parse_input(STARTING3);
+        let starting = parse_input(STARTING3).unwrap();
         assert_eq!(part1_folding(&starting), 10);
     }
 
     #[test]
     #[ignore = "takes long time to run for a unit test"]
     fn running_part2() {
-        let starting = parse_input(STARTING1);
+        let starting = parse_input(STARTING1).unwrap();
         assert_eq!(part2(&starting), 175594);
 
-        let starting = parse_input(STARTING1);
+        let starting = parse_input(STARTING1).unwrap();
         assert_eq!(part2_folding(&starting), 175594);
     }
 }
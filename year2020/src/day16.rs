@@ -1,13 +1,12 @@
 /// https://adventofcode.com/2020/day/16
-use std::{
-    collections::{HashMap, HashSet},
-    ops::Range,
-};
+use aoc_common::{assignment::assign, IntervalSet};
+use aoc_core::{Error, Registry, Result, Solution};
+use std::{collections::HashMap, ops::Range};
 
 use nom::{
     bytes::complete::{tag, take_till},
     character::complete::digit1,
-    combinator::map,
+    combinator::{map, map_res},
     multi::separated_list1,
     sequence::separated_pair,
     IResult,
@@ -15,14 +14,14 @@ use nom::{
 
 type Validations = HashMap<String, (Range<usize>, Range<usize>)>;
 type Ticket = Vec<usize>;
+type Notes = (Validations, Ticket, Vec<Ticket>);
 
-#[aoc_generator(day16)]
-fn parse_input(input: &str) -> (Validations, Ticket, Vec<Ticket>) {
-    let (_, out) = parse_input_nom(input).unwrap();
-    out
+fn parse_input(input: &str) -> Result<Notes, nom::Err<nom::error::Error<&str>>> {
+    let (_, out) = parse_input_nom(input)?;
+    Ok(out)
 }
 
-fn parse_input_nom(input: &str) -> IResult<&str, (Validations, Ticket, Vec<Ticket>)> {
+fn parse_input_nom(input: &str) -> IResult<&str, Notes> {
     let (input, validations) = parse_validations(input)?;
     let (input, _) = tag("\n\nyour ticket:\n")(input)?;
     let (input, own_ticket) = parse_ticket(input)?;
@@ -32,9 +31,11 @@ fn parse_input_nom(input: &str) -> IResult<&str, (Validations, Ticket, Vec<Ticke
 }
 
 fn parse_range(input: &str) -> IResult<&str, Range<usize>> {
-    map::<_, (&str, &str), _, _, _, _>(separated_pair(digit1, tag("-"), digit1), |(a, b)| Range {
-        start: a.parse().unwrap(),
-        end: b.parse::<usize>().unwrap() + 1,
+    map(separated_pair(number, tag("-"), number), |(start, end)| {
+        Range {
+            start,
+            end: end + 1,
+        }
     })(input)
 }
 
@@ -54,54 +55,26 @@ fn parse_validations(input: &str) -> IResult<&str, Validations> {
 }
 
 fn parse_ticket(input: &str) -> IResult<&str, Vec<usize>> {
-    let (input, out) = separated_list1(
-        tag(","),
-        map::<_, &str, _, _, _, _>(digit1, |d| d.parse().unwrap()),
-    )(input)?;
-    Ok((input, out))
+    separated_list1(tag(","), number)(input)
 }
 
-fn consolidate_ranges(ranges: &mut Vec<Range<usize>>) {
-    loop {
-        let mut consolidated = Vec::new();
-        if ranges.is_empty() {
-            return;
-        }
-        consolidated.push(ranges[0].clone());
-
-        'range: for range in ranges[1..].iter() {
-            for cons in consolidated.iter_mut() {
-                if cons.contains(&range.start) && !cons.contains(&range.end) {
-                    cons.end = range.end;
-                    continue 'range;
-                }
-                if !cons.contains(&range.start) && cons.contains(&range.end) {
-                    cons.start = range.start;
-                    continue 'range;
-                }
-            }
-            consolidated.push(range.clone());
-        }
-        if consolidated.len() == ranges.len() {
-            break;
-        }
-        *ranges = consolidated;
-    }
+fn number(input: &str) -> IResult<&str, usize> {
+    map_res(digit1, str::parse)(input)
+}
+
+/// Every value that at least one field allows.
+fn valid_values(validations: &Validations) -> IntervalSet<usize> {
+    validations
+        .values()
+        .flat_map(|rules| [rules.0.clone(), rules.1.clone()])
+        .collect()
 }
 
 fn filter_tickets<'a>(validations: &Validations, nearby: &'a [Ticket]) -> Vec<&'a Ticket> {
-    let mut ranges = validations
-        .iter()
-        .flat_map(|(_, rules)| vec![rules.0.clone(), rules.1.clone()])
-        .collect();
-    consolidate_ranges(&mut ranges);
+    let valid = valid_values(validations);
     nearby
         .iter()
-        .filter(|fields| {
-            fields
-                .iter()
-                .all(|field| ranges.iter().any(|r| r.contains(field)))
-        })
+        .filter(|fields| fields.iter().all(|field| valid.contains(field)))
         .collect()
 }
 
@@ -120,56 +93,15 @@ fn flip_vec(vectors: Vec<&Ticket>) -> Vec<Vec<usize>> {
     flipped
 }
 
-fn clean_findings(findings: &mut HashMap<String, Vec<usize>>) {
-    let mut eliminated = HashSet::new();
-
-    loop {
-        let mut eliminate = HashSet::new();
-        // find what I can eliminate
-        for (_, find) in findings.iter() {
-            if find.len() == 1 && !eliminated.contains(&find[0]) {
-                eliminate.insert(find[0]);
-            }
-        }
-
-        if eliminate.is_empty() {
-            break;
-        }
-
-        // remove eliminatable elements from map
-        for elim in eliminate.iter() {
-            for (_, find) in findings.iter_mut() {
-                if find.len() == 1 {
-                    continue;
-                }
-                *find = find.clone().into_iter().filter(|f| f != elim).collect();
-            }
-            eliminated.insert(*elim);
-        }
-    }
-}
-
-#[aoc(day16, part1)]
-fn part1((validations, _, nearby): &(Validations, Ticket, Vec<Ticket>)) -> usize {
-    let mut ranges = validations
-        .iter()
-        .flat_map(|(_, rules)| vec![rules.0.clone(), rules.1.clone()])
-        .collect();
-    consolidate_ranges(&mut ranges);
+fn part1((validations, _, nearby): &Notes) -> usize {
+    let valid = valid_values(validations);
     nearby
         .iter()
-        .map::<Vec<_>, _>(|fields| {
-            fields
-                .iter()
-                .filter(|field| !ranges.iter().any(|r| r.contains(field)))
-                .collect()
-        })
-        .flatten()
+        .flat_map(|fields| fields.iter().filter(|field| !valid.contains(field)))
         .sum()
 }
 
-#[aoc(day16, part2)]
-fn part2((validations, own, nearby): &(Validations, Ticket, Vec<Ticket>)) -> usize {
+fn part2((validations, own, nearby): &Notes) -> Result<usize> {
     let fields = flip_vec(filter_tickets(validations, nearby));
     // figure out which fields are valid with which validation
     let mut defs: HashMap<String, Vec<usize>> = HashMap::new();
@@ -177,17 +109,43 @@ fn part2((validations, own, nearby): &(Validations, Ticket, Vec<Ticket>)) -> usi
         for (key, rules) in validations {
             if field
                 .iter()
-                .all(|f| rules.0.contains(&f) || rules.1.contains(&f))
+                .all(|f| rules.0.contains(f) || rules.1.contains(f))
             {
-                defs.entry(key.to_string()).or_insert_with(Vec::new).push(i);
+                defs.entry(key.to_string()).or_default().push(i);
             }
         }
     }
-    clean_findings(&mut defs);
-    defs.iter()
+    let columns = assign(defs).map_err(Error::solve)?;
+    Ok(columns
+        .iter()
         .filter(|(key, _)| key.starts_with("departure"))
-        .map(|(_, find)| own[find[0]])
-        .product()
+        .map(|(_, column)| own[*column])
+        .product())
+}
+
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day16>();
+}
+
+struct Day16;
+
+impl Solution for Day16 {
+    const YEAR: u16 = 2020;
+    const DAY: u8 = 16;
+    type Input<'i> = Notes;
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse_input(input.trim_end_matches('\n')).map_err(Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(part1(input))
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
@@ -223,7 +181,7 @@ nearby tickets:
             (Range { start: 13, end: 41 }, Range { start: 45, end: 51 }),
         );
         assert_eq!(
-            parse_input(NOTES),
+            parse_input(NOTES).unwrap(),
             (
                 map,
                 vec![7, 1, 14],
@@ -239,13 +197,13 @@ nearby tickets:
 
     #[test]
     fn running_part1() {
-        let notes = parse_input(NOTES);
+        let notes = parse_input(NOTES).unwrap();
         assert_eq!(part1(&notes), 71);
     }
 
     #[test]
     fn running_part2() {
-        let notes = parse_input(NOTES);
-        assert_eq!(part2(&notes), 14);
+        let notes = parse_input(NOTES).unwrap();
+        assert_eq!(part2(&notes).unwrap(), 14);
     }
 }
@@ -1,173 +1,60 @@
 /// https://adventofcode.com/2020/day/18
-use std::fmt;
-
-use nom::{
-    branch::alt,
-    character::complete::{char as c, digit1, multispace0, one_of},
-    combinator::{cut, map, map_res},
-    multi::many0,
-    sequence::{delimited, pair},
-    IResult,
-};
-
-enum Operation {
-    Addition,
-    Multiplication,
-}
+use aoc_common::expr::{Assoc, BinOp, EvalError, Expr, Operators, ParseError};
+use aoc_core::{Error, Registry, Result, Solution};
 
-enum ExpressionType {
-    Constante(u64),
-    Addition(Expression, Expression),
-    Multiplication(Expression, Expression),
+/// Addition and multiplication are evaluated left to right.
+fn rules_p1() -> Operators {
+    Operators::new()
+        .binary(BinOp::Add, 1, Assoc::Left)
+        .binary(BinOp::Mul, 1, Assoc::Left)
 }
 
-struct Expression {
-    expression: Box<ExpressionType>,
+/// Addition is evaluated before multiplication.
+fn rules_p2() -> Operators {
+    Operators::new()
+        .binary(BinOp::Add, 2, Assoc::Left)
+        .binary(BinOp::Mul, 1, Assoc::Left)
 }
 
-impl fmt::Debug for Expression {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match *self.expression {
-            ExpressionType::Constante(ref val) => write!(f, "{}", val),
-            ExpressionType::Addition(ref left, ref right) => {
-                write!(f, "({:?} + {:?})", left, right)
-            }
-            ExpressionType::Multiplication(ref left, ref right) => {
-                write!(f, "({:?} * {:?})", left, right)
-            }
-        }
-    }
+fn parse_input(input: &str, rules: &Operators) -> Result<Vec<Expr>, ParseError> {
+    input.lines().map(|l| rules.parse(l)).collect()
 }
 
-impl Expression {
-    fn new(t: ExpressionType) -> Self {
-        Expression {
-            expression: Box::new(t),
-        }
-    }
-
-    fn evaluate(&self) -> u64 {
-        match *self.expression {
-            ExpressionType::Constante(val) => val,
-            ExpressionType::Addition(ref left, ref right) => left.evaluate() + right.evaluate(),
-            ExpressionType::Multiplication(ref left, ref right) => {
-                left.evaluate() * right.evaluate()
-            }
-        }
-    }
+fn sum(homework: &[Expr]) -> Result<i64, EvalError> {
+    homework.iter().try_fold(0i64, |total, e| {
+        total.checked_add(e.evaluate()?).ok_or(EvalError::Overflow)
+    })
 }
 
-fn parse_constant(input: &str) -> IResult<&str, u64> {
-    map_res(digit1, |s: &str| s.parse())(input)
+/// Both parts read the same homework with a different precedence table.
+fn solve(homework: &str, rules: &Operators) -> Result<i64> {
+    let homework = parse_input(homework, rules).map_err(Error::parse)?;
+    sum(&homework).map_err(Error::solve)
 }
 
-fn parse_operation_am(input: &str) -> IResult<&str, Operation> {
-    map(one_of("*+"), |c| {
-        if c == '+' {
-            Operation::Addition
-        } else {
-            Operation::Multiplication
-        }
-    })(input)
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day18>();
 }
 
-fn parse_operation_add(input: &str) -> IResult<&str, Operation> {
-    map(c('+'), |_| Operation::Addition)(input)
-}
+struct Day18;
 
-fn parse_operation_mult(input: &str) -> IResult<&str, Operation> {
-    map(c('*'), |_| Operation::Multiplication)(input)
-}
+impl Solution for Day18 {
+    const YEAR: u16 = 2020;
+    const DAY: u8 = 18;
+    type Input<'i> = &'i str;
+    type Output = i64;
 
-fn fold(acc: Expression, (op, val): (Operation, Expression)) -> Expression {
-    match op {
-        Operation::Addition => Expression::new(ExpressionType::Addition(acc, val)),
-        Operation::Multiplication => Expression::new(ExpressionType::Multiplication(acc, val)),
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Ok(input.trim_end_matches('\n'))
     }
-}
-
-fn parse_parens_p1(input: &str) -> IResult<&str, Expression> {
-    delimited(c('('), cut(parse_expression_p1), cut(c(')')))(input)
-}
-
-fn parse_parens_p2(input: &str) -> IResult<&str, Expression> {
-    delimited(c('('), cut(parse_expression_p2), cut(c(')')))(input)
-}
-
-fn parse_factor_p1(input: &str) -> IResult<&str, Expression> {
-    alt((
-        delimited(multispace0, parse_parens_p1, multispace0),
-        map(delimited(multispace0, parse_constant, multispace0), |d| {
-            Expression::new(ExpressionType::Constante(d))
-        }),
-    ))(input)
-}
 
-fn parse_factor_p2(input: &str) -> IResult<&str, Expression> {
-    alt((
-        delimited(multispace0, parse_parens_p2, multispace0),
-        map(delimited(multispace0, parse_constant, multispace0), |d| {
-            Expression::new(ExpressionType::Constante(d))
-        }),
-    ))(input)
-}
-
-fn parse_term_p2(input: &str) -> IResult<&str, Expression> {
-    let (input, mut init) = parse_factor_p2(input)?;
-    let (input, rights) = many0(pair(parse_operation_add, cut(parse_factor_p2)))(input)?;
-    for right in rights {
-        init = fold(init, right)
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        solve(input, &rules_p1())
     }
-    Ok((input, init))
-}
 
-fn parse_expression_p1(input: &str) -> IResult<&str, Expression> {
-    let (input, mut init) = parse_factor_p1(input)?;
-    let (input, rights) = many0(pair(parse_operation_am, cut(parse_factor_p1)))(input)?;
-    for right in rights {
-        init = fold(init, right)
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        solve(input, &rules_p2())
     }
-    Ok((input, init))
-}
-
-fn parse_expression_p2(input: &str) -> IResult<&str, Expression> {
-    let (input, mut init) = parse_term_p2(input)?;
-    let (input, rights) = many0(pair(parse_operation_mult, cut(parse_term_p2)))(input)?;
-    for right in rights {
-        init = fold(init, right)
-    }
-    Ok((input, init))
-}
-#[aoc_generator(day18, part1)]
-fn parse_input_p1(input: &str) -> Vec<Expression> {
-    input
-        .lines()
-        .map(|l| {
-            let (_, exp) = parse_expression_p1(l).unwrap();
-            exp
-        })
-        .collect()
-}
-
-#[aoc_generator(day18, part2)]
-fn parse_input_p2(input: &str) -> Vec<Expression> {
-    input
-        .lines()
-        .map(|l| {
-            let (_, exp) = parse_expression_p2(l).unwrap();
-            exp
-        })
-        .collect()
-}
-
-#[aoc(day18, part1)]
-fn part1(homework: &[Expression]) -> u64 {
-    homework.iter().map(|e| e.evaluate()).sum()
-}
-
-#[aoc(day18, part2)]
-fn part2(homework: &[Expression]) -> u64 {
-    homework.iter().map(|e| e.evaluate()).sum()
 }
 
 #[cfg(test)]
@@ -184,9 +71,10 @@ mod test {
     #[test]
     fn parsing_input() {
         assert_eq!(
-            parse_input_p1(HOMEWORK)
+            parse_input(HOMEWORK, &rules_p1())
+                .unwrap()
                 .iter()
-                .map(|exp| format!("{:?}", exp))
+                .map(|exp| exp.to_string())
                 .collect::<Vec<_>>(),
             vec![
                 "(((((1 + 2) * 3) + 4) * 5) + 6)",
@@ -198,9 +86,10 @@ mod test {
             ]
         );
         assert_eq!(
-            parse_input_p2(HOMEWORK)
+            parse_input(HOMEWORK, &rules_p2())
+                .unwrap()
                 .iter()
-                .map(|exp| format!("{:?}", exp))
+                .map(|exp| exp.to_string())
                 .collect::<Vec<_>>(),
             vec![
                 "(((1 + 2) * (3 + 4)) * (5 + 6))",
@@ -215,13 +104,11 @@ mod test {
 
     #[test]
     fn running_part1() {
-        let homework = parse_input_p1(HOMEWORK);
-        assert_eq!(part1(&homework), 26457)
+        assert_eq!(solve(HOMEWORK, &rules_p1()).unwrap(), 26457)
     }
 
     #[test]
     fn running_part2() {
-        let homework = parse_input_p2(HOMEWORK);
-        assert_eq!(part2(&homework), 694173)
+        assert_eq!(solve(HOMEWORK, &rules_p2()).unwrap(), 694173)
     }
 }
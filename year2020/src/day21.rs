@@ -1,4 +1,6 @@
 /// https://adventofcode.com/2020/day/21
+use aoc_common::assignment::assign;
+use aoc_core::{Error, Registry, Result, Solution};
 use pest::Parser;
 use std::collections::{HashMap, HashSet};
 
@@ -6,12 +8,13 @@ use std::collections::{HashMap, HashSet};
 #[grammar = "pest/day21.pest"]
 struct InputParser;
 
-#[aoc_generator(day21)]
-fn parse_input(input: &str) -> Vec<(HashSet<String>, HashSet<String>)> {
-    InputParser::parse(Rule::file, input)
-        .expect("unable to parse input")
+type Listing = (HashSet<String>, HashSet<String>);
+
+fn parse_input(input: &str) -> Result<Vec<Listing>, pest::error::Error<Rule>> {
+    Ok(InputParser::parse(Rule::file, input)?
         .filter(|r| r.as_rule() == Rule::listing)
         .map(|l| {
+            // The grammar guarantees both lists are there.
             let mut inner = l.into_inner();
             let ingrediants = inner
                 .next()
@@ -27,7 +30,7 @@ fn parse_input(input: &str) -> Vec<(HashSet<String>, HashSet<String>)> {
                 .collect::<HashSet<_>>();
             (ingrediants, allergens)
         })
-        .collect()
+        .collect())
 }
 
 fn clean_input(
@@ -47,7 +50,6 @@ fn clean_input(
     (ingrediants, allergens)
 }
 
-#[aoc(day21, part1)]
 fn part1(listing: &[(HashSet<String>, HashSet<String>)]) -> usize {
     let (ingrediants, allergens) = clean_input(listing);
     let mut base = HashSet::new();
@@ -60,49 +62,43 @@ fn part1(listing: &[(HashSet<String>, HashSet<String>)]) -> usize {
         .count()
 }
 
-fn clean_allergens(allergens: &mut HashMap<String, HashSet<String>>) {
-    let mut removed = HashSet::new();
-    loop {
-        let mut to_remove = HashSet::new();
-        for (_, ings) in allergens.iter() {
-            if ings.len() == 1 {
-                let ing = ings.iter().next().unwrap();
-                if !removed.contains(ing) {
-                    to_remove.insert(ing.to_owned());
-                }
-            }
-        }
-
-        if to_remove.is_empty() {
-            break;
-        }
-
-        for r in to_remove.iter() {
-            for (_, ings) in allergens.iter_mut() {
-                if ings.len() == 1 {
-                    continue;
-                }
-                ings.remove(r);
-            }
-            removed.insert(r.to_owned());
-        }
-    }
-}
-
-#[allow(dead_code)]
-fn part2(listing: &[(HashSet<String>, HashSet<String>)]) -> String {
-    let (_, mut allergens) = clean_input(listing);
-    clean_allergens(&mut allergens);
-    let mut allergens = allergens
+fn part2(listing: &[(HashSet<String>, HashSet<String>)]) -> Result<String> {
+    let (_, allergens) = clean_input(listing);
+    let mut allergens = assign(allergens)
+        .map_err(Error::solve)?
         .into_iter()
-        .map(|(k, v)| (k, v))
         .collect::<Vec<_>>();
     allergens.sort_by(|(a, _), (b, _)| a.cmp(b));
-    allergens
+    Ok(allergens
         .into_iter()
-        .map(|(_, v)| v.into_iter().next().unwrap())
+        .map(|(_, v)| v)
         .collect::<Vec<_>>()
-        .join(",")
+        .join(","))
+}
+
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day21>();
+}
+
+struct Day21;
+
+impl Solution for Day21 {
+    const YEAR: u16 = 2020;
+    const DAY: u8 = 21;
+    type Input<'i> = Vec<Listing>;
+    type Output = String;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse_input(input.trim_end_matches('\n')).map_err(Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(part1(input).to_string())
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
@@ -117,31 +113,34 @@ sqjhc mxmxvkd sbzzf (contains fish)";
 
     #[test]
     fn parsing_input() {
-        let map = parse_input(LISTING);
+        let map = parse_input(LISTING).unwrap();
         println!("{:#?}", map);
     }
 
     #[test]
     fn running_part1() {
-        let listing = parse_input(LISTING);
+        let listing = parse_input(LISTING).unwrap();
         assert_eq!(part1(&listing), 5);
     }
 
     #[test]
     fn running_part2() {
-        let listing = parse_input(LISTING);
-        assert_eq!(part2(&listing), "mxmxvkd,sqjhc,fvjkl");
+        let listing = parse_input(LISTING).unwrap();
+        assert_eq!(part2(&listing).unwrap(), "mxmxvkd,sqjhc,fvjkl");
     }
 
     #[test]
     #[ignore = "this is actually running the code not the test"]
     fn running() {
         let mut input = String::new();
-        File::open("input/2020/day21.txt")
-            .unwrap()
-            .read_to_string(&mut input)
-            .unwrap();
-        let listing = parse_input(input.as_str());
-        println!("{}", part2(&listing));
+        File::open(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../inputs/2020/day21.txt"
+        ))
+        .unwrap()
+        .read_to_string(&mut input)
+        .unwrap();
+        let listing = parse_input(input.as_str()).unwrap();
+        println!("{}", part2(&listing).unwrap());
     }
 }
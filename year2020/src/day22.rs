@@ -1,114 +1,188 @@
 /// https://adventofcode.com/2020/day/22
+use aoc_core::{Error, Registry, Result, Solution};
 use pest::Parser;
-use std::collections::hash_map::DefaultHasher;
-use std::collections::{HashSet, VecDeque};
-use std::hash::{Hash, Hasher};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::{self, Display},
+};
 
 #[derive(Parser)]
 #[grammar = "pest/day22.pest"]
 struct InputParser;
 
-#[aoc_generator(day22)]
-fn parse_input(input: &str) -> (VecDeque<usize>, VecDeque<usize>) {
-    let mut file = InputParser::parse(Rule::file, input).expect("could not parse the file");
-    let player1 = file
-        .next()
-        .unwrap()
-        .into_inner()
-        .map(|c| c.as_str().parse::<usize>().unwrap())
-        .collect::<VecDeque<_>>();
-    let player2 = file
-        .next()
-        .unwrap()
-        .into_inner()
-        .map(|c| c.as_str().parse::<usize>().unwrap())
-        .collect::<VecDeque<_>>();
-    (player1, player2)
+fn parse_input(input: &str) -> Result<Decks, String> {
+    let mut file = InputParser::parse(Rule::file, input).map_err(|e| e.to_string())?;
+    // The grammar guarantees there are two players.
+    let mut deck = || {
+        file.next()
+            .unwrap()
+            .into_inner()
+            .map(|c| {
+                c.as_str()
+                    .parse::<usize>()
+                    .map_err(|e| format!("`{c}`: {e}"))
+            })
+            .collect::<Result<VecDeque<_>, _>>()
+    };
+    Ok((deck()?, deck()?))
 }
 
-fn play_compat((player_1, player_2): (&mut VecDeque<usize>, &mut VecDeque<usize>)) {
-    loop {
-        if player_1.is_empty() || player_2.is_empty() {
-            break;
-        }
-        let p1 = player_1.pop_front().unwrap();
-        let p2 = player_2.pop_front().unwrap();
-        match p1.cmp(&p2) {
-            std::cmp::Ordering::Less => {
-                player_2.push_back(p2);
-                player_2.push_back(p1);
-            }
-            std::cmp::Ordering::Greater => {
-                player_1.push_back(p1);
-                player_1.push_back(p2);
-            }
-            std::cmp::Ordering::Equal => unreachable!(),
+/// Which version of the game is being played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rules {
+    Classic,
+    Recursive,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Player {
+    One,
+    Two,
+}
+
+impl Display for Player {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Player::One => write!(f, "1"),
+            Player::Two => write!(f, "2"),
         }
     }
 }
 
-enum Winner {
-    Player1,
-    Player2,
-}
+pub type Decks = (VecDeque<usize>, VecDeque<usize>);
 
-fn card_hasher(cards: &VecDeque<usize>) -> u64 {
-    let mut s = DefaultHasher::new();
-    cards.hash(&mut s);
-    s.finish()
+/// Plays games of Combat under either set of rules.
+pub struct Combat {
+    rules: Rules,
+    /// Who won each sub-game, by the decks it started with.
+    winners: HashMap<Decks, Player>,
+    games: usize,
+    transcript: Option<Vec<String>>,
 }
 
-fn play_recurse((player_1, player_2): (&mut VecDeque<usize>, &mut VecDeque<usize>)) -> Winner {
-    let (mut p1_hash, mut p2_hash) = (HashSet::new(), HashSet::new());
-    loop {
-        let (new_p1_hash, new_p2_hash) = (card_hasher(player_1), card_hasher(player_2));
-        if p1_hash.contains(&new_p1_hash) || p2_hash.contains(&new_p2_hash) {
-            break Winner::Player1;
+impl Combat {
+    pub fn new(rules: Rules) -> Self {
+        Combat {
+            rules,
+            winners: HashMap::new(),
+            games: 0,
+            transcript: None,
         }
-        p1_hash.insert(new_p1_hash);
-        p2_hash.insert(new_p2_hash);
+    }
 
-        if player_1.is_empty() || player_2.is_empty() {
-            break if !player_1.is_empty() {
-                Winner::Player1
-            } else {
-                Winner::Player2
-            };
+    /// Writes down every round the way the puzzle's worked examples do. Every
+    /// sub-game is played out in full so the game numbers line up.
+    pub fn transcribed(mut self) -> Self {
+        self.transcript = Some(Vec::new());
+        self
+    }
+
+    pub fn transcript(&self) -> Option<String> {
+        self.transcript.as_ref().map(|lines| lines.join("\n"))
+    }
+
+    fn log(&mut self, line: impl FnOnce() -> String) {
+        if let Some(transcript) = &mut self.transcript {
+            transcript.push(line());
         }
+    }
+
+    /// Plays a whole game, giving the winner and the decks it ended with.
+    pub fn play(&mut self, decks: &Decks) -> (Player, Decks) {
+        let mut decks = decks.clone();
+        let winner = self.game(&mut decks);
+        self.log(String::new);
+        self.log(String::new);
+        self.log(|| "== Post-game results ==".to_string());
+        self.log(|| format!("Player 1's deck: {}", show(&decks.0)));
+        self.log(|| format!("Player 2's deck: {}", show(&decks.1)));
+        (winner, decks)
+    }
 
-        let p1 = player_1.pop_front().unwrap();
-        let p2 = player_2.pop_front().unwrap();
-
-        if p1 <= player_1.len() && p2 <= player_2.len() {
-            let mut rec_player_1: VecDeque<_> = player_1.iter().take(p1).cloned().collect();
-            let mut rec_player_2: VecDeque<_> = player_2.iter().take(p2).cloned().collect();
-            match play_recurse((&mut rec_player_1, &mut rec_player_2)) {
-                Winner::Player1 => {
-                    player_1.push_back(p1);
-                    player_1.push_back(p2);
-                }
-                Winner::Player2 => {
-                    player_2.push_back(p2);
-                    player_2.push_back(p1);
-                }
+    fn game(&mut self, decks: &mut Decks) -> Player {
+        self.games += 1;
+        let (game, recursive) = (self.games, self.rules == Rules::Recursive);
+        if recursive {
+            self.log(|| format!("=== Game {game} ==="));
+            self.log(String::new);
+        }
+        // Only the recursive rules end a game that has been seen before.
+        let mut seen = HashSet::new();
+        let mut round = 0;
+        let winner = loop {
+            if decks.0.is_empty() {
+                break Player::Two;
             }
-        } else {
-            match p1.cmp(&p2) {
-                std::cmp::Ordering::Less => {
-                    player_2.push_back(p2);
-                    player_2.push_back(p1);
-                }
-                std::cmp::Ordering::Greater => {
-                    player_1.push_back(p1);
-                    player_1.push_back(p2);
-                }
-                std::cmp::Ordering::Equal => unreachable!(),
+            if decks.1.is_empty() || recursive && !seen.insert(decks.clone()) {
+                break Player::One;
+            }
+            round += 1;
+            if round > 1 {
+                self.log(String::new);
+            }
+            self.log(|| match recursive {
+                true => format!("-- Round {round} (Game {game}) --"),
+                false => format!("-- Round {round} --"),
+            });
+            self.log(|| format!("Player 1's deck: {}", show(&decks.0)));
+            self.log(|| format!("Player 2's deck: {}", show(&decks.1)));
+            let (one, two) = (decks.0.pop_front().unwrap(), decks.1.pop_front().unwrap());
+            self.log(|| format!("Player 1 plays: {one}"));
+            self.log(|| format!("Player 2 plays: {two}"));
+
+            let winner = if recursive && one <= decks.0.len() && two <= decks.1.len() {
+                self.log(|| "Playing a sub-game to determine the winner...".to_string());
+                self.log(String::new);
+                let sub = (
+                    decks.0.iter().take(one).copied().collect(),
+                    decks.1.iter().take(two).copied().collect(),
+                );
+                let winner = self.sub_game(sub);
+                self.log(String::new);
+                self.log(|| format!("...anyway, back to game {game}."));
+                winner
+            } else if one > two {
+                Player::One
+            } else {
+                Player::Two
+            };
+            self.log(|| match recursive {
+                true => format!("Player {winner} wins round {round} of game {game}!"),
+                false => format!("Player {winner} wins the round!"),
+            });
+            match winner {
+                Player::One => decks.0.extend([one, two]),
+                Player::Two => decks.1.extend([two, one]),
             }
+        };
+        if recursive {
+            self.log(|| format!("The winner of game {game} is player {winner}!"));
         }
+        winner
     }
+
+    fn sub_game(&mut self, mut decks: Decks) -> Player {
+        if self.transcript.is_some() {
+            return self.game(&mut decks);
+        }
+        if let Some(&winner) = self.winners.get(&decks) {
+            return winner;
+        }
+        let start = decks.clone();
+        let winner = self.game(&mut decks);
+        self.winners.insert(start, winner);
+        winner
+    }
+}
+
+fn show(deck: &VecDeque<usize>) -> String {
+    deck.iter()
+        .map(|card| card.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
-fn calculate_score(cards: &VecDeque<usize>) -> usize {
+fn score(cards: &VecDeque<usize>) -> usize {
     cards
         .iter()
         .rev()
@@ -117,23 +191,42 @@ fn calculate_score(cards: &VecDeque<usize>) -> usize {
         .sum()
 }
 
-#[aoc(day22, part1)]
-fn part1(cards: &(VecDeque<usize>, VecDeque<usize>)) -> usize {
-    let (mut player_1, mut player_2) = cards.clone();
-    play_compat((&mut player_1, &mut player_2));
-    if !player_1.is_empty() {
-        calculate_score(&player_1)
-    } else {
-        calculate_score(&player_2)
+fn winning_score(rules: Rules, cards: &Decks) -> usize {
+    match Combat::new(rules).play(cards) {
+        (Player::One, (deck, _)) | (Player::Two, (_, deck)) => score(&deck),
     }
 }
 
-#[aoc(day22, part2)]
-fn part2(cards: &(VecDeque<usize>, VecDeque<usize>)) -> usize {
-    let (mut player_1, mut player_2) = cards.clone();
-    match play_recurse((&mut player_1, &mut player_2)) {
-        Winner::Player1 => calculate_score(&player_1),
-        Winner::Player2 => calculate_score(&player_2),
+fn part1(cards: &Decks) -> usize {
+    winning_score(Rules::Classic, cards)
+}
+
+fn part2(cards: &Decks) -> usize {
+    winning_score(Rules::Recursive, cards)
+}
+
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day22>();
+}
+
+struct Day22;
+
+impl Solution for Day22 {
+    const YEAR: u16 = 2020;
+    const DAY: u8 = 22;
+    type Input<'i> = Decks;
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse_input(input.trim_end_matches('\n')).map_err(Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(part1(input))
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(part2(input))
     }
 }
 
@@ -158,7 +251,7 @@ Player 2:
     #[test]
     fn parsing_input() {
         assert_eq!(
-            parse_input(CARDS),
+            parse_input(CARDS).unwrap(),
             (
                 vec![9, 2, 6, 3, 1].into_iter().collect(),
                 vec![5, 8, 4, 7, 10].into_iter().collect()
@@ -168,13 +261,94 @@ Player 2:
 
     #[test]
     fn running_part1() {
-        let cards = parse_input(CARDS);
+        let cards = parse_input(CARDS).unwrap();
         assert_eq!(part1(&cards), 306);
     }
 
     #[test]
     fn running_part2() {
-        let cards = parse_input(CARDS);
+        let cards = parse_input(CARDS).unwrap();
         assert_eq!(part2(&cards), 291);
     }
+
+    #[test]
+    fn ending_loops() {
+        let cards = (VecDeque::from([43, 19]), VecDeque::from([2, 29, 14]));
+        let (winner, _) = Combat::new(Rules::Recursive).play(&cards);
+        assert_eq!(winner, Player::One);
+    }
+
+    #[test]
+    fn writing_transcripts() {
+        let cards = parse_input(CARDS).unwrap();
+        let mut combat = Combat::new(Rules::Classic).transcribed();
+        combat.play(&cards);
+        let transcript = combat.transcript().unwrap();
+        assert!(transcript.starts_with(
+            "-- Round 1 --
+Player 1's deck: 9, 2, 6, 3, 1
+Player 2's deck: 5, 8, 4, 7, 10
+Player 1 plays: 9
+Player 2 plays: 5
+Player 1 wins the round!
+
+-- Round 2 --"
+        ));
+        assert!(transcript.ends_with(
+            "-- Round 29 --
+Player 1's deck: 1
+Player 2's deck: 7, 3, 2, 10, 6, 8, 5, 9, 4
+Player 1 plays: 1
+Player 2 plays: 7
+Player 2 wins the round!
+
+
+== Post-game results ==
+Player 1's deck: 
+Player 2's deck: 3, 2, 10, 6, 8, 5, 9, 4, 7, 1"
+        ));
+
+        let mut combat = Combat::new(Rules::Recursive).transcribed();
+        combat.play(&cards);
+        let transcript = combat.transcript().unwrap();
+        assert!(transcript.starts_with("=== Game 1 ===\n\n-- Round 1 (Game 1) --\n"));
+        assert!(transcript.contains(
+            "Player 1 plays: 4
+Player 2 plays: 3
+Playing a sub-game to determine the winner...
+
+=== Game 2 ===
+
+-- Round 1 (Game 2) --
+Player 1's deck: 9, 8, 5, 2
+Player 2's deck: 10, 1, 7
+Player 1 plays: 9
+Player 2 plays: 10
+Player 2 wins round 1 of game 2!
+"
+        ));
+        assert!(transcript.contains(
+            "Player 2 wins round 6 of game 2!
+The winner of game 2 is player 2!
+
+...anyway, back to game 1.
+Player 2 wins round 9 of game 1!
+
+-- Round 10 (Game 1) --"
+        ));
+        assert!(transcript.ends_with(
+            "-- Round 17 (Game 1) --
+Player 1's deck: 3
+Player 2's deck: 9, 7, 5, 6, 2, 4, 1, 10, 8
+Player 1 plays: 3
+Player 2 plays: 9
+Player 2 wins round 17 of game 1!
+The winner of game 1 is player 2!
+
+
+== Post-game results ==
+Player 1's deck: 
+Player 2's deck: 7, 5, 6, 2, 4, 1, 10, 8, 9, 3"
+        ));
+    }
 }
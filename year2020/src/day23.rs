@@ -1,18 +1,137 @@
 /// https://adventofcode.com/2020/day/23
+use aoc_core::{Error, Registry, Result, Solution, Variants};
 use std::hash::Hash;
 use std::{cell::RefCell, fmt::Display};
 use std::{collections::HashMap, rc::Rc};
 
-#[aoc_generator(day23)]
-fn parse_input(input: &str) -> Vec<usize> {
-    input
+fn parse_input(input: &str) -> Result<Vec<usize>, String> {
+    let cups = input
         .lines()
         .next()
-        .unwrap()
+        .ok_or("there are no cups")?
         .chars()
-        .into_iter()
-        .map(|b| b.to_digit(10).unwrap() as usize)
-        .collect()
+        .map(|b| {
+            b.to_digit(10)
+                .map(|label| label as usize)
+                .ok_or_else(|| format!("`{b}` is not a cup label"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    check_labels(&cups, cups.len())?;
+    Ok(cups)
+}
+
+/// Checks that `starting` labels its cups 1 to however many there are, once
+/// each, and that a circle of `count` cups can hold them and play a move.
+fn check_labels(starting: &[usize], count: usize) -> Result<(), String> {
+    if count < 4 {
+        return Err(format!("a move needs at least four cups, not {count}"));
+    }
+    if count < starting.len() {
+        return Err(format!(
+            "a circle of {count} cups cannot hold {} starting cups",
+            starting.len()
+        ));
+    }
+    let mut seen = vec![false; starting.len() + 1];
+    for &label in starting {
+        if !(1..=starting.len()).contains(&label) {
+            return Err(format!(
+                "cup {label} is not labelled 1 to {}",
+                starting.len()
+            ));
+        }
+        if seen[label] {
+            return Err(format!("there are two cups labelled {label}"));
+        }
+        seen[label] = true;
+    }
+    Ok(())
+}
+
+/// The circle of cups as a successor list, where `next[label]` is the label
+/// of the cup clockwise from `label`. Nothing sits at label 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Cups {
+    next: Vec<u32>,
+    current: u32,
+}
+
+impl Cups {
+    /// Lays out `starting` clockwise from the current cup, then carries on
+    /// with the labels after the highest one until there are `count` cups.
+    /// A move picks up three cups and needs somewhere else to put them, so
+    /// there have to be at least four.
+    fn new(starting: &[usize], count: usize) -> Result<Self, String> {
+        check_labels(starting, count)?;
+        let labels = starting
+            .iter()
+            .map(|&label| label as u32)
+            .chain(starting.len() as u32 + 1..=count as u32);
+        let mut next = vec![0; count + 1];
+        let mut first = None;
+        let mut last = 0;
+        for label in labels {
+            match first {
+                None => first = Some(label),
+                Some(_) => next[last as usize] = label,
+            }
+            last = label;
+        }
+        let current = first.unwrap_or(0);
+        next[last as usize] = current;
+        Ok(Cups { next, current })
+    }
+
+    fn play(&mut self, moves: usize) {
+        let highest = self.next.len() as u32 - 1;
+        let next = &mut self.next;
+        for _ in 0..moves {
+            let current = self.current;
+            let a = next[current as usize];
+            let b = next[a as usize];
+            let c = next[b as usize];
+            let mut dest = current;
+            loop {
+                dest = if dest == 1 { highest } else { dest - 1 };
+                if dest != a && dest != b && dest != c {
+                    break;
+                }
+            }
+            next[current as usize] = next[c as usize];
+            next[c as usize] = next[dest as usize];
+            next[dest as usize] = a;
+            self.current = next[current as usize];
+        }
+    }
+
+    /// The labels of the other cups, clockwise from `label`.
+    fn after(&self, label: usize) -> impl Iterator<Item = usize> + '_ {
+        let mut at = label as u32;
+        (1..self.next.len() - 1).map(move |_| {
+            at = self.next[at as usize];
+            at as usize
+        })
+    }
+
+    /// Every label, clockwise from `label`.
+    fn snapshot(&self, label: usize) -> Vec<usize> {
+        std::iter::once(label).chain(self.after(label)).collect()
+    }
+}
+
+fn part1(cups: &[usize]) -> Result<String, String> {
+    let mut cups = Cups::new(cups, cups.len())?;
+    cups.play(100);
+    Ok(cups.snapshot(1)[1..]
+        .iter()
+        .map(|label| label.to_string())
+        .collect())
+}
+
+fn part2(cups: &[usize]) -> Result<usize, String> {
+    let mut cups = Cups::new(cups, 1_000_000)?;
+    cups.play(10_000_000);
+    Ok(cups.after(1).take(2).product())
 }
 
 type NodeRef<T> = Rc<RefCell<Node<T>>>;
@@ -177,14 +296,13 @@ where
     }
 }
 
-#[aoc(day23, part1)]
-fn part1(cups: &[usize]) -> String {
+fn part1_linked_list(cups: &[usize]) -> String {
     let mut cups = WrappedLinkedList::new(cups.to_vec());
     play_game(&mut cups, 100);
     format!("{}", cups)
 }
-#[aoc(day23, part2)]
-fn part2(cups: &[usize]) -> usize {
+
+fn part2_linked_list(cups: &[usize]) -> usize {
     let mut cups = cups.to_vec();
     cups.resize(1_000_000, 0);
     cups = cups
@@ -203,6 +321,37 @@ fn part2(cups: &[usize]) -> usize {
     prod
 }
 
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add_variants(
+        Variants::<Day23>::new()
+            .part_one("linked_list", |c| Ok(part1_linked_list(c)))
+            .part_two("linked_list", |c| Ok(part2_linked_list(c).to_string())),
+    );
+}
+
+struct Day23;
+
+impl Solution for Day23 {
+    const YEAR: u16 = 2020;
+    const DAY: u8 = 23;
+    type Input<'i> = Vec<usize>;
+    type Output = String;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse_input(input.trim_end_matches('\n')).map_err(Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        part1(input).map_err(Error::solve)
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        part2(input)
+            .map(|product| product.to_string())
+            .map_err(Error::solve)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -210,20 +359,57 @@ mod test {
 
     #[test]
     fn parsing_input() {
-        let cups = parse_input(CUPS);
+        let cups = parse_input(CUPS).unwrap();
         assert_eq!(cups, vec![3, 8, 9, 1, 2, 5, 4, 6, 7]);
     }
 
+    #[test]
+    fn playing_cups() {
+        let mut cups = Cups::new(&parse_input(CUPS).unwrap(), 9).unwrap();
+        assert_eq!(
+            cups.snapshot(cups.current as usize),
+            vec![3, 8, 9, 1, 2, 5, 4, 6, 7]
+        );
+        cups.play(1);
+        assert_eq!(
+            cups.snapshot(cups.current as usize),
+            vec![2, 8, 9, 1, 5, 4, 6, 7, 3]
+        );
+        cups.play(9);
+        assert_eq!(
+            cups.after(1).collect::<Vec<_>>(),
+            vec![9, 2, 6, 5, 8, 3, 7, 4]
+        );
+        let cups = Cups::new(&[2, 1], 5).unwrap();
+        assert_eq!(cups.snapshot(cups.current as usize), vec![2, 1, 3, 4, 5]);
+    }
+
+    #[test]
+    fn rejecting_labels() {
+        for cups in ["123", "1239", "1123", "0123", "12a4"] {
+            assert!(parse_input(cups).is_err(), "{}", cups);
+        }
+        assert!(Cups::new(&[2, 1], 3).is_err());
+        assert!(Cups::new(&[1, 2, 3, 4, 5], 4).is_err());
+    }
+
     #[test]
     fn running_part1() {
-        let cups = parse_input(CUPS);
-        assert_eq!(part1(&cups), "67384529");
+        let cups = parse_input(CUPS).unwrap();
+        assert_eq!(part1(&cups).unwrap(), "67384529");
+        assert_eq!(part1_linked_list(&cups), "67384529");
     }
 
     #[test]
-    #[ignore = "long running test"]
     fn running_part2() {
-        let cups = parse_input(CUPS);
-        assert_eq!(part2(&cups), 149245887792);
+        let cups = parse_input(CUPS).unwrap();
+        assert_eq!(part2(&cups).unwrap(), 149245887792);
+    }
+
+    #[test]
+    #[ignore = "long running test"]
+    fn running_part2_linked_list() {
+        let cups = parse_input(CUPS).unwrap();
+        assert_eq!(part2_linked_list(&cups), 149245887792);
     }
 }
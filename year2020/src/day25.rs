@@ -1,40 +1,50 @@
 /// https://adventofcode.com/2020/day/25
+use aoc_common::math;
+use aoc_core::{Error, Registry, Result, Solution};
 
-#[aoc_generator(day25)]
-fn parse_input(input: &str) -> (usize, usize) {
-    let mut l = input.lines();
-    let card = l.next().unwrap().parse::<usize>().unwrap();
-    let door = l.next().unwrap().parse::<usize>().unwrap();
-    (card, door)
+fn parse_input(input: &str) -> Result<(usize, usize), String> {
+    let mut keys = input
+        .lines()
+        .map(|l| l.parse::<usize>().map_err(|e| e.to_string()));
+    let mut key = |name| keys.next().unwrap_or(Err(format!("no {name} public key")));
+    Ok((key("card")?, key("door")?))
 }
 
-fn loop_size(key: &usize, subject_number: usize) -> usize {
-    let mut l: usize = 0;
-    let mut value: usize = 1;
-    loop {
-        l += 1;
-        value *= subject_number;
-        value %= 20201227;
-        if &value == key {
-            break l;
-        }
-    }
+const MODULUS: usize = 20201227;
+
+fn loop_size(key: &usize, subject_number: usize) -> Option<usize> {
+    math::discrete_log(subject_number, *key, MODULUS)
 }
 
 fn encryption_key(key: &usize, loop_size: usize) -> usize {
-    let mut value: usize = 1;
-    for _ in 0..loop_size {
-        value *= key;
-        value %= 20201227;
-    }
-    value
+    math::mod_pow(*key, loop_size as u64, MODULUS)
 }
 
-#[aoc(day25, part1)]
-fn part1(input: &(usize, usize)) -> usize {
+fn part1(input: &(usize, usize)) -> Option<usize> {
     let (card, door) = input;
-    let l = loop_size(card, 7);
-    encryption_key(door, l)
+    let l = loop_size(card, 7)?;
+    Some(encryption_key(door, l))
+}
+
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day25>();
+}
+
+struct Day25;
+
+impl Solution for Day25 {
+    const YEAR: u16 = 2020;
+    const DAY: u8 = 25;
+    type Input<'i> = (usize, usize);
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse_input(input.trim_end_matches('\n')).map_err(Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        part1(input).ok_or(Error::NoAnswer)
+    }
 }
 
 #[cfg(test)]
@@ -46,13 +56,13 @@ mod test {
 
     #[test]
     fn parsing_input() {
-        assert_eq!(parse_input(INPUT), (5764801, 17807724));
+        assert_eq!(parse_input(INPUT).unwrap(), (5764801, 17807724));
     }
 
     #[test]
     fn test_loop_size() {
-        assert_eq!(loop_size(&5764801, 7), 8);
-        assert_eq!(loop_size(&17807724, 7), 11);
+        assert_eq!(loop_size(&5764801, 7), Some(8));
+        assert_eq!(loop_size(&17807724, 7), Some(11));
     }
 
     #[test]
@@ -63,8 +73,7 @@ mod test {
 
     #[test]
     fn running_part1() {
-        let input = parse_input(INPUT);
-        assert_eq!(part1(&input), 14897079)
+        let input = parse_input(INPUT).unwrap();
+        assert_eq!(part1(&input), Some(14897079))
     }
 }
-
@@ -1,13 +1,11 @@
 // TODO would be cool to see how if I can use pest instead of nom and compare results of the parsers (make sure to look at streaming)
-// TODO remove use of aoc in favor of my own helpers so I can write meaningful benchmarks with multiple parsers and solutions
 // TODO may make sense to write some macros or build.rs to help maintain boiler plate
-// TODO support workspaces so that I have all the years in one workspace but CLI can run specific year/day/part
 
 extern crate pest;
 #[macro_use]
 extern crate pest_derive;
-#[macro_use]
-extern crate aoc_runner_derive;
+
+use aoc_core::Registry;
 
 pub mod day01;
 pub mod day02;
@@ -35,4 +33,30 @@ pub mod day23;
 pub mod day24;
 pub mod day25;
 
-aoc_lib!(year = 2020);
+pub fn register(registry: &mut Registry) {
+    day01::register(registry);
+    day02::register(registry);
+    day03::register(registry);
+    day04::register(registry);
+    day05::register(registry);
+    day06::register(registry);
+    day07::register(registry);
+    day08::register(registry);
+    day09::register(registry);
+    day10::register(registry);
+    day11::register(registry);
+    day12::register(registry);
+    day13::register(registry);
+    day14::register(registry);
+    day15::register(registry);
+    day16::register(registry);
+    day17::register(registry);
+    day18::register(registry);
+    day19::register(registry);
+    day20::register(registry);
+    day21::register(registry);
+    day22::register(registry);
+    day23::register(registry);
+    day24::register(registry);
+    day25::register(registry);
+}
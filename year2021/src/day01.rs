@@ -1,14 +1,11 @@
 /// https://adventofcode.com/2021/day/1
+use aoc_core::{Error, Registry, Result, Solution};
+use std::num::ParseIntError;
 
-#[aoc_generator(day1)]
-pub fn parse_input(input: &str) -> Vec<usize> {
-    input
-        .lines()
-        .map(|l| l.parse().expect("positive integers only"))
-        .collect()
+pub fn parse_input(input: &str) -> Result<Vec<usize>, ParseIntError> {
+    input.lines().map(|l| l.parse()).collect()
 }
 
-#[aoc(day1, part1)]
 fn part1(depths: &[usize]) -> Option<usize> {
     let sec = <&[usize]>::clone(&depths);
     let increased =
@@ -25,7 +22,6 @@ fn part1(depths: &[usize]) -> Option<usize> {
     Some(increased)
 }
 
-#[aoc(day1, part2)]
 fn part2(depths: &[usize]) -> Option<usize> {
     let sec = <&[usize]>::clone(&depths);
     let third = <&[usize]>::clone(&depths);
@@ -40,6 +36,31 @@ fn part2(depths: &[usize]) -> Option<usize> {
     Some(increased)
 }
 
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day01>();
+}
+
+struct Day01;
+
+impl Solution for Day01 {
+    const YEAR: u16 = 2021;
+    const DAY: u8 = 1;
+    type Input<'i> = Vec<usize>;
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse_input(input.trim_end_matches('\n')).map_err(Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        part1(input).ok_or(Error::NoAnswer)
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        part2(input).ok_or(Error::NoAnswer)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -58,20 +79,20 @@ mod test {
     #[test]
     fn parsing_input_test() {
         assert_eq!(
-            parse_input(DEPTHS),
+            parse_input(DEPTHS).unwrap(),
             vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263]
         )
     }
 
     #[test]
     fn part1_test() {
-        let depths = parse_input(DEPTHS);
+        let depths = parse_input(DEPTHS).unwrap();
         assert_eq!(part1(&depths), Some(7))
     }
 
     #[test]
     fn part2_test() {
-        let depths = parse_input(DEPTHS);
+        let depths = parse_input(DEPTHS).unwrap();
         assert_eq!(part2(&depths), Some(5))
     }
 }
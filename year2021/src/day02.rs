@@ -1,4 +1,5 @@
-/// https://adventofcode.com/2021/day/2
+//! https://adventofcode.com/2021/day/2
+use aoc_core::{Error, Registry, Result, Solution};
 
 use nom::{
     character::complete::{alpha1, digit1, line_ending, multispace1},
@@ -15,11 +16,9 @@ pub enum Move {
     Up(usize),
 }
 
-#[aoc_generator(day2)]
-pub fn parse_input(input: &str) -> Vec<Move> {
-    let (_, moves) = all_consuming(separated_list1(line_ending, parse_move))(input)
-        .expect("Could not parse input");
-    moves
+pub fn parse_input(input: &str) -> Result<Vec<Move>, nom::Err<nom::error::Error<&str>>> {
+    let (_, moves) = all_consuming(separated_list1(line_ending, parse_move))(input)?;
+    Ok(moves)
 }
 
 fn from_number(input: &str) -> Result<usize, std::num::ParseIntError> {
@@ -42,7 +41,6 @@ fn parse_move(input: &str) -> IResult<&str, Move> {
     )(input)
 }
 
-#[aoc(day2, part1)]
 fn part1(moves: &[Move]) -> Option<usize> {
     let pos = moves.iter().fold((0, 0), |acc, m| match m {
         Move::Forward(x) => (acc.0 + x, acc.1),
@@ -52,7 +50,6 @@ fn part1(moves: &[Move]) -> Option<usize> {
     Some(pos.0 * pos.1)
 }
 
-#[aoc(day2, part2)]
 fn part2(moves: &[Move]) -> Option<usize> {
     let pos = moves.iter().fold((0, 0, 0), |acc, m| match m {
         Move::Forward(x) => (acc.0 + x, acc.1 + (acc.2 * x), acc.2),
@@ -62,6 +59,31 @@ fn part2(moves: &[Move]) -> Option<usize> {
     Some(pos.0 * pos.1)
 }
 
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day02>();
+}
+
+struct Day02;
+
+impl Solution for Day02 {
+    const YEAR: u16 = 2021;
+    const DAY: u8 = 2;
+    type Input<'i> = Vec<Move>;
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse_input(input.trim_end_matches('\n')).map_err(Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        part1(input).ok_or(Error::NoAnswer)
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        part2(input).ok_or(Error::NoAnswer)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -76,7 +98,7 @@ forward 2";
     #[test]
     fn parsing_input_test() {
         assert_eq!(
-            parse_input(MOVES),
+            parse_input(MOVES).unwrap(),
             vec![
                 Move::Forward(5),
                 Move::Down(5),
@@ -90,13 +112,13 @@ forward 2";
 
     #[test]
     fn part1_test() {
-        let moves = parse_input(MOVES);
+        let moves = parse_input(MOVES).unwrap();
         assert_eq!(part1(&moves), Some(150))
     }
 
     #[test]
     fn part2_test() {
-        let moves = parse_input(MOVES);
+        let moves = parse_input(MOVES).unwrap();
         assert_eq!(part2(&moves), Some(900))
     }
 }
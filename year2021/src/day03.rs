@@ -1,14 +1,11 @@
 /// https://adventofcode.com/2021/day/3
+use aoc_core::{Error, Registry, Result, Solution};
+use std::num::ParseIntError;
 
-#[aoc_generator(day3)]
-pub fn parse_input(input: &str) -> Vec<u16> {
-    input
-        .lines()
-        .map(|l| u16::from_str_radix(l, 2).expect("not a binary number"))
-        .collect()
+pub fn parse_input(input: &str) -> Result<Vec<u16>, ParseIntError> {
+    input.lines().map(|l| u16::from_str_radix(l, 2)).collect()
 }
 
-#[aoc(day3, part1)]
 fn part1(diags: &[u16]) -> Option<usize> {
     let (gamma, epsilon) = get_gamma_epsilon(diags, 12);
     Some(gamma * epsilon)
@@ -26,7 +23,6 @@ fn get_gamma_epsilon(diags: &[u16], size: u8) -> (usize, usize) {
     (gamma, epsilon)
 }
 
-#[aoc(day3, part2)]
 fn part2(diags: &[u16]) -> Option<usize> {
     let (o2, co2) = get_o2_co2_ratings(diags, 12);
     Some(o2 * co2)
@@ -59,9 +55,8 @@ fn get_most_common(diags: &[u16], mask: u16) -> u8 {
     }
 }
 
-
 // I wonder if there is a way to be more memory effecient than to copy the values
-// like it is doing when length is 0. I am not modifying the original source and 
+// like it is doing when length is 0. I am not modifying the original source and
 // really just need a reference to the values in the original array. New vec makes
 // sense but copying the values doesn't
 fn filter_o2(diags: &[u16], mask: u16) -> Vec<u16> {
@@ -94,6 +89,31 @@ fn filter(diags: &[u16], mask: u16, value: u8) -> Vec<u16> {
         .collect()
 }
 
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day03>();
+}
+
+struct Day03;
+
+impl Solution for Day03 {
+    const YEAR: u16 = 2021;
+    const DAY: u8 = 3;
+    type Input<'i> = Vec<u16>;
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse_input(input.trim_end_matches('\n')).map_err(Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        part1(input).ok_or(Error::NoAnswer)
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        part2(input).ok_or(Error::NoAnswer)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -114,21 +134,21 @@ mod test {
     #[test]
     fn parsing_input_test() {
         assert_eq!(
-            parse_input(DIAG),
+            parse_input(DIAG).unwrap(),
             vec![4, 30, 22, 23, 21, 15, 7, 28, 16, 25, 2, 10]
         )
     }
 
     #[test]
     fn part1_test() {
-        let diags = parse_input(DIAG);
+        let diags = parse_input(DIAG).unwrap();
         let (gamma, epsilon) = get_gamma_epsilon(&diags, 5);
         assert_eq!(gamma * epsilon, 198)
     }
 
     #[test]
     fn part2_test() {
-        let diags = parse_input(DIAG);
+        let diags = parse_input(DIAG).unwrap();
         let (o2, co2) = get_o2_co2_ratings(&diags, 5);
         assert_eq!(o2 * co2, 230)
     }
@@ -1,8 +1,11 @@
-#[macro_use]
-extern crate aoc_runner_derive;
+use aoc_core::Registry;
 
 pub mod day01;
 pub mod day02;
 pub mod day03;
 
-aoc_lib!(year = 2021);
+pub fn register(registry: &mut Registry) {
+    day01::register(registry);
+    day02::register(registry);
+    day03::register(registry);
+}
@@ -1,86 +1,87 @@
 use anyhow::{Error, Result};
-
-const INPUT: &str = include_str!("inputs/day01.txt");
-
-pub fn run_part_a() -> Result<()> {
-    let i = parse(INPUT)?;
-    println!("{}", part_a(i)?);
-    Ok(())
-}
-
-pub fn run_part_b() -> Result<()> {
-    let i = parse(INPUT)?;
-    println!("{}", part_b(i)?);
-    Ok(())
-}
+use aoc_core::{Registry, Solution};
 
 type Parsed = Vec<Vec<isize>>;
 
 fn parse(input: &str) -> Result<Parsed> {
-    Ok(input
-        .lines()
-        .map(|i| i.parse::<isize>().ok())
-        .fold(
-            (Vec::new(), Vec::new()),
-            |(mut f, mut int), cal| match cal {
-                Some(i) => {
-                    int.push(i);
-                    (f, int)
-                }
-                None => {
-                    f.push(int);
-                    (f, Vec::new())
-                }
-            },
-        )
-        .0)
+    let (mut elves, last) = input.lines().map(|i| i.parse::<isize>().ok()).fold(
+        (Vec::new(), Vec::new()),
+        |(mut f, mut int), cal| match cal {
+            Some(i) => {
+                int.push(i);
+                (f, int)
+            }
+            None => {
+                f.push(int);
+                (f, Vec::new())
+            }
+        },
+    );
+    // Inputs do not always end with a blank line to close off the last elf.
+    if !last.is_empty() {
+        elves.push(last);
+    }
+    Ok(elves)
 }
 
-fn part_a(elf_calories: Parsed) -> Result<isize> {
+fn part_a(elf_calories: &Parsed) -> Result<isize> {
     elf_calories
-        .into_iter()
-        .map(|cal| cal.into_iter().sum::<isize>())
+        .iter()
+        .map(|cal| cal.iter().sum::<isize>())
         .max()
         .ok_or(Error::msg("could not find max"))
 }
 
-fn part_b(elf_calories: Parsed) -> Result<isize> {
+fn part_b(elf_calories: &Parsed) -> Result<isize> {
     let mut sums = elf_calories
-        .into_iter()
-        .map(|cal| cal.into_iter().sum::<isize>())
+        .iter()
+        .map(|cal| cal.iter().sum::<isize>())
         .collect::<Vec<_>>();
     sums.sort_unstable();
     sums.reverse();
     Ok(sums.into_iter().take(3).sum::<isize>())
 }
 
-#[cfg(test)]
-mod test {
-    use std::vec;
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day01>();
+}
 
-    use super::*;
+struct Day01;
+
+impl Solution for Day01 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 1;
+    type Input<'i> = Parsed;
+    type Output = isize;
 
-    const TEST_INPUT: &str = "1000
-2000
-3000
+    fn parse(input: &str) -> aoc_core::Result<Self::Input<'_>> {
+        parse(input).map_err(aoc_core::Error::parse)
+    }
 
-4000
+    fn part_one(input: &Self::Input<'_>) -> aoc_core::Result<Self::Output> {
+        part_a(input).map_err(aoc_core::Error::solve)
+    }
 
-5000
-6000
+    fn part_two(input: &Self::Input<'_>) -> aoc_core::Result<Self::Output> {
+        part_b(input).map_err(aoc_core::Error::solve)
+    }
+}
 
-7000
-8000
-9000
+#[cfg(test)]
+mod test {
+    use std::vec;
 
-10000
+    use super::*;
+    use aoc_core::Part;
 
-";
+    fn test_input() -> String {
+        crate::example(1, Part::One)
+    }
 
     #[test]
     fn test_parse_input() {
         assert_eq!(
-            parse(TEST_INPUT).unwrap(),
+            parse(&test_input()).unwrap(),
             vec![
                 vec![1000, 2000, 3000],
                 vec![4000],
@@ -91,15 +92,5 @@ mod test {
         );
     }
 
-    #[test]
-    fn test_part_a() {
-        let parsed = parse(TEST_INPUT).unwrap();
-        assert_eq!(part_a(parsed).unwrap(), 24000);
-    }
-
-    #[test]
-    fn test_part_b() {
-        let parsed = parse(TEST_INPUT).unwrap();
-        assert_eq!(part_b(parsed).unwrap(), 45000);
-    }
+    aoc_core::derive_tests!(Day01);
 }
@@ -1,20 +1,7 @@
 use anyhow::{Error, Result};
+use aoc_core::{Registry, Solution};
 
-const INPUT: &str = include_str!("inputs/day02.txt");
-
-pub fn run_part_a() -> Result<()> {
-    let i = parse_a(INPUT)?;
-    println!("{}", part_a(i)?);
-    Ok(())
-}
-
-pub fn run_part_b() -> Result<()> {
-    let i = parse_b(INPUT)?;
-    println!("{}", part_b(i)?);
-    Ok(())
-}
-
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 enum Choice {
     Rock,
     Paper,
@@ -35,7 +22,7 @@ impl TryFrom<char> for Choice {
 }
 
 impl Choice {
-    fn into_score(&self) -> isize {
+    fn score(&self) -> isize {
         match self {
             Choice::Rock => 1,
             Choice::Paper => 2,
@@ -59,27 +46,27 @@ fn parse_a(input: &str) -> Result<ParsedA> {
         .collect())
 }
 
-fn part_a(rounds: ParsedA) -> Result<isize> {
+fn part_a(rounds: &ParsedA) -> Result<isize> {
     Ok(rounds
-        .into_iter()
+        .iter()
         .map(|round| {
-            round.1.into_score()
+            round.1.score()
                 + match (round.0, round.1) {
                     (Choice::Rock, Choice::Rock)
                     | (Choice::Paper, Choice::Paper)
-                    | (Choice::Scissors, Choice::Scissors) => Resolution::Draw.into_score(),
+                    | (Choice::Scissors, Choice::Scissors) => Resolution::Draw.score(),
                     (Choice::Rock, Choice::Paper)
                     | (Choice::Paper, Choice::Scissors)
-                    | (Choice::Scissors, Choice::Rock) => Resolution::Win.into_score(),
+                    | (Choice::Scissors, Choice::Rock) => Resolution::Win.score(),
                     (Choice::Rock, Choice::Scissors)
                     | (Choice::Paper, Choice::Rock)
-                    | (Choice::Scissors, Choice::Paper) => Resolution::Lose.into_score(),
+                    | (Choice::Scissors, Choice::Paper) => Resolution::Lose.score(),
                 }
         })
         .sum())
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 enum Resolution {
     Win,
     Lose,
@@ -100,7 +87,7 @@ impl TryFrom<char> for Resolution {
 }
 
 impl Resolution {
-    fn into_score(&self) -> isize {
+    fn score(&self) -> isize {
         match self {
             Resolution::Win => 6,
             Resolution::Lose => 0,
@@ -124,43 +111,69 @@ fn parse_b(input: &str) -> Result<ParsedB> {
         .collect())
 }
 
-fn part_b(rounds: ParsedB) -> Result<isize> {
+fn part_b(rounds: &ParsedB) -> Result<isize> {
     Ok(rounds
-        .into_iter()
+        .iter()
         .map(|round| {
-            round.1.into_score()
+            round.1.score()
                 + match (round.0, round.1) {
-                    (i, Resolution::Draw) => i.into_score(),
+                    (i, Resolution::Draw) => i.score(),
                     (Choice::Rock, Resolution::Win) | (Choice::Scissors, Resolution::Lose) => {
-                        Choice::Paper.into_score()
+                        Choice::Paper.score()
                     }
                     (Choice::Rock, Resolution::Lose) | (Choice::Paper, Resolution::Win) => {
-                        Choice::Scissors.into_score()
+                        Choice::Scissors.score()
                     }
                     (Choice::Paper, Resolution::Lose) | (Choice::Scissors, Resolution::Win) => {
-                        Choice::Rock.into_score()
+                        Choice::Rock.score()
                     }
                 }
         })
         .sum())
 }
 
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day02>();
+}
+
+struct Day02;
+
+impl Solution for Day02 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 2;
+    type Input<'i> = (ParsedA, ParsedB);
+    type Output = isize;
+
+    fn parse(input: &str) -> aoc_core::Result<Self::Input<'_>> {
+        parse_a(input)
+            .and_then(|a| Ok((a, parse_b(input)?)))
+            .map_err(aoc_core::Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> aoc_core::Result<Self::Output> {
+        part_a(&input.0).map_err(aoc_core::Error::solve)
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> aoc_core::Result<Self::Output> {
+        part_b(&input.1).map_err(aoc_core::Error::solve)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::vec;
 
     use super::*;
+    use aoc_core::Part;
 
-    const TEST_INPUT: &str = "A Y
-B X
-C Z
-
-";
+    fn test_input() -> String {
+        crate::example(2, Part::One)
+    }
 
     #[test]
     fn test_parse_input() {
         assert_eq!(
-            parse_a(TEST_INPUT).unwrap(),
+            parse_a(&test_input()).unwrap(),
             vec![
                 (Choice::Rock, Choice::Paper),
                 (Choice::Paper, Choice::Rock),
@@ -168,7 +181,7 @@ C Z
             ]
         );
         assert_eq!(
-            parse_b(TEST_INPUT).unwrap(),
+            parse_b(&test_input()).unwrap(),
             vec![
                 (Choice::Rock, Resolution::Draw),
                 (Choice::Paper, Resolution::Lose),
@@ -177,15 +190,5 @@ C Z
         );
     }
 
-    #[test]
-    fn test_part_a() {
-        let parsed = parse_a(TEST_INPUT).unwrap();
-        assert_eq!(part_a(parsed).unwrap(), 15);
-    }
-
-    #[test]
-    fn test_part_b() {
-        let parsed = parse_b(TEST_INPUT).unwrap();
-        assert_eq!(part_b(parsed).unwrap(), 12);
-    }
+    aoc_core::derive_tests!(Day02);
 }
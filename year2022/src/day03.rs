@@ -1,18 +1,5 @@
 use anyhow::Result;
-
-const INPUT: &str = include_str!("inputs/day03.txt");
-
-pub fn run_part_a() -> Result<()> {
-    let i = parse_a(INPUT)?;
-    println!("{}", part_a(i)?);
-    Ok(())
-}
-
-pub fn run_part_b() -> Result<()> {
-    let i = parse_b(INPUT)?;
-    println!("{}", part_b(i)?);
-    Ok(())
-}
+use aoc_core::{Registry, Solution};
 
 type ParsedA = Vec<(Vec<char>, Vec<char>)>;
 
@@ -38,10 +25,12 @@ fn priority(c: char) -> u8 {
     (u8::try_from(c).unwrap() & 0x1F) + (u8::from(c.is_ascii_uppercase()) * 26)
 }
 
-fn part_a(sacks: ParsedA) -> Result<isize> {
+fn part_a(sacks: &ParsedA) -> Result<isize> {
     Ok(sacks
-        .into_iter()
-        .map(|(mut compa, mut compb)| {
+        .iter()
+        .map(|(compa, compb)| {
+            let mut compa = compa.clone();
+            let mut compb = compb.clone();
             compa.sort_unstable();
             compb.sort_unstable();
             let mut a_iter = compa.into_iter();
@@ -77,10 +66,9 @@ fn parse_b(input: &str) -> Result<ParsedB> {
         .collect())
 }
 
-fn part_b(sacks: ParsedB) -> Result<isize> {
+fn part_b(sacks: &ParsedB) -> Result<isize> {
     Ok(sacks
         .chunks(3)
-        .into_iter()
         .map(|group| {
             let mut groupa = group[0].to_owned();
             groupa.sort_unstable();
@@ -118,25 +106,48 @@ fn part_b(sacks: ParsedB) -> Result<isize> {
         .sum::<isize>())
 }
 
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day03>();
+}
+
+struct Day03;
+
+impl Solution for Day03 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 3;
+    type Input<'i> = (ParsedA, ParsedB);
+    type Output = isize;
+
+    fn parse(input: &str) -> aoc_core::Result<Self::Input<'_>> {
+        parse_a(input)
+            .and_then(|a| Ok((a, parse_b(input)?)))
+            .map_err(aoc_core::Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> aoc_core::Result<Self::Output> {
+        part_a(&input.0).map_err(aoc_core::Error::solve)
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> aoc_core::Result<Self::Output> {
+        part_b(&input.1).map_err(aoc_core::Error::solve)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::vec;
 
     use super::*;
+    use aoc_core::Part;
 
-    const TEST_INPUT: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
-jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
-PmmdzqPrVvPwwTWBwg
-wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
-ttgJtRGJQctTZtZT
-CrZsJsPPZsGzwwsLwLmpwMDw
-
-";
+    fn test_input() -> String {
+        crate::example(3, Part::One)
+    }
 
     #[test]
     fn test_parse_input() {
         assert_eq!(
-            parse_a(TEST_INPUT).unwrap(),
+            parse_a(&test_input()).unwrap(),
             vec![
                 (
                     "vJrwpWtwJgWr".chars().collect::<Vec<_>>(),
@@ -165,7 +176,7 @@ CrZsJsPPZsGzwwsLwLmpwMDw
             ]
         );
         assert_eq!(
-            parse_b(TEST_INPUT).unwrap(),
+            parse_b(&test_input()).unwrap(),
             vec![
                 "vJrwpWtwJgWrhcsFMMfFFhFp".chars().collect::<Vec<_>>(),
                 "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL"
@@ -187,15 +198,5 @@ CrZsJsPPZsGzwwsLwLmpwMDw
         assert_eq!(priority('z'), 26);
     }
 
-    #[test]
-    fn test_part_a() {
-        let parsed = parse_a(TEST_INPUT).unwrap();
-        assert_eq!(part_a(parsed).unwrap(), 157);
-    }
-
-    #[test]
-    fn test_part_b() {
-        let parsed = parse_b(TEST_INPUT).unwrap();
-        assert_eq!(part_b(parsed).unwrap(), 70);
-    }
+    aoc_core::derive_tests!(Day03);
 }
@@ -1,22 +1,11 @@
 use std::ops::RangeInclusive;
 
 use anyhow::Result;
-
-const INPUT: &str = include_str!("inputs/day04.txt");
-
-pub fn run_part_a() -> Result<()> {
-    let i = parse(INPUT)?;
-    println!("{}", part_a(i)?);
-    Ok(())
-}
-
-pub fn run_part_b() -> Result<()> {
-    let i = parse(INPUT)?;
-    println!("{}", part_b(i)?);
-    Ok(())
-}
+use aoc_common::IntervalSet;
+use aoc_core::{Registry, Solution};
 
 type Parsed = Vec<(RangeInclusive<isize>, RangeInclusive<isize>)>;
+type Sections = IntervalSet<isize>;
 
 fn parse(input: &str) -> Result<Parsed> {
     Ok(input
@@ -27,8 +16,7 @@ fn parse(input: &str) -> Result<Parsed> {
             }
             let mut r = line
                 .split(',')
-                .map(|r| r.split('-').map(|n| n.parse::<isize>().unwrap()))
-                .flatten();
+                .flat_map(|r| r.split('-').map(|n| n.parse::<isize>().unwrap()));
             Some((
                 RangeInclusive::new(r.next().unwrap(), r.next().unwrap()),
                 RangeInclusive::new(r.next().unwrap(), r.next().unwrap()),
@@ -37,48 +25,72 @@ fn parse(input: &str) -> Result<Parsed> {
         .collect())
 }
 
-#[inline]
+fn sections(a: &RangeInclusive<isize>, b: &RangeInclusive<isize>) -> (Sections, Sections) {
+    (Sections::from(a.clone()), Sections::from(b.clone()))
+}
+
 fn fully_contains(a: &RangeInclusive<isize>, b: &RangeInclusive<isize>) -> bool {
-    (b.contains(a.start()) && b.contains(a.end())) || (a.contains(b.start()) && a.contains(b.end()))
+    let (a, b) = sections(a, b);
+    a.is_subset(&b) || b.is_subset(&a)
 }
 
-fn part_a(assignments: Parsed) -> Result<isize> {
+fn part_a(assignments: &Parsed) -> Result<isize> {
     Ok(assignments
-        .into_iter()
-        .map(|(a, b)| if fully_contains(&a, &b) { 1 } else { 0 })
+        .iter()
+        .map(|(a, b)| if fully_contains(a, b) { 1 } else { 0 })
         .sum::<isize>())
 }
 
-#[inline]
 fn semi_contains(a: &RangeInclusive<isize>, b: &RangeInclusive<isize>) -> bool {
-    b.contains(a.start()) || b.contains(a.end()) || a.contains(b.start()) || a.contains(b.end())
+    let (a, b) = sections(a, b);
+    !a.is_disjoint(&b)
 }
 
-fn part_b(assignments: Parsed) -> Result<isize> {
+fn part_b(assignments: &Parsed) -> Result<isize> {
     Ok(assignments
-        .into_iter()
-        .map(|(a, b)| if semi_contains(&a, &b) { 1 } else { 0 })
+        .iter()
+        .map(|(a, b)| if semi_contains(a, b) { 1 } else { 0 })
         .sum::<isize>())
 }
 
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day04>();
+}
+
+struct Day04;
+
+impl Solution for Day04 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 4;
+    type Input<'i> = Parsed;
+    type Output = isize;
+
+    fn parse(input: &str) -> aoc_core::Result<Self::Input<'_>> {
+        parse(input).map_err(aoc_core::Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> aoc_core::Result<Self::Output> {
+        part_a(input).map_err(aoc_core::Error::solve)
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> aoc_core::Result<Self::Output> {
+        part_b(input).map_err(aoc_core::Error::solve)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use aoc_core::Part;
 
-    const TEST_INPUT: &str = "
-2-4,6-8
-2-3,4-5
-5-7,7-9
-2-8,3-7
-6-6,4-6
-2-6,4-8
-
-";
+    fn test_input() -> String {
+        crate::example(4, Part::One)
+    }
 
     #[test]
     fn test_parse_input() {
         assert_eq!(
-            parse(TEST_INPUT).unwrap(),
+            parse(&test_input()).unwrap(),
             vec![
                 (RangeInclusive::new(2, 4), RangeInclusive::new(6, 8)),
                 (RangeInclusive::new(2, 3), RangeInclusive::new(4, 5)),
@@ -90,15 +102,5 @@ mod test {
         )
     }
 
-    #[test]
-    fn test_part_a() {
-        let parsed = parse(TEST_INPUT).unwrap();
-        assert_eq!(part_a(parsed).unwrap(), 2);
-    }
-
-    #[test]
-    fn test_part_b() {
-        let parsed = parse(TEST_INPUT).unwrap();
-        assert_eq!(part_b(parsed).unwrap(), 4);
-    }
+    aoc_core::derive_tests!(Day04);
 }
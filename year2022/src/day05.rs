@@ -1,21 +1,8 @@
 use anyhow::Result;
+use aoc_core::{Registry, Solution};
 use pest_consume::{match_nodes, Parser};
 
-const INPUT: &str = include_str!("inputs/day05.txt");
-
-pub fn run_part_a() -> Result<()> {
-    let i = parse(INPUT)?;
-    println!("{}", part_a(i)?);
-    Ok(())
-}
-
-pub fn run_part_b() -> Result<()> {
-    let i = parse(INPUT)?;
-    println!("{}", part_b(i)?);
-    Ok(())
-}
-
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 struct Parsed {
     stack: Vec<Vec<char>>,
     moves: Vec<(usize, usize, usize)>,
@@ -129,7 +116,8 @@ fn parse(input: &str) -> Result<Parsed> {
     Day05Parser::file(input).map_err(|e| e.into())
 }
 
-fn part_a(mut cargo: Parsed) -> Result<String> {
+fn part_a(cargo: &Parsed) -> Result<String> {
+    let mut cargo = cargo.clone();
     for (how_many, from, to) in cargo.moves {
         for _i in 0..how_many {
             let c = cargo.stack[from].pop();
@@ -139,14 +127,12 @@ fn part_a(mut cargo: Parsed) -> Result<String> {
     Ok(cargo
         .stack
         .into_iter()
-        .filter_map(|s| match s.last() {
-            Some(&c) => Some(c),
-            None => None,
-        })
+        .filter_map(|s| s.last().copied())
         .collect::<String>())
 }
 
-fn part_b(mut cargo: Parsed) -> Result<String> {
+fn part_b(cargo: &Parsed) -> Result<String> {
+    let mut cargo = cargo.clone();
     for (how_many, from, to) in cargo.moves {
         let from_len = cargo.stack[from].len();
         let pop = cargo.stack[from]
@@ -157,33 +143,48 @@ fn part_b(mut cargo: Parsed) -> Result<String> {
     Ok(cargo
         .stack
         .into_iter()
-        .filter_map(|s| match s.last() {
-            Some(&c) => Some(c),
-            None => None,
-        })
+        .filter_map(|s| s.last().copied())
         .collect::<String>())
 }
 
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day05>();
+}
+
+struct Day05;
+
+impl Solution for Day05 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 5;
+    type Input<'i> = Parsed;
+    type Output = String;
+
+    fn parse(input: &str) -> aoc_core::Result<Self::Input<'_>> {
+        parse(input).map_err(aoc_core::Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> aoc_core::Result<Self::Output> {
+        part_a(input).map_err(aoc_core::Error::solve)
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> aoc_core::Result<Self::Output> {
+        part_b(input).map_err(aoc_core::Error::solve)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use aoc_core::Part;
 
-    const TEST_INPUT: &str = "    [D]    
-[N] [C]    
-[Z] [M] [P]
- 1   2   3 
-
-move 1 from 2 to 1
-move 3 from 1 to 3
-move 2 from 2 to 1
-move 1 from 1 to 2
-
-";
+    fn test_input() -> String {
+        crate::example(5, Part::One)
+    }
 
     #[test]
     fn test_parse_input() {
         assert_eq!(
-            parse(TEST_INPUT).unwrap(),
+            parse(&test_input()).unwrap(),
             Parsed {
                 stack: vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']],
                 moves: vec![(1, 1, 0), (3, 0, 2), (2, 1, 0), (1, 0, 1)]
@@ -191,15 +192,5 @@ move 1 from 1 to 2
         );
     }
 
-    #[test]
-    fn test_part_a() {
-        let parsed = parse(TEST_INPUT).unwrap();
-        assert_eq!(part_a(parsed).unwrap(), "CMZ");
-    }
-
-    #[test]
-    fn test_part_b() {
-        let parsed = parse(TEST_INPUT).unwrap();
-        assert_eq!(part_b(parsed).unwrap(), "MCD");
-    }
+    aoc_core::derive_tests!(Day05);
 }
@@ -1,18 +1,5 @@
 use anyhow::{Error, Ok, Result};
-
-const INPUT: &str = include_str!("inputs/day06.txt");
-
-pub fn run_part_a() -> Result<()> {
-    let i = parse(INPUT)?;
-    println!("{}", part_a(i)?);
-    Ok(())
-}
-
-pub fn run_part_b() -> Result<()> {
-    let i = parse(INPUT)?;
-    println!("{}", part_b(i)?);
-    Ok(())
-}
+use aoc_core::{Registry, Solution};
 
 type Parsed = Vec<char>;
 
@@ -20,7 +7,7 @@ fn parse(input: &str) -> Result<Parsed> {
     Ok(input.chars().collect())
 }
 
-fn part_a(signal: Parsed) -> Result<usize> {
+fn part_a(signal: &Parsed) -> Result<usize> {
     for (i, window) in signal.windows(4).enumerate() {
         let mut w = window.to_owned();
         w.sort_unstable();
@@ -32,7 +19,7 @@ fn part_a(signal: Parsed) -> Result<usize> {
     Err(Error::msg("starting sequence not found"))
 }
 
-fn part_b(signal: Parsed) -> Result<usize> {
+fn part_b(signal: &Parsed) -> Result<usize> {
     for (i, window) in signal.windows(14).enumerate() {
         let mut w = window.to_owned();
         w.sort_unstable();
@@ -44,6 +31,31 @@ fn part_b(signal: Parsed) -> Result<usize> {
     Err(Error::msg("starting sequence not found"))
 }
 
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day06>();
+}
+
+struct Day06;
+
+impl Solution for Day06 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 6;
+    type Input<'i> = Parsed;
+    type Output = usize;
+
+    fn parse(input: &str) -> aoc_core::Result<Self::Input<'_>> {
+        parse(input).map_err(aoc_core::Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> aoc_core::Result<Self::Output> {
+        part_a(input).map_err(aoc_core::Error::solve)
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> aoc_core::Result<Self::Output> {
+        part_b(input).map_err(aoc_core::Error::solve)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -51,36 +63,38 @@ mod test {
     #[test]
     fn test_part_a() {
         let parsed = parse("mjqjpqmgbljsphdztnvjfqwrcgsmlb").unwrap();
-        assert_eq!(part_a(parsed).unwrap(), 7);
+        assert_eq!(part_a(&parsed).unwrap(), 7);
 
         let parsed = parse("bvwbjplbgvbhsrlpgdmjqwftvncz").unwrap();
-        assert_eq!(part_a(parsed).unwrap(), 5);
+        assert_eq!(part_a(&parsed).unwrap(), 5);
 
         let parsed = parse("nppdvjthqldpwncqszvftbrmjlhg").unwrap();
-        assert_eq!(part_a(parsed).unwrap(), 6);
+        assert_eq!(part_a(&parsed).unwrap(), 6);
 
         let parsed = parse("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg").unwrap();
-        assert_eq!(part_a(parsed).unwrap(), 10);
+        assert_eq!(part_a(&parsed).unwrap(), 10);
 
         let parsed = parse("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw").unwrap();
-        assert_eq!(part_a(parsed).unwrap(), 11);
+        assert_eq!(part_a(&parsed).unwrap(), 11);
     }
 
     #[test]
     fn test_part_b() {
         let parsed = parse("mjqjpqmgbljsphdztnvjfqwrcgsmlb").unwrap();
-        assert_eq!(part_b(parsed).unwrap(), 19);
+        assert_eq!(part_b(&parsed).unwrap(), 19);
 
         let parsed = parse("bvwbjplbgvbhsrlpgdmjqwftvncz").unwrap();
-        assert_eq!(part_b(parsed).unwrap(), 23);
+        assert_eq!(part_b(&parsed).unwrap(), 23);
 
         let parsed = parse("nppdvjthqldpwncqszvftbrmjlhg").unwrap();
-        assert_eq!(part_b(parsed).unwrap(), 23);
+        assert_eq!(part_b(&parsed).unwrap(), 23);
 
         let parsed = parse("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg").unwrap();
-        assert_eq!(part_b(parsed).unwrap(), 29);
+        assert_eq!(part_b(&parsed).unwrap(), 29);
 
         let parsed = parse("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw").unwrap();
-        assert_eq!(part_b(parsed).unwrap(), 26);
+        assert_eq!(part_b(&parsed).unwrap(), 26);
     }
+
+    aoc_core::derive_tests!(Day06);
 }
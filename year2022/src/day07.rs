@@ -1,22 +1,9 @@
 use std::{cell::RefCell, fmt::Debug, rc::Rc};
 
 use anyhow::Result;
+use aoc_core::{Registry, Solution};
 use pest_consume::{match_nodes, Parser};
 
-const INPUT: &str = include_str!("inputs/day07.txt");
-
-pub fn run_part_a() -> Result<()> {
-    let i = build(parse(INPUT)?);
-    println!("{}", part_a(i)?);
-    Ok(())
-}
-
-pub fn run_part_b() -> Result<()> {
-    let i = build(parse(INPUT)?);
-    println!("{}", part_b(i)?);
-    Ok(())
-}
-
 #[derive(Debug, PartialEq)]
 enum Command<'a> {
     ChangeDirectory(Directory<'a>),
@@ -52,8 +39,8 @@ struct Tree {
 
 impl Debug for Tree {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.size.is_some() {
-            writeln!(f, "- {} (file, size={})", self.name, self.size.unwrap())?;
+        if let Some(size) = self.size {
+            writeln!(f, "- {} (file, size={})", self.name, size)?;
         } else {
             writeln!(f, "- {} (dir)", self.name)?;
         }
@@ -175,11 +162,11 @@ impl Day07Parser {
         Ok(Directory::Up)
     }
 
-    fn dir_name(input: Node) -> PResult<&str> {
+    fn dir_name(input: Node<'_>) -> PResult<&str> {
         Ok(input.as_str())
     }
 
-    fn file_name(input: Node) -> PResult<&str> {
+    fn file_name(input: Node<'_>) -> PResult<&str> {
         Ok(input.as_str())
     }
 
@@ -192,7 +179,7 @@ impl Day07Parser {
     }
 }
 
-fn parse(input: &str) -> Result<Parsed> {
+fn parse(input: &str) -> Result<Parsed<'_>> {
     let inputs = Day07Parser::parse(Rule::file, input)?;
     let input = inputs.single()?;
     Day07Parser::file(input).map_err(|e| e.into())
@@ -205,7 +192,7 @@ fn build(raw: Parsed) -> Built {
             Prompt::Command(c) => match c {
                 Command::ChangeDirectory(d) => match d {
                     Directory::Root => {
-                        if directory.len() == 0 {
+                        if directory.is_empty() {
                             directory.push(Rc::new(RefCell::new(Tree {
                                 name: "/".to_owned(),
                                 parent: None,
@@ -286,11 +273,11 @@ fn build(raw: Parsed) -> Built {
         }
     }
     directory.reverse();
-    let foo = (*directory.pop().unwrap()).clone().into_inner();
-    foo
+
+    (*directory.pop().unwrap()).clone().into_inner()
 }
 
-fn part_a(tree: Built) -> Result<usize> {
+fn part_a(tree: &Built) -> Result<usize> {
     Ok(tree
         .list_all_directories()
         .iter()
@@ -307,7 +294,7 @@ fn part_a(tree: Built) -> Result<usize> {
 const AVAILABLE: usize = 70_000_000;
 const NEEDED_UNUSED: usize = 30_000_000;
 
-fn part_b(tree: Built) -> Result<usize> {
+fn part_b(tree: &Built) -> Result<usize> {
     let free = AVAILABLE - tree.get_size();
     let need_to_free = NEEDED_UNUSED - free;
     Ok(tree
@@ -324,40 +311,44 @@ fn part_b(tree: Built) -> Result<usize> {
         .unwrap())
 }
 
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day07>();
+}
+
+struct Day07;
+
+impl Solution for Day07 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 7;
+    type Input<'i> = Built;
+    type Output = usize;
+
+    fn parse(input: &str) -> aoc_core::Result<Self::Input<'_>> {
+        parse(input).map(build).map_err(aoc_core::Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> aoc_core::Result<Self::Output> {
+        part_a(input).map_err(aoc_core::Error::solve)
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> aoc_core::Result<Self::Output> {
+        part_b(input).map_err(aoc_core::Error::solve)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use aoc_core::Part;
 
-    const TEST_INPUT: &str = "$ cd /
-$ ls
-dir a
-14848514 b.txt
-8504156 c.dat
-dir d
-$ cd a
-$ ls
-dir e
-29116 f
-2557 g
-62596 h.lst
-$ cd e
-$ ls
-584 i
-$ cd ..
-$ cd ..
-$ cd d
-$ ls
-4060174 j
-8033020 d.log
-5626152 d.ext
-7214296 k
-
-";
+    fn test_input() -> String {
+        crate::example(7, Part::One)
+    }
 
     #[test]
     fn test_parse() {
         assert_eq!(
-            parse(TEST_INPUT).unwrap(),
+            parse(&test_input()).unwrap(),
             vec![
                 Prompt::Command(Command::ChangeDirectory(Directory::Root)),
                 Prompt::Command(Command::ListDirectory),
@@ -404,20 +395,10 @@ $ ls
 
     #[test]
     fn test_build_tree() {
-        let parsed = parse(TEST_INPUT).unwrap();
-        let tree = build(parsed);
+        let input = test_input();
+        let tree = build(parse(&input).unwrap());
         assert_eq!(format!("{:?}", tree), EXPECTED_FMT);
     }
 
-    #[test]
-    fn test_part_a() {
-        let built = build(parse(TEST_INPUT).unwrap());
-        assert_eq!(part_a(built).unwrap(), 95437)
-    }
-
-    #[test]
-    fn test_part_b() {
-        let built = build(parse(TEST_INPUT).unwrap());
-        assert_eq!(part_b(built).unwrap(), 24933642)
-    }
+    aoc_core::derive_tests!(Day07);
 }
@@ -1,136 +1,87 @@
 use anyhow::{Ok, Result};
+use aoc_common::{Grid, Wrap, ORTHOGONAL};
+use aoc_core::{Registry, Solution};
 
-const INPUT: &str = include_str!("inputs/day08.txt");
-
-pub fn run_part_a() -> Result<()> {
-    let i = parse(INPUT)?;
-    println!("{}", part_a(i)?);
-    Ok(())
-}
-
-pub fn run_part_b() -> Result<()> {
-    let i = parse(INPUT)?;
-    println!("{}", part_b(i)?);
-    Ok(())
-}
-
-type Parsed = (usize, usize, Vec<u8>);
+type Parsed = Grid<u8>;
 
 fn parse(input: &str) -> Result<Parsed> {
-    Ok(input
-        .lines()
-        .filter_map(|l| {
-            if l.is_empty() {
-                return None;
-            }
-            Some(l.chars().map(|c| c.to_digit(10).unwrap() as u8))
-        })
-        .fold(
-            (0usize, 0usize, Vec::new()),
-            |(mut x, mut y, mut trees), t| {
-                y += 1;
-                trees.extend(t);
-                if x == 0 {
-                    x = trees.len();
-                }
-                (x, y, trees)
-            },
-        ))
+    Ok(Grid::parse_with(input, |c| {
+        c.to_digit(10).map(|h| h as u8).ok_or("not a tree height")
+    })?)
 }
 
-fn part_a((x_max, y_max, trees): Parsed) -> Result<usize> {
+fn part_a(trees: &Parsed) -> Result<usize> {
     Ok(trees
         .iter()
-        .enumerate()
-        .filter_map(|(i, t)| {
-            let x = i % x_max; //2
-            let y = i / x_max; //1
-            if y == 0 || y == y_max - 1 || x == 0 || x == x_max - 1 {
-                return None;
-            }
-            let up = (0..y).map(|v| trees[v * x_max + x]).max().unwrap();
-            let down = (y + 1..y_max).map(|v| trees[v * x_max + x]).max().unwrap();
-            let left = (0..x).map(|h| trees[h + y * x_max]).max().unwrap();
-            let right = (x + 1..x_max).map(|h| trees[h + y * x_max]).max().unwrap();
-            if &up < t || &down < t || &left < t || &right < t {
-                Some(t)
-            } else {
-                None
-            }
+        .filter(|&(pos, t)| {
+            ORTHOGONAL
+                .into_iter()
+                .any(|dir| trees.ray(pos, dir, Wrap::None).all(|p| &trees[p] < t))
         })
-        .count()
-        + (x_max * 2)
-        + (y_max * 2)
-        - 4)
+        .count())
 }
 
-fn part_b((x_max, y_max, trees): Parsed) -> Result<usize> {
+fn part_b(trees: &Parsed) -> Result<usize> {
     Ok(trees
         .iter()
-        .enumerate()
-        .map(|(i, t)| {
-            let x = i % x_max; //2
-            let y = i / x_max; //1
-            if y == 0 || y == y_max - 1 || x == 0 || x == x_max - 1 {
-                return 0;
-            }
-            let up = (0..y)
-                .rev()
-                .map(|v| trees[v * x_max + x])
-                .enumerate()
-                .find(|(_, u)| u >= t)
-                .or(Some((y - 1, 0)))
-                .unwrap()
-                .0
-                + 1;
-            let down = (y + 1..y_max)
-                .map(|v| trees[v * x_max + x])
-                .enumerate()
-                .find(|(_, u)| u >= t)
-                .or(Some((y_max - y - 2, 0)))
-                .unwrap()
-                .0
-                + 1;
-            let left = (0..x)
-                .rev()
-                .map(|h| trees[h + y * x_max])
-                .enumerate()
-                .find(|(_, u)| u >= t)
-                .or(Some((x - 1, 0)))
-                .unwrap()
-                .0
-                + 1;
-            let right = (x + 1..x_max)
-                .map(|h| trees[h + y * x_max])
-                .enumerate()
-                .find(|(_, u)| u >= t)
-                .or(Some((x_max - x - 2, 0)))
-                .unwrap()
-                .0
-                + 1;
-            up * down * left * right
+        .map(|(pos, t)| {
+            ORTHOGONAL
+                .into_iter()
+                .map(|dir| {
+                    let mut seen = 0;
+                    for p in trees.ray(pos, dir, Wrap::None) {
+                        seen += 1;
+                        if &trees[p] >= t {
+                            break;
+                        }
+                    }
+                    seen
+                })
+                .product()
         })
         .max()
         .unwrap())
 }
 
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day08>();
+}
+
+struct Day08;
+
+impl Solution for Day08 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 8;
+    type Input<'i> = Parsed;
+    type Output = usize;
+
+    fn parse(input: &str) -> aoc_core::Result<Self::Input<'_>> {
+        parse(input).map_err(aoc_core::Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> aoc_core::Result<Self::Output> {
+        part_a(input).map_err(aoc_core::Error::solve)
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> aoc_core::Result<Self::Output> {
+        part_b(input).map_err(aoc_core::Error::solve)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use aoc_core::Part;
 
-    const TEST_INPUT: &str = "30373
-25512
-65332
-33549
-35390
-
-";
+    fn test_input() -> String {
+        crate::example(8, Part::One)
+    }
 
     #[test]
     fn test_parse() {
         assert_eq!(
-            parse(TEST_INPUT).unwrap(),
-            (
+            parse(&test_input()).unwrap(),
+            Grid::from_vec(
                 5,
                 5,
                 "3037325512653323354935390"
@@ -138,18 +89,9 @@ mod test {
                     .map(|c| c.to_digit(10).unwrap() as u8)
                     .collect::<Vec<_>>()
             )
+            .unwrap()
         );
     }
 
-    #[test]
-    fn test_part_a() {
-        let parsed = parse(TEST_INPUT).unwrap();
-        assert_eq!(part_a(parsed).unwrap(), 21);
-    }
-
-    #[test]
-    fn test_part_b() {
-        let parsed = parse(TEST_INPUT).unwrap();
-        assert_eq!(part_b(parsed).unwrap(), 8);
-    }
+    aoc_core::derive_tests!(Day08);
 }
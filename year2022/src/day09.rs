@@ -1,20 +1,7 @@
 use std::collections::HashSet;
 
 use anyhow::{Ok, Result};
-
-const INPUT: &str = include_str!("inputs/day09.txt");
-
-pub fn run_part_a() -> Result<()> {
-    let i = parse(INPUT)?;
-    println!("{}", part_a(i)?);
-    Ok(())
-}
-
-pub fn run_part_b() -> Result<()> {
-    let i = parse(INPUT)?;
-    println!("{}", part_b(i)?);
-    Ok(())
-}
+use aoc_core::{Registry, Solution};
 
 #[derive(Debug, PartialEq)]
 enum Move {
@@ -48,8 +35,7 @@ fn parse(input: &str) -> Result<Parsed> {
 
 fn move_tail(head: &(isize, isize), tail: &mut (isize, isize)) {
     let is_adjacent = (head.0 - 1..=head.0 + 1)
-        .map(|x| (head.1 - 1..=head.1 + 1).map(move |y| (x, y)))
-        .flatten()
+        .flat_map(|x| (head.1 - 1..=head.1 + 1).map(move |y| (x, y)))
         .any(|p| p.0 == tail.0 && p.1 == tail.1);
     if is_adjacent {
         return;
@@ -84,41 +70,41 @@ fn move_tail(head: &(isize, isize), tail: &mut (isize, isize)) {
 fn move_tails(knots: &mut [(isize, isize); 10]) {
     for i in 1..10 {
         let h = knots.get(i - 1).unwrap();
-        let mut t = knots.get(i).unwrap().clone();
-        move_tail(&h, &mut t);
+        let mut t = *knots.get(i).unwrap();
+        move_tail(h, &mut t);
         knots[i] = t;
     }
 }
 
-fn part_a(movement: Parsed) -> Result<usize> {
+fn part_a(movement: &Parsed) -> Result<usize> {
     let mut head = (0, 0);
     let mut tail = (0, 0);
     let mut set = HashSet::new();
     for m in movement {
         match m {
             Move::Up(s) => {
-                for _ in 0..s {
+                for _ in 0..*s {
                     head.1 += 1;
                     move_tail(&head, &mut tail);
                     set.insert(tail);
                 }
             }
             Move::Down(s) => {
-                for _ in 0..s {
+                for _ in 0..*s {
                     head.1 -= 1;
                     move_tail(&head, &mut tail);
                     set.insert(tail);
                 }
             }
             Move::Right(s) => {
-                for _ in 0..s {
+                for _ in 0..*s {
                     head.0 += 1;
                     move_tail(&head, &mut tail);
                     set.insert(tail);
                 }
             }
             Move::Left(s) => {
-                for _ in 0..s {
+                for _ in 0..*s {
                     head.0 -= 1;
                     move_tail(&head, &mut tail);
                     set.insert(tail);
@@ -129,34 +115,34 @@ fn part_a(movement: Parsed) -> Result<usize> {
     Ok(set.len())
 }
 
-fn part_b(movement: Parsed) -> Result<usize> {
+fn part_b(movement: &Parsed) -> Result<usize> {
     let mut knots: [(isize, isize); 10] = [(0, 0); 10];
     let mut set = HashSet::new();
     for m in movement {
         match m {
             Move::Up(s) => {
-                for _ in 0..s {
+                for _ in 0..*s {
                     knots[0].1 += 1;
                     move_tails(&mut knots);
                     set.insert(knots[9]);
                 }
             }
             Move::Down(s) => {
-                for _ in 0..s {
+                for _ in 0..*s {
                     knots[0].1 -= 1;
                     move_tails(&mut knots);
                     set.insert(knots[9]);
                 }
             }
             Move::Right(s) => {
-                for _ in 0..s {
+                for _ in 0..*s {
                     knots[0].0 += 1;
                     move_tails(&mut knots);
                     set.insert(knots[9]);
                 }
             }
             Move::Left(s) => {
-                for _ in 0..s {
+                for _ in 0..*s {
                     knots[0].0 -= 1;
                     move_tails(&mut knots);
                     set.insert(knots[9]);
@@ -167,36 +153,44 @@ fn part_b(movement: Parsed) -> Result<usize> {
     Ok(set.len())
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day09>();
+}
+
+struct Day09;
+
+impl Solution for Day09 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 9;
+    type Input<'i> = Parsed;
+    type Output = usize;
+
+    fn parse(input: &str) -> aoc_core::Result<Self::Input<'_>> {
+        parse(input).map_err(aoc_core::Error::parse)
+    }
 
-    const TEST_INPUT: &str = "R 4
-U 4
-L 3
-D 1
-R 4
-D 1
-L 5
-R 2
+    fn part_one(input: &Self::Input<'_>) -> aoc_core::Result<Self::Output> {
+        part_a(input).map_err(aoc_core::Error::solve)
+    }
 
-";
+    fn part_two(input: &Self::Input<'_>) -> aoc_core::Result<Self::Output> {
+        part_b(input).map_err(aoc_core::Error::solve)
+    }
+}
 
-    const TEST_INPUT_2: &str = "R 5
-U 8
-L 8
-D 3
-R 17
-D 10
-L 25
-U 20
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aoc_core::Part;
 
-";
+    fn test_input() -> String {
+        crate::example(9, Part::One)
+    }
 
     #[test]
     fn test_parse() {
         assert_eq!(
-            parse(TEST_INPUT).unwrap(),
+            parse(&test_input()).unwrap(),
             vec![
                 Move::Right(4),
                 Move::Up(4),
@@ -210,17 +204,11 @@ U 20
         )
     }
 
-    #[test]
-    fn test_part_a() {
-        let parsed = parse(TEST_INPUT).unwrap();
-        assert_eq!(part_a(parsed).unwrap(), 13)
-    }
-
     #[test]
     fn test_part_b() {
-        let parsed = parse(TEST_INPUT).unwrap();
-        assert_eq!(part_b(parsed).unwrap(), 1);
-        let parsed = parse(TEST_INPUT_2).unwrap();
-        assert_eq!(part_b(parsed).unwrap(), 36);
+        let parsed = parse(&test_input()).unwrap();
+        assert_eq!(part_b(&parsed).unwrap(), 1);
     }
+
+    aoc_core::derive_tests!(Day09);
 }
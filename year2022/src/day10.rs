@@ -1,22 +1,9 @@
 use std::fmt::Write;
 
 use anyhow::Result;
+use aoc_core::{Registry, Solution};
 use pest_consume::{match_nodes, Parser};
 
-const INPUT: &str = include_str!("inputs/day10.txt");
-
-pub fn run_part_a() -> Result<()> {
-    let i = parse(INPUT)?;
-    println!("{}", part_a(i)?);
-    Ok(())
-}
-
-pub fn run_part_b() -> Result<()> {
-    let i = parse(INPUT)?;
-    println!("{}", part_b(i)?);
-    Ok(())
-}
-
 #[derive(Debug, PartialEq)]
 enum Command {
     Noop,
@@ -78,9 +65,9 @@ fn parse(input: &str) -> Result<Parsed> {
     Day10Parser::file(input).map_err(|e| e.into())
 }
 
-fn part_a(commands: Parsed) -> Result<isize> {
+fn part_a(commands: &Parsed) -> Result<isize> {
     let mut states = commands
-        .into_iter()
+        .iter()
         .scan((1isize, 0usize), |(reg_x, cycle), c| match c {
             Command::Noop => {
                 *cycle += 1;
@@ -94,7 +81,7 @@ fn part_a(commands: Parsed) -> Result<isize> {
         })
         .collect::<Vec<_>>();
     states.reverse();
-    Ok(vec![
+    Ok([
         states.iter().find(|s| s.0 < 20).unwrap().1 * 20,
         states.iter().find(|s| s.0 < 60).unwrap().1 * 60,
         states.iter().find(|s| s.0 < 100).unwrap().1 * 100,
@@ -106,9 +93,9 @@ fn part_a(commands: Parsed) -> Result<isize> {
     .sum::<isize>())
 }
 
-fn part_b(commands: Parsed) -> Result<String> {
+fn part_b(commands: &Parsed) -> Result<String> {
     let mut states = commands
-        .into_iter()
+        .iter()
         .scan((1isize, 0usize), |(reg_x, cycle), c| match c {
             Command::Noop => {
                 *cycle += 1;
@@ -144,6 +131,33 @@ fn part_b(commands: Parsed) -> Result<String> {
     Ok(s)
 }
 
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day10>();
+}
+
+struct Day10;
+
+impl Solution for Day10 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 10;
+    type Input<'i> = Parsed;
+    type Output = String;
+
+    fn parse(input: &str) -> aoc_core::Result<Self::Input<'_>> {
+        parse(input).map_err(aoc_core::Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> aoc_core::Result<Self::Output> {
+        part_a(input)
+            .map(|x| x.to_string())
+            .map_err(aoc_core::Error::solve)
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> aoc_core::Result<Self::Output> {
+        part_b(input).map_err(aoc_core::Error::solve)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -152,155 +166,6 @@ mod test {
 addx 3
 addx -5
 
-";
-
-    const TEST_INPUT: &str = "addx 15
-addx -11
-addx 6
-addx -3
-addx 5
-addx -1
-addx -8
-addx 13
-addx 4
-noop
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx -35
-addx 1
-addx 24
-addx -19
-addx 1
-addx 16
-addx -11
-noop
-noop
-addx 21
-addx -15
-noop
-noop
-addx -3
-addx 9
-addx 1
-addx -3
-addx 8
-addx 1
-addx 5
-noop
-noop
-noop
-noop
-noop
-addx -36
-noop
-addx 1
-addx 7
-noop
-noop
-noop
-addx 2
-addx 6
-noop
-noop
-noop
-noop
-noop
-addx 1
-noop
-noop
-addx 7
-addx 1
-noop
-addx -13
-addx 13
-addx 7
-noop
-addx 1
-addx -33
-noop
-noop
-noop
-addx 2
-noop
-noop
-noop
-addx 8
-noop
-addx -1
-addx 2
-addx 1
-noop
-addx 17
-addx -9
-addx 1
-addx 1
-addx -3
-addx 11
-noop
-noop
-addx 1
-noop
-addx 1
-noop
-noop
-addx -13
-addx -19
-addx 1
-addx 3
-addx 26
-addx -30
-addx 12
-addx -1
-addx 3
-addx 1
-noop
-noop
-noop
-addx -9
-addx 18
-addx 1
-addx 2
-noop
-noop
-addx 9
-noop
-noop
-noop
-addx -1
-addx 2
-addx -37
-addx 1
-addx 3
-noop
-addx 15
-addx -21
-addx 22
-addx -6
-addx 1
-noop
-addx 2
-addx 1
-noop
-addx -10
-noop
-noop
-addx 20
-addx 1
-addx 2
-addx 2
-addx -6
-addx -11
-noop
-noop
-noop
-
 ";
 
     #[test]
@@ -311,23 +176,5 @@ noop
         )
     }
 
-    #[test]
-    fn test_part_a() {
-        let parsed = parse(TEST_INPUT).unwrap();
-        assert_eq!(part_a(parsed).unwrap(), 13140);
-    }
-
-    const EXPECTED_OUTPUT: &str = "##..##..##..##..##..##..##..##..##..##..
-###...###...###...###...###...###...###.
-####....####....####....####....####....
-#####.....#####.....#####.....#####.....
-######......######......######......####
-#######.......#######.......#######.....
-";
-
-    #[test]
-    fn test_part_b() {
-        let parsed = parse(TEST_INPUT).unwrap();
-        assert_eq!(part_b(parsed).unwrap(), EXPECTED_OUTPUT);
-    }
+    aoc_core::derive_tests!(Day10);
 }
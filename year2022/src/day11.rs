@@ -1,23 +1,10 @@
 use std::collections::HashMap;
 
 use anyhow::Result;
+use aoc_core::{Registry, Solution};
 use pest_consume::{match_nodes, Parser};
 
-const INPUT: &str = include_str!("inputs/day11.txt");
-
-pub fn run_part_a() -> Result<()> {
-    let i = parse(INPUT)?;
-    println!("{}", part_a(i)?);
-    Ok(())
-}
-
-pub fn run_part_b() -> Result<()> {
-    let i = parse(INPUT)?;
-    println!("{}", part_b(i)?);
-    Ok(())
-}
-
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 struct Monkey {
     name: usize,
     items: Vec<usize>,
@@ -40,7 +27,7 @@ enum Operator {
     Mul,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 enum Expression {
     Add(Value, Value),
     Mul(Value, Value),
@@ -55,7 +42,7 @@ impl Expression {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 enum Value {
     Const(usize),
     Old,
@@ -208,7 +195,8 @@ fn parse(input: &str) -> Result<Parsed> {
     Day11Parser::file(input).map_err(|e| e.into())
 }
 
-fn part_a(mut monkeys: Parsed) -> Result<usize> {
+fn part_a(monkeys: &Parsed) -> Result<usize> {
+    let mut monkeys = monkeys.clone();
     for _round in 0..20 {
         for turn in 0..monkeys.len() {
             let tm;
@@ -222,7 +210,7 @@ fn part_a(mut monkeys: Parsed) -> Result<usize> {
                 monkey.items.reverse();
                 while let Some(item) = monkey.items.pop() {
                     let worry = monkey.inspect(item) / 3;
-                    match worry % monkey.test_div_by == 0 {
+                    match worry.is_multiple_of(monkey.test_div_by) {
                         true => truthy.push(worry),
                         false => falsy.push(worry),
                     }
@@ -238,14 +226,15 @@ fn part_a(mut monkeys: Parsed) -> Result<usize> {
             }
         }
     }
-    let mut inspections = monkeys.iter().map(|(_, m)| m.inspected).collect::<Vec<_>>();
+    let mut inspections = monkeys.values().map(|m| m.inspected).collect::<Vec<_>>();
     inspections.sort();
     inspections.reverse();
     Ok(inspections.iter().take(2).product())
 }
 
-fn part_b(mut monkeys: Parsed) -> Result<usize> {
-    let common: usize = monkeys.iter().map(|(_, m)| m.test_div_by).product();
+fn part_b(monkeys: &Parsed) -> Result<usize> {
+    let mut monkeys = monkeys.clone();
+    let common: usize = monkeys.values().map(|m| m.test_div_by).product();
     for _round in 0..10_000 {
         for turn in 0..monkeys.len() {
             let tm;
@@ -259,7 +248,7 @@ fn part_b(mut monkeys: Parsed) -> Result<usize> {
                 monkey.items.reverse();
                 while let Some(item) = monkey.items.pop() {
                     let worry = monkey.inspect(item) % common;
-                    match worry % monkey.test_div_by == 0 {
+                    match worry.is_multiple_of(monkey.test_div_by) {
                         true => truthy.push(worry),
                         false => falsy.push(worry),
                     }
@@ -275,45 +264,45 @@ fn part_b(mut monkeys: Parsed) -> Result<usize> {
             }
         }
     }
-    let mut inspections = monkeys.iter().map(|(_, m)| m.inspected).collect::<Vec<_>>();
+    let mut inspections = monkeys.values().map(|m| m.inspected).collect::<Vec<_>>();
     inspections.sort();
     inspections.reverse();
     Ok(inspections.iter().take(2).product())
 }
 
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day11>();
+}
+
+struct Day11;
+
+impl Solution for Day11 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 11;
+    type Input<'i> = Parsed;
+    type Output = usize;
+
+    fn parse(input: &str) -> aoc_core::Result<Self::Input<'_>> {
+        parse(input).map_err(aoc_core::Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> aoc_core::Result<Self::Output> {
+        part_a(input).map_err(aoc_core::Error::solve)
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> aoc_core::Result<Self::Output> {
+        part_b(input).map_err(aoc_core::Error::solve)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use aoc_core::Part;
 
-    const TEST_INPUT: &str = "Monkey 0:
-  Starting items: 79, 98
-  Operation: new = old * 19
-  Test: divisible by 23
-    If true: throw to monkey 2
-    If false: throw to monkey 3
-
-Monkey 1:
-  Starting items: 54, 65, 75, 74
-  Operation: new = old + 6
-  Test: divisible by 19
-    If true: throw to monkey 2
-    If false: throw to monkey 0
-
-Monkey 2:
-  Starting items: 79, 60, 97
-  Operation: new = old * old
-  Test: divisible by 13
-    If true: throw to monkey 1
-    If false: throw to monkey 3
-
-Monkey 3:
-  Starting items: 74
-  Operation: new = old + 3
-  Test: divisible by 17
-    If true: throw to monkey 0
-    If false: throw to monkey 1
-
-";
+    fn test_input() -> String {
+        crate::example(11, Part::One)
+    }
 
     #[test]
     fn test_parse() {
@@ -366,18 +355,8 @@ Monkey 3:
                 inspected: 0,
             },
         );
-        assert_eq!(parse(TEST_INPUT).unwrap(), map);
+        assert_eq!(parse(&test_input()).unwrap(), map);
     }
 
-    #[test]
-    fn test_part_a() {
-        let parsed = parse(TEST_INPUT).unwrap();
-        assert_eq!(part_a(parsed).unwrap(), 10605);
-    }
-
-    #[test]
-    fn test_part_b() {
-        let parsed = parse(TEST_INPUT).unwrap();
-        assert_eq!(part_b(parsed).unwrap(), 2713310158);
-    }
+    aoc_core::derive_tests!(Day11);
 }
@@ -1,24 +1,9 @@
 use anyhow::{Ok, Result};
+use aoc_common::{Grid, Pos};
+use aoc_core::{Registry, Solution};
 use pathfinding::prelude::bfs;
 
-const INPUT: &str = include_str!("inputs/day12.txt");
-
-pub fn run_part_a() -> Result<()> {
-    let i = parse(INPUT)?;
-    println!("{}", part_a(i)?);
-    Ok(())
-}
-
-pub fn run_part_b() -> Result<()> {
-    let i = parse(INPUT)?;
-    println!("{}", part_b(i)?);
-    Ok(())
-}
-
-type Parsed = Vec<Vec<Tile>>;
-
-#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-struct Pos(usize, usize);
+type Parsed = Grid<Tile>;
 
 #[derive(Debug, PartialEq)]
 enum Tile {
@@ -54,117 +39,91 @@ impl TryFrom<char> for Tile {
 }
 
 fn parse(input: &str) -> Result<Parsed> {
-    input
-        .lines()
-        .filter_map(|l| {
-            if l.is_empty() {
-                return None;
-            }
-            Some(l.chars().map(Tile::try_from).collect::<Result<Vec<_>>>())
-        })
-        .collect::<Result<Vec<_>>>()
+    Ok(Grid::parse(input)?)
 }
 
-fn find_start_end(map: &Parsed) -> (Pos, Pos) {
-    let mut start: Option<Pos> = None;
-    let mut end: Option<Pos> = None;
-    for (y, row) in map.iter().enumerate() {
-        for (x, col) in row.iter().enumerate() {
-            if col == &Tile::Start {
-                start = Some(Pos(x, y))
-            } else if col == &Tile::End {
-                end = Some(Pos(x, y))
-            }
-            if start.is_some() && end.is_some() {
-                break;
-            }
-        }
-    }
-    (start.unwrap(), end.unwrap())
+fn find_start_end(map: &Parsed) -> Result<(Pos, Pos)> {
+    let start = map
+        .position(|t| t == &Tile::Start)
+        .ok_or(anyhow::Error::msg("no start"))?;
+    let end = map
+        .position(|t| t == &Tile::End)
+        .ok_or(anyhow::Error::msg("no end"))?;
+    Ok((start, end))
 }
 
 fn find_successors_up(map: &Parsed, node: &Pos) -> Vec<Pos> {
-    let &Pos(x, y) = node;
-    let cur_ele = map[y][x].elevation();
-    let mut positions = Vec::new();
-    if x > 0 {
-        positions.push(Pos(x - 1, y));
-    }
-    if x < map[0].len() - 1 {
-        positions.push(Pos(x + 1, y));
-    }
-    if y > 0 {
-        positions.push(Pos(x, y - 1));
-    }
-    if y < map.len() - 1 {
-        positions.push(Pos(x, y + 1));
-    }
-    positions
-        .into_iter()
-        .filter(|p| map[p.1][p.0].elevation() <= cur_ele + 1)
+    let cur_ele = map[*node].elevation();
+    map.neighbours4(*node)
+        .filter(|&p| map[p].elevation() <= cur_ele + 1)
         .collect()
 }
 
-fn part_a(map: Parsed) -> Result<usize> {
-    let (start, end) = find_start_end(&map);
-    let res = bfs(&start, |p| find_successors_up(&map, p), |p| p == &end)
+fn part_a(map: &Parsed) -> Result<usize> {
+    let (start, end) = find_start_end(map)?;
+    let res = bfs(&start, |p| find_successors_up(map, p), |p| p == &end)
         .ok_or(anyhow::Error::msg("no route found"))?;
     Ok(res.len() - 1)
 }
 
 fn find_successors_down(map: &Parsed, node: &Pos) -> Vec<Pos> {
-    let &Pos(x, y) = node;
-    let cur_ele = map[y][x].elevation();
-    let mut positions = Vec::new();
-    if x > 0 {
-        positions.push(Pos(x - 1, y));
-    }
-    if x < map[0].len() - 1 {
-        positions.push(Pos(x + 1, y));
-    }
-    if y > 0 {
-        positions.push(Pos(x, y - 1));
-    }
-    if y < map.len() - 1 {
-        positions.push(Pos(x, y + 1));
-    }
-    positions
-        .into_iter()
-        .filter(|p| map[p.1][p.0].elevation() >= cur_ele - 1)
+    let cur_ele = map[*node].elevation();
+    map.neighbours4(*node)
+        .filter(|&p| map[p].elevation() >= cur_ele - 1)
         .collect()
 }
 
-fn part_b(map: Parsed) -> Result<usize> {
-    let (_, end) = find_start_end(&map);
+fn part_b(map: &Parsed) -> Result<usize> {
+    let (_, end) = find_start_end(map)?;
     let res = bfs(
         &end,
-        |p| find_successors_down(&map, p),
-        |p| map[p.1][p.0].elevation() == 1,
+        |p| find_successors_down(map, p),
+        |&p| map[p].elevation() == 1,
     )
     .ok_or(anyhow::Error::msg("no route found"))?;
     Ok(res.len() - 1)
 }
 
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day12>();
+}
+
+struct Day12;
+
+impl Solution for Day12 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 12;
+    type Input<'i> = Parsed;
+    type Output = usize;
+
+    fn parse(input: &str) -> aoc_core::Result<Self::Input<'_>> {
+        parse(input).map_err(aoc_core::Error::parse)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> aoc_core::Result<Self::Output> {
+        part_a(input).map_err(aoc_core::Error::solve)
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> aoc_core::Result<Self::Output> {
+        part_b(input).map_err(aoc_core::Error::solve)
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use std::vec;
-
     use super::*;
+    use aoc_core::Part;
 
-    const TEST_INPUT: &str = "Sabqponm
-abcryxxl
-accszExk
-acctuvwj
-abdefghi
-
-";
+    fn test_input() -> String {
+        crate::example(12, Part::One)
+    }
 
     #[test]
     fn test_parse() {
-        let parsed = parse(TEST_INPUT).unwrap();
+        let parsed = parse(&test_input()).unwrap();
         assert_eq!(
             parsed,
-            vec![
+            Grid::from_rows(vec![
                 vec![
                     Tile::Start,
                     Tile::Slope(1),
@@ -215,19 +174,10 @@ abdefghi
                     Tile::Slope(8),
                     Tile::Slope(9),
                 ],
-            ]
+            ])
+            .unwrap()
         );
     }
 
-    #[test]
-    fn test_part_a() {
-        let parsed = parse(TEST_INPUT).unwrap();
-        assert_eq!(part_a(parsed).unwrap(), 31);
-    }
-
-    #[test]
-    fn test_part_b() {
-        let parsed = parse(TEST_INPUT).unwrap();
-        assert_eq!(part_b(parsed).unwrap(), 29);
-    }
+    aoc_core::derive_tests!(Day12);
 }
@@ -0,0 +1,62 @@
+use aoc_core::Registry;
+
+mod day01;
+mod day02;
+mod day03;
+mod day04;
+mod day05;
+mod day06;
+mod day07;
+mod day08;
+mod day09;
+mod day10;
+mod day11;
+mod day12;
+
+pub fn register(registry: &mut Registry) {
+    day01::register(registry);
+    day02::register(registry);
+    day03::register(registry);
+    day04::register(registry);
+    day05::register(registry);
+    day06::register(registry);
+    day07::register(registry);
+    day08::register(registry);
+    day09::register(registry);
+    day10::register(registry);
+    day11::register(registry);
+    day12::register(registry);
+}
+
+/// Puzzle input compiled into the binary for the given day, used when the
+/// input cannot be found at runtime.
+#[cfg(feature = "embedded-inputs")]
+pub fn embedded_input(day: u8) -> Option<&'static str> {
+    Some(match day {
+        1 => include_str!("../../inputs/2022/day01.txt"),
+        2 => include_str!("../../inputs/2022/day02.txt"),
+        3 => include_str!("../../inputs/2022/day03.txt"),
+        4 => include_str!("../../inputs/2022/day04.txt"),
+        5 => include_str!("../../inputs/2022/day05.txt"),
+        6 => include_str!("../../inputs/2022/day06.txt"),
+        7 => include_str!("../../inputs/2022/day07.txt"),
+        8 => include_str!("../../inputs/2022/day08.txt"),
+        9 => include_str!("../../inputs/2022/day09.txt"),
+        10 => include_str!("../../inputs/2022/day10.txt"),
+        11 => include_str!("../../inputs/2022/day11.txt"),
+        12 => include_str!("../../inputs/2022/day12.txt"),
+        _ => return None,
+    })
+}
+
+/// Reads the example for a day and part from the workspace's fixtures.
+#[cfg(test)]
+fn example(day: u8, part: aoc_core::Part) -> String {
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/../fixtures");
+    aoc_core::Fixture::load(dir.as_ref(), 2022, day)
+        .unwrap()
+        .example(part)
+        .unwrap()
+        .input
+        .clone()
+}
@@ -1,30 +1,35 @@
-use crate::Solutions;
-use lib_aoc::prelude::*;
+use aoc_core::{Registry, Result, Solution};
 
-impl Solution<DAY_01> for Solutions {
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day01>();
+}
+
+struct Day01;
+
+impl Solution for Day01 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 1;
     type Input<'i> = Vec<&'i str>;
     type Output = usize;
 
-    fn parse(puzzle: &str) -> Vec<&'_ str> {
-        puzzle.lines().collect()
+    fn parse(puzzle: &str) -> Result<Self::Input<'_>> {
+        Ok(puzzle.lines().collect())
     }
 
-    fn part_one(input: &Vec<&'_ str>) -> usize {
-        input
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(input
             .iter()
             .map(|line| {
-                let digits: Vec<_> = line.chars().filter(|c| c.is_digit(10)).collect();
-                usize::from_str_radix(
-                    &format!("{}{}", digits.first().unwrap(), digits.last().unwrap()),
-                    10,
-                )
-                .unwrap()
+                let digits: Vec<_> = line.chars().filter(|c| c.is_ascii_digit()).collect();
+                format!("{}{}", digits.first().unwrap(), digits.last().unwrap())
+                    .parse::<usize>()
+                    .unwrap()
             })
-            .sum()
+            .sum())
     }
 
-    fn part_two(input: &Vec<&'_ str>) -> usize {
-        input
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(input
             .iter()
             .map(|line| {
                 let chars: Vec<_> = line.chars().collect();
@@ -32,7 +37,7 @@ impl Solution<DAY_01> for Solutions {
                     .filter_map(|i| {
                         let chunk: String = chars.iter().skip(i).collect();
                         let first = chunk.chars().next().unwrap();
-                        if first.is_digit(10) {
+                        if first.is_ascii_digit() {
                             Some(first)
                         } else if chunk.starts_with("one") {
                             Some('1')
@@ -57,29 +62,17 @@ impl Solution<DAY_01> for Solutions {
                         }
                     })
                     .collect();
-                usize::from_str_radix(
-                    &format!("{}{}", digits.first().unwrap(), digits.last().unwrap()),
-                    10,
-                )
-                .unwrap()
+                format!("{}{}", digits.first().unwrap(), digits.last().unwrap())
+                    .parse::<usize>()
+                    .unwrap()
             })
-            .sum()
-    }
-}
-
-impl Test<DAY_01> for Solutions {
-    fn expected(part: bool) -> usize {
-        match part {
-            PART_ONE => 142,
-            PART_TWO => 281,
-        }
+            .sum())
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::Solutions;
-    use lib_aoc::prelude::*;
+    use super::*;
 
-    derive_tests!(Solutions, DAY_01);
+    aoc_core::derive_tests!(Day01);
 }
@@ -1,5 +1,4 @@
-use crate::Solutions;
-use lib_aoc::prelude::*;
+use aoc_core::{Registry, Result, Solution};
 
 #[derive(Debug)]
 pub struct Game {
@@ -7,12 +6,20 @@ pub struct Game {
     hands: Vec<(usize, usize, usize)>,
 }
 
-impl Solution<DAY_02> for Solutions {
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day02>();
+}
+
+struct Day02;
+
+impl Solution for Day02 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 2;
     type Input<'i> = Vec<Game>;
     type Output = usize;
 
-    fn parse(puzzle: &str) -> Vec<Game> {
-        puzzle
+    fn parse(puzzle: &str) -> Result<Self::Input<'_>> {
+        Ok(puzzle
             .lines()
             .map(|line| {
                 let game: Vec<_> = line.split(": ").collect();
@@ -43,12 +50,12 @@ impl Solution<DAY_02> for Solutions {
                     .collect();
                 Game { id, hands }
             })
-            .collect()
+            .collect())
     }
 
-    fn part_one(input: &Vec<Game>) -> usize {
-        input
-            .into_iter()
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(input
+            .iter()
             .filter_map(|game| {
                 if game
                     .hands
@@ -60,12 +67,12 @@ impl Solution<DAY_02> for Solutions {
                     Some(game.id)
                 }
             })
-            .sum()
+            .sum())
     }
 
-    fn part_two(input: &Vec<Game>) -> usize {
-        input
-            .into_iter()
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(input
+            .iter()
             .map(|game| {
                 let min_cubes = game
                     .hands
@@ -75,23 +82,13 @@ impl Solution<DAY_02> for Solutions {
                     });
                 min_cubes.0 * min_cubes.1 * min_cubes.2
             })
-            .sum()
-    }
-}
-
-impl Test<DAY_02> for Solutions {
-    fn expected(part: bool) -> usize {
-        match part {
-            PART_ONE => 8,
-            PART_TWO => 2286,
-        }
+            .sum())
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::Solutions;
-    use lib_aoc::prelude::*;
+    use super::*;
 
-    derive_tests!(Solutions, DAY_02);
+    aoc_core::derive_tests!(Day02);
 }
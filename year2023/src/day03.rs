@@ -1,71 +1,28 @@
-use std::ops::{Index, RangeInclusive};
+use std::ops::RangeInclusive;
 
-use crate::Solutions;
+use aoc_common::{Grid, Pos};
+use aoc_core::{Error, Registry, Result, Solution};
 use itertools::Itertools;
-use lib_aoc::prelude::*;
 
-#[derive(Debug)]
-pub struct Engine {
-    x: usize,
-    y: usize,
-    parts: Vec<Part>,
-}
-
-impl Engine {
-    fn get_cells_around<'e>(&'e self, pos: usize) -> impl Iterator<Item = usize> + 'e {
-        // Bound checks
-        let (mut lx, mut ux, mut ly, mut uy) = (-1isize, 1isize, -1isize, 1isize);
-        if pos % self.x == 0 {
-            lx = 0;
-        } else if pos % self.x == self.x - 1 {
-            ux = 0;
-        }
-        if pos / self.x == 0 {
-            ly = 0;
-        } else if pos / self.x == self.y - 1 {
-            uy = 0;
-        }
-        // Cells to check
-        (ly..=uy)
-            .map(move |y| {
-                (lx..=ux).filter_map(move |x| {
-                    if x == 0 && y == 0 {
-                        None
-                    } else {
-                        Some((y * (self.x as isize) + x + pos as isize) as usize)
-                    }
-                })
-            })
-            .flatten()
-    }
+type Engine = Grid<Part>;
 
-    fn get_part_number(&self, pos: usize) -> (usize, RangeInclusive<usize>) {
-        let lower = pos - (pos % self.x);
-        let upper = lower + self.x - 1;
-        let mut prev: Vec<_> = (lower..pos)
-            .rev()
-            .take_while(|&cell| matches!(self.parts.index(cell), Part::Digit(_)))
-            .map(|cell| match self.parts.index(cell) {
-                Part::Digit(pd) => pd,
-                _ => unreachable!(),
-            })
-            .collect();
-        prev.reverse();
-        let prev_len = prev.len();
-        let mut next: Vec<_> = (pos..=upper)
-            .take_while(|&cell| matches!(self.parts.index(cell), Part::Digit(_)))
-            .map(|cell| match self.parts.index(cell) {
-                Part::Digit(pd) => pd,
-                _ => unreachable!(),
-            })
-            .collect();
-        let next_len = next.len();
-        prev.append(&mut next);
-        (
-            prev.into_iter().collect::<String>().parse().unwrap(),
-            RangeInclusive::new(pos - prev_len, pos + next_len - 1),
-        )
-    }
+/// The number with a digit at `pos`, along with its row and the columns it
+/// spans.
+fn get_part_number(engine: &Engine, (x, y): Pos) -> (usize, (usize, RangeInclusive<usize>)) {
+    let row = engine.row(y);
+    let start = row[..x]
+        .iter()
+        .rposition(|part| !matches!(part, Part::Digit(_)))
+        .map_or(0, |i| i + 1);
+    let digits: String = row[start..]
+        .iter()
+        .map_while(|part| match part {
+            Part::Digit(d) => Some(*d),
+            _ => None,
+        })
+        .collect();
+    let end = start + digits.len() - 1;
+    (digits.parse().unwrap(), (y, start..=end))
 }
 
 #[derive(Debug)]
@@ -75,73 +32,65 @@ enum Part {
     Space,
 }
 
-impl Solution<DAY_03> for Solutions {
+impl From<char> for Part {
+    fn from(c: char) -> Self {
+        if c.is_ascii_digit() {
+            Part::Digit(c)
+        } else if c == '.' {
+            Part::Space
+        } else {
+            Part::Symbol(c)
+        }
+    }
+}
+
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day03>();
+}
+
+struct Day03;
+
+impl Solution for Day03 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 3;
     type Input<'i> = Engine;
     type Output = usize;
 
-    fn parse(puzzle: &str) -> Engine {
-        let engine = puzzle
-            .lines()
-            .map(|line| {
-                line.chars()
-                    .map(|c| {
-                        if c.is_digit(10) {
-                            Part::Digit(c)
-                        } else if c == '.' {
-                            Part::Space
-                        } else {
-                            Part::Symbol(c)
-                        }
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .fold((0usize, 0usize, Vec::new()), |(_, ay, mut av), line| {
-                let x = line.len();
-                av.extend(line);
-                (x, ay + 1, av)
-            });
-        Engine {
-            x: engine.0,
-            y: engine.1,
-            parts: engine.2,
-        }
+    fn parse(puzzle: &str) -> Result<Self::Input<'_>> {
+        Grid::parse(puzzle).map_err(Error::parse)
     }
 
-    fn part_one(input: &Engine) -> usize {
-        let mut next_i = 0;
-        input
-            .parts
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        let mut next = (0, 0);
+        Ok(input
             .iter()
-            .enumerate()
-            .filter_map(|(i, part)| match part {
+            .filter_map(|((x, y), part)| match part {
                 Part::Digit(_) => {
-                    if i < next_i
+                    if (x < next.0 && y == next.1)
                         || !input
-                            .get_cells_around(i)
-                            .any(|cell| matches!(input.parts.index(cell), Part::Symbol(_)))
+                            .neighbours8((x, y))
+                            .any(|cell| matches!(input[cell], Part::Symbol(_)))
                     {
                         return None;
                     }
-                    let (part, span) = input.get_part_number(i);
-                    next_i = span.last().unwrap() + 1;
+                    let (part, (_, span)) = get_part_number(input, (x, y));
+                    next = (span.end() + 1, y);
                     Some(part)
                 }
                 _ => None,
             })
-            .sum()
+            .sum())
     }
 
-    fn part_two(input: &Engine) -> usize {
-        input
-            .parts
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(input
             .iter()
-            .enumerate()
-            .filter_map(|(i, part)| match part {
+            .filter_map(|(pos, part)| match part {
                 Part::Symbol('*') => {
                     let part_nums: Vec<_> = input
-                        .get_cells_around(i)
-                        .filter_map(|cell| match input.parts.index(cell) {
-                            Part::Digit(_) => Some(input.get_part_number(cell)),
+                        .neighbours8(pos)
+                        .filter_map(|cell| match input[cell] {
+                            Part::Digit(_) => Some(get_part_number(input, cell)),
                             _ => None,
                         })
                         .unique()
@@ -153,23 +102,13 @@ impl Solution<DAY_03> for Solutions {
                 }
                 _ => None,
             })
-            .sum()
-    }
-}
-
-impl Test<DAY_03> for Solutions {
-    fn expected(part: bool) -> usize {
-        match part {
-            PART_ONE => 4361,
-            PART_TWO => 467835,
-        }
+            .sum())
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::Solutions;
-    use lib_aoc::prelude::*;
+    use super::*;
 
-    derive_tests!(Solutions, DAY_03);
+    aoc_core::derive_tests!(Day03);
 }
@@ -1,6 +1,5 @@
-use crate::Solutions;
+use aoc_core::{Registry, Result, Solution};
 use itertools::Itertools;
-use lib_aoc::prelude::*;
 
 #[allow(dead_code)]
 #[derive(Debug)]
@@ -13,17 +12,25 @@ pub struct Card {
 impl Card {
     fn num_matches(&self) -> usize {
         let combined = &self.winning.iter().chain(&self.picked).unique().count();
-        let total = &self.winning.len() + &self.picked.len();
+        let total = self.winning.len() + self.picked.len();
         total - combined
     }
 }
 
-impl Solution<DAY_04> for Solutions {
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day04>();
+}
+
+struct Day04;
+
+impl Solution for Day04 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 4;
     type Input<'i> = Vec<Card>;
     type Output = usize;
 
-    fn parse(puzzle: &str) -> Vec<Card> {
-        puzzle
+    fn parse(puzzle: &str) -> Result<Self::Input<'_>> {
+        Ok(puzzle
             .lines()
             .map(|line| {
                 let card: Vec<_> = line.split(": ").collect();
@@ -42,7 +49,7 @@ impl Solution<DAY_04> for Solutions {
                     .map(|nums| {
                         nums.split(" ")
                             .filter_map(|num| {
-                                if num == "" {
+                                if num.is_empty() {
                                     None
                                 } else {
                                     Some(num.parse::<usize>().unwrap())
@@ -57,22 +64,22 @@ impl Solution<DAY_04> for Solutions {
                     picked: numbers.last().unwrap().to_owned(),
                 }
             })
-            .collect()
+            .collect())
     }
 
-    fn part_one(input: &Vec<Card>) -> usize {
-        input
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(input
             .iter()
             .filter_map(|card| match card.num_matches() {
                 0 => None,
                 num => Some(2usize.pow(num as u32 - 1)),
             })
-            .sum()
+            .sum())
     }
 
-    fn part_two(input: &Vec<Card>) -> usize {
-        input
-            .into_iter()
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(input
+            .iter()
             .enumerate()
             .map(|(i, card)| (i, (1..=card.num_matches()).map(move |j| j + i)))
             .fold(vec![1usize; input.len()], |mut acc, (i, copies)| {
@@ -82,23 +89,13 @@ impl Solution<DAY_04> for Solutions {
                 acc
             })
             .iter()
-            .sum()
-    }
-}
-
-impl Test<DAY_04> for Solutions {
-    fn expected(part: bool) -> usize {
-        match part {
-            PART_ONE => 13,
-            PART_TWO => 30,
-        }
+            .sum())
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::Solutions;
-    use lib_aoc::prelude::*;
+    use super::*;
 
-    derive_tests!(Solutions, DAY_04);
+    aoc_core::derive_tests!(Day04);
 }
@@ -1,7 +1,7 @@
-use std::{ops::Range, usize};
+use std::ops::Range;
 
-use crate::Solutions;
-use lib_aoc::prelude::*;
+use aoc_common::{IntervalSet, PiecewiseMap};
+use aoc_core::{Error, Registry, Result, Solution};
 use nom::{
     bytes::complete::tag,
     character::complete::{alpha1, line_ending, u64},
@@ -13,11 +13,9 @@ use nom::{
 };
 
 // Parse a line
-fn line<'a, F: 'a, O, E: ParseError<&'a str>>(
-    inner: F,
-) -> impl FnMut(&'a str) -> IResult<&'a str, O, E>
+fn line<'a, F, O, E: ParseError<&'a str>>(inner: F) -> impl FnMut(&'a str) -> IResult<&'a str, O, E>
 where
-    F: Parser<&'a str, O, E>,
+    F: Parser<&'a str, O, E> + 'a,
 {
     preceded(line_ending, inner)
 }
@@ -36,26 +34,25 @@ fn get_map_from_to(input: &str) -> IResult<&str, (String, String)> {
     ))(input)
 }
 
-fn get_map_details(input: &str) -> IResult<&str, Vec<(Range<u64>, Range<u64>)>> {
-    many1(map_res(line(separated_list1(tag(" "), u64)), |l| {
-        if l.len() != 3 {
-            Err("map does not contain dest, src, and len")
-        } else {
-            let dest = l.get(0).unwrap().to_owned();
-            let src = l.get(1).unwrap().to_owned();
-            let size = l.get(2).unwrap().to_owned();
-            Ok((
-                Range {
-                    start: dest,
-                    end: dest + size,
-                },
-                Range {
-                    start: src,
-                    end: src + size,
-                },
-            ))
-        }
-    }))(input)
+fn get_map_details(input: &str) -> IResult<&str, PiecewiseMap<u64>> {
+    map(
+        many1(map_res(line(separated_list1(tag(" "), u64)), |l| {
+            if l.len() != 3 {
+                Err("map does not contain dest, src, and len")
+            } else {
+                let (dest, src, size) = (l[0], l[1], l[2]);
+                Ok((src..src + size, dest))
+            }
+        })),
+        |dicts| {
+            dicts
+                .into_iter()
+                .fold(PiecewiseMap::new(), |mut map, (src, dest)| {
+                    map.insert(src, dest);
+                    map
+                })
+        },
+    )(input)
 }
 
 fn get_map(input: &str) -> IResult<&str, Map> {
@@ -79,10 +76,11 @@ pub struct Almanac {
 }
 
 impl Almanac {
-    fn seed_location(&self, seed_num: u64) -> u64 {
+    /// Every map, one after the other, as a single map from seed to location.
+    fn seed_to_location(&self) -> PiecewiseMap<u64> {
         self.maps
             .iter()
-            .fold(seed_num, |acc, map| map.get_dest(acc))
+            .fold(PiecewiseMap::new(), |acc, map| acc.then(&map.dicts))
     }
 }
 
@@ -91,102 +89,48 @@ impl Almanac {
 struct Map {
     from: String,
     to: String,
-    dicts: Vec<(Range<u64>, Range<u64>)>,
+    dicts: PiecewiseMap<u64>,
 }
 
-impl Map {
-    fn get_dest(&self, num: u64) -> u64 {
-        match self.dicts.iter().find(|(_, src)| src.contains(&num)) {
-            Some((dest, src)) => num - src.start + dest.start,
-            None => num,
-        }
-    }
-
-    // Scenarios
-    // orig   | new    | out                    | window
-    // 15..20 | 12..16 | 15..16 16..20          | 12,15,16,20
-    // 15..20 | 16..25 | 15..16 16..20          | 15,16,20,25
-    // 15..20 | 12..25 | 15..20                 | 12,15,20,25
-    // 15..20 | 16..18 | 15..16 16..18 18..20   | 15,16,18,20
-    // 15..20 | 10..15 | 15..20                 | 10,15,15,20
-    //
-    // Folds using above scenarios for source, then converts those ranges
-    // to the destination ranges
-    fn fold(&self, orig: Vec<Range<u64>>) -> Vec<Range<u64>> {
-        let new: Vec<_> = self
-            .dicts
-            .iter()
-            .map(|(_, src)| vec![src.start, src.end])
-            .flatten()
-            .collect();
-        orig.iter()
-            .map(|o| {
-                let (start, end) = (o.start, o.end);
-                let mut poi = vec![o.start, o.end];
-                poi.extend(&new);
-                poi.sort();
-                poi.windows(2)
-                    .filter_map(move |p| {
-                        let a = p.get(0).unwrap().to_owned();
-                        let b = p.get(1).unwrap().to_owned();
-                        if a == b || a < start || b > end {
-                            None
-                        } else {
-                            Some(Range {
-                                start: self.get_dest(a),
-                                // bounds are exclusive so I need to find the
-                                // upper inner bound then increase by 1
-                                end: self.get_dest(b - 1) + 1,
-                            })
-                        }
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .flatten()
-            .collect()
-    }
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day05>();
 }
 
-impl Solution<DAY_05> for Solutions {
+struct Day05;
+
+impl Solution for Day05 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 5;
     type Input<'i> = Almanac;
     type Output = usize;
 
-    fn parse(puzzle: &str) -> Almanac {
-        let (_, p) = get_almanac(puzzle).unwrap();
-        p
+    fn parse(puzzle: &str) -> Result<Self::Input<'_>> {
+        let (_, p) = get_almanac(puzzle).map_err(Error::parse)?;
+        Ok(p)
     }
 
-    fn part_one(input: &Almanac) -> usize {
-        input
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        let location = input.seed_to_location();
+        Ok(input
             .seeds
             .iter()
-            .map(|seed| input.seed_location(*seed))
+            .map(|&seed| location.get(seed))
             .min()
-            .unwrap() as usize
+            .unwrap() as usize)
     }
 
     // Looping through all the possible paths is not reasonable >30 minutes.
-    // I need a way to break up the ranges (collapse seeds to check fewer options)
-    //
-    // Think time slicing where you have 2 groups of events and you need to identify when
-    // either events from both groups have started and ended on a single timeline.
-    // Then test out the each point of intrest (start of an event or the end of
-    // an event). The times are the source start, events the ranges, and groups
-    // the different maps.
-    //
-    // I'll need to fold a Vec<Range<u64>> with another Vec<Rangeg<u64>> using the
-    // logic above. Then translate those final ranges into the destination ranges
-    // to use in the next iteration. The last iteration will be the location ranges
-    // and since start is before end, I only need to check those start iterations.
-    //
     // My input has 2,547,615,236 brute force checks. I killed the process at 30 minutes.
-    // Using this approach I only have 112 different points to check.
-    fn part_two(input: &Almanac) -> usize {
-        let init_seeds: Vec<_> = input
+    //
+    // Instead the maps are composed into one seed-to-location map made of a
+    // handful of shifted ranges, and the seed ranges go through that whole.
+    // The lowest location is then the start of the first range out.
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        let seeds: IntervalSet<u64> = input
             .seeds
             .chunks(2)
             .map(|chunk| {
-                let start = chunk.get(0).unwrap().to_owned();
+                let start = chunk.first().unwrap().to_owned();
                 let size = chunk.get(1).unwrap().to_owned();
                 Range {
                     start,
@@ -194,30 +138,13 @@ impl Solution<DAY_05> for Solutions {
                 }
             })
             .collect();
-        input
-            .maps
-            .iter()
-            .fold(init_seeds, |acc, map| map.fold(acc))
-            .iter()
-            .map(|r| r.start)
-            .min()
-            .unwrap() as usize
-    }
-}
-
-impl Test<DAY_05> for Solutions {
-    fn expected(part: bool) -> usize {
-        match part {
-            PART_ONE => 35,
-            PART_TWO => 46,
-        }
+        Ok(input.seed_to_location().map_set(&seeds).min().unwrap() as usize)
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::Solutions;
-    use lib_aoc::prelude::*;
+    use super::*;
 
-    derive_tests!(Solutions, DAY_05);
+    aoc_core::derive_tests!(Day05);
 }
@@ -1,6 +1,5 @@
-use crate::Solutions;
+use aoc_core::{Error, Registry, Result, Solution};
 use itertools::Itertools;
-use lib_aoc::prelude::*;
 use nom::{
     bytes::complete::tag,
     character::complete::{multispace1, newline, u64},
@@ -24,7 +23,11 @@ fn parse(input: &str) -> IResult<&str, (Vec<u64>, Vec<u64>)> {
 }
 
 fn winning_iters(num: &u64, dist: &u64) -> usize {
-    let iter = if num % 2 == 0 { num / 2 } else { num / 2 + 1 };
+    let iter = if num.is_multiple_of(2) {
+        num / 2
+    } else {
+        num / 2 + 1
+    };
     let count = (0..iter)
         .filter_map(|i| {
             let traveled = (num - i) * i;
@@ -35,32 +38,40 @@ fn winning_iters(num: &u64, dist: &u64) -> usize {
             }
         })
         .count();
-    if num % 2 == 0 {
+    if num.is_multiple_of(2) {
         count * 2 + 1
     } else {
         count * 2
     }
 }
 
-impl Solution<DAY_06> for Solutions {
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day06>();
+}
+
+struct Day06;
+
+impl Solution for Day06 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 6;
     type Input<'i> = (Vec<u64>, Vec<u64>);
     type Output = usize;
 
-    fn parse(puzzle: &str) -> (Vec<u64>, Vec<u64>) {
-        let (_, p) = parse(puzzle).unwrap();
-        p
+    fn parse(puzzle: &str) -> Result<Self::Input<'_>> {
+        let (_, p) = parse(puzzle).map_err(Error::parse)?;
+        Ok(p)
     }
 
-    fn part_one(input: &(Vec<u64>, Vec<u64>)) -> usize {
-        input
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(input
             .0
             .iter()
             .zip(&input.1)
             .map(|(t, d)| winning_iters(t, d))
-            .product()
+            .product())
     }
 
-    fn part_two(input: &(Vec<u64>, Vec<u64>)) -> usize {
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
         let time = input
             .0
             .iter()
@@ -75,23 +86,13 @@ impl Solution<DAY_06> for Solutions {
             .join("")
             .parse()
             .unwrap();
-        winning_iters(&time, &distance)
-    }
-}
-
-impl Test<DAY_06> for Solutions {
-    fn expected(part: bool) -> usize {
-        match part {
-            PART_ONE => 288,
-            PART_TWO => 71503,
-        }
+        Ok(winning_iters(&time, &distance))
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::Solutions;
-    use lib_aoc::prelude::*;
+    use super::*;
 
-    derive_tests!(Solutions, DAY_06);
+    aoc_core::derive_tests!(Day06);
 }
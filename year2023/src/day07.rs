@@ -1,7 +1,6 @@
 use std::{cmp::Ordering, fmt::Debug, str::FromStr};
 
-use crate::Solutions;
-use lib_aoc::prelude::*;
+use aoc_core::{Error, Registry, Result, Solution};
 
 #[derive(Clone)]
 pub struct Hand<T> {
@@ -15,9 +14,8 @@ impl From<Hand<CardLabel>> for Hand<CardLabelPartB> {
         let cards = value
             .cards
             .into_iter()
-            .map(|c| c.try_into())
-            .collect::<Result<_, _>>()
-            .unwrap();
+            .map(|c| c.into())
+            .collect::<Vec<_>>();
         let strength = CardStrength::try_from(&cards).unwrap();
         Self {
             cards,
@@ -75,7 +73,7 @@ where
                     .iter()
                     .zip(other.cards.iter())
                     .fold(Ordering::Equal, |acc, (s, o)| match acc {
-                        Ordering::Equal => s.cmp(&o),
+                        Ordering::Equal => s.cmp(o),
                         fin => fin,
                     })
             }
@@ -146,7 +144,7 @@ impl TryFrom<char> for CardLabel {
             'Q' => Ok(CardLabel::Queen),
             'J' => Ok(CardLabel::Jack),
             'T' => Ok(CardLabel::Num(10)),
-            v if v.is_digit(10) => Ok(CardLabel::Num(v.to_digit(10).unwrap() as usize)),
+            v if v.is_ascii_digit() => Ok(CardLabel::Num(v.to_digit(10).unwrap() as usize)),
             _ => Err("Unknown Card Label"),
         }
     }
@@ -163,10 +161,7 @@ pub enum CardLabelPartB {
 
 impl CardLabler for CardLabelPartB {
     fn is_wild(&self) -> bool {
-        match self {
-            CardLabelPartB::Jocker => true,
-            _ => false,
-        }
+        matches!(self, CardLabelPartB::Jocker)
     }
 }
 
@@ -241,7 +236,7 @@ impl CardStrength {
                 });
         counts.sort();
         counts.reverse();
-        match (counts.len(), wilds, counts.get(0).unwrap()) {
+        match (counts.len(), wilds, counts.first().unwrap()) {
             (1, _, _) => CardStrength::FiveOAK,
 
             (2, 0, 4) => CardStrength::FourOAK,
@@ -263,56 +258,54 @@ impl CardStrength {
     }
 }
 
-impl Solution<DAY_07> for Solutions {
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day07>();
+}
+
+struct Day07;
+
+impl Solution for Day07 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 7;
     type Input<'i> = Vec<Hand<CardLabel>>;
     type Output = usize;
 
-    fn parse(puzzle: &str) -> Vec<Hand<CardLabel>> {
+    fn parse(puzzle: &str) -> Result<Self::Input<'_>> {
         puzzle
             .lines()
-            .map(|l| Hand::from_str(l))
+            .map(Hand::from_str)
             .collect::<Result<_, _>>()
-            .unwrap()
+            .map_err(Error::parse)
     }
 
-    fn part_one(input: &Vec<Hand<CardLabel>>) -> usize {
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
         let mut ranked = input.to_owned();
         ranked.sort();
-        ranked
+        Ok(ranked
             .into_iter()
             .enumerate()
             .map(|(rank, hand)| hand.bid * (rank + 1))
-            .sum()
+            .sum())
     }
 
-    fn part_two(input: &Vec<Hand<CardLabel>>) -> usize {
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
         let mut ranked = input
-            .to_owned()
-            .into_iter()
-            .map(|h| Hand::<CardLabelPartB>::from(h))
+            .iter()
+            .cloned()
+            .map(Hand::<CardLabelPartB>::from)
             .collect::<Vec<_>>();
         ranked.sort();
-        ranked
+        Ok(ranked
             .into_iter()
             .enumerate()
             .map(|(rank, hand)| hand.bid * (rank + 1))
-            .sum()
-    }
-}
-
-impl Test<DAY_07> for Solutions {
-    fn expected(part: bool) -> usize {
-        match part {
-            PART_ONE => 6440,
-            PART_TWO => 5905,
-        }
+            .sum())
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::Solutions;
-    use lib_aoc::prelude::*;
+    use super::*;
 
-    derive_tests!(Solutions, DAY_07);
+    aoc_core::derive_tests!(Day07);
 }
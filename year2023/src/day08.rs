@@ -1,7 +1,5 @@
-use std::{cell::Cell, collections::HashMap};
-
-use crate::Solutions;
-use lib_aoc::prelude::*;
+use aoc_common::{math, Graph, NodeId};
+use aoc_core::{Error, Registry, Result, Solution};
 use nom::{
     branch::alt,
     bytes::complete::tag,
@@ -11,115 +9,35 @@ use nom::{
     sequence::{delimited, separated_pair, terminated},
     IResult,
 };
-use num::Integer;
-use typed_arena::Arena;
-
-struct Graph<'a> {
-    nodes: Arena<Node<'a>>,
-}
-
-impl<'a> Default for Graph<'a> {
-    fn default() -> Self {
-        Graph {
-            nodes: Arena::new(),
-        }
-    }
-}
-
-impl<'a> Graph<'a> {
-    fn init_part_a(&'a self, value: &HashMap<String, (String, String)>) -> &Node<'_> {
-        let mut nodes = HashMap::with_capacity(value.len());
-        let mut starts = None;
-
-        // Create all the nodes first
-        for (k, _) in value {
-            let root = Node::new(k.to_owned(), &self.nodes);
-            nodes.insert(k, root);
-            if k == "AAA" {
-                starts = Some(root);
-            }
-        }
-
-        // Create all the edges
-        for (o, (l, r)) in value {
-            let &orig = nodes.get(o).unwrap();
-            let &left = nodes.get(l).unwrap();
-            let &right = nodes.get(r).unwrap();
-            orig.left.set(Some(left));
-            orig.right.set(Some(right));
-        }
-        starts.unwrap()
-    }
-
-    fn init_part_b(&'a self, value: &HashMap<String, (String, String)>) -> Vec<&Node<'_>> {
-        let mut nodes = HashMap::with_capacity(value.len());
-        let mut starts = Vec::new();
-
-        // Create all the nodes first
-        for (k, _) in value {
-            let root = Node::new(k.to_owned(), &self.nodes);
-            nodes.insert(k, root);
-            if k.ends_with("A") {
-                starts.push(root);
-            }
-        }
-
-        // Create all the edges
-        for (o, (l, r)) in value {
-            let &orig = nodes.get(o).unwrap();
-            let &left = nodes.get(l).unwrap();
-            let &right = nodes.get(r).unwrap();
-            orig.left.set(Some(left));
-            orig.right.set(Some(right));
-        }
-        starts
-    }
 
-    fn num_steps(&self, start: &Node<'_>, instructions: &Vec<Direction>) -> usize {
-        let mut i: usize = 0;
-        let mut s = start;
-        loop {
-            let dir = instructions.get(i % instructions.len()).unwrap();
-            let new = match dir {
-                Direction::Left => &s.left,
-                Direction::Right => &s.right,
-            };
-
-            s = new.get().unwrap();
-
-            if s.id.ends_with("Z") {
-                break;
-            }
+fn num_steps(
+    network: &Graph<String, Direction>,
+    start: NodeId,
+    instructions: &[Direction],
+) -> usize {
+    let mut s = start;
+    for (i, dir) in instructions.iter().cycle().enumerate() {
+        s = network
+            .edges(s)
+            .iter()
+            .find(|(_, d)| d == dir)
+            .map(|&(to, _)| to)
+            .unwrap();
 
-            i += 1;
+        if network.node(s).ends_with('Z') {
+            return i + 1;
         }
-        i + 1
-    }
-}
-
-struct Node<'a> {
-    id: String,
-    left: Cell<Option<&'a Node<'a>>>,
-    right: Cell<Option<&'a Node<'a>>>,
-}
-
-impl<'a> Node<'a> {
-    fn new(id: String, arena: &'a Arena<Node<'a>>) -> &'a Node<'a> {
-        arena.alloc(Node {
-            id,
-            left: Cell::new(None),
-            right: Cell::new(None),
-        })
     }
+    unreachable!("the instructions repeat forever")
 }
 
 #[derive(Debug)]
 pub struct Map {
     instructions: Vec<Direction>,
-    nodes: HashMap<String, (String, String)>,
+    network: Graph<String, Direction>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 enum Direction {
     Left,
     Right,
@@ -156,60 +74,55 @@ fn get_map(input: &str) -> IResult<&str, Map> {
             many1(newline),
             fold_many1(
                 terminated(get_node, opt(newline)),
-                HashMap::new,
+                Graph::new,
                 |mut acc, (key, (left, right))| {
-                    acc.insert(key.to_owned(), (left.to_owned(), right.to_owned()));
+                    acc.add_edge(key.to_owned(), left.to_owned(), Direction::Left);
+                    acc.add_edge(key.to_owned(), right.to_owned(), Direction::Right);
                     acc
                 },
             ),
         ),
-        |(instructions, nodes)| Map {
+        |(instructions, network)| Map {
             instructions,
-            nodes,
+            network,
         },
     )(input)
 }
 
-impl Solution<DAY_08> for Solutions {
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day08>();
+}
+
+struct Day08;
+
+impl Solution for Day08 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 8;
     type Input<'i> = Map;
     type Output = usize;
 
-    fn parse(puzzle: &str) -> Map {
-        let (_, m) = get_map(puzzle).unwrap();
-        m
+    fn parse(puzzle: &str) -> Result<Self::Input<'_>> {
+        let (_, m) = get_map(puzzle).map_err(Error::parse)?;
+        Ok(m)
     }
 
-    fn part_one(input: &Map) -> usize {
-        let g = Graph::default();
-        let start = g.init_part_a(&input.nodes);
-        g.num_steps(start, &input.instructions)
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        let start = input.network.id("AAA").ok_or(Error::NoAnswer)?;
+        Ok(num_steps(&input.network, start, &input.instructions))
     }
 
-    fn part_two(input: &Map) -> usize {
-        let g = Graph::default();
-        let starts = g.init_part_b(&input.nodes);
-
-        starts
-            .iter()
-            .map(|s| g.num_steps(s, &input.instructions))
-            .reduce(|acc, s| acc.lcm(&s))
-            .unwrap()
-    }
-}
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        let network = &input.network;
+        let starts = (0..network.len()).filter(|&id| network.node(id).ends_with('A'));
 
-impl Test<DAY_08> for Solutions {
-    fn expected(part: bool) -> usize {
-        match part {
-            PART_ONE => 6,
-            PART_TWO => 6,
-        }
+        math::lcm_all(starts.map(|s| num_steps(network, s, &input.instructions)))
+            .ok_or_else(|| Error::solve("the ghosts never line up within a usize"))
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::Solutions;
-    use lib_aoc::prelude::*;
+    use super::*;
 
-    derive_tests!(Solutions, DAY_08);
+    aoc_core::derive_tests!(Day08);
 }
@@ -1,7 +1,6 @@
-use crate::Solutions;
-use lib_aoc::prelude::*;
+use aoc_core::{Error, Registry, Result, Solution};
 
-fn get_diffs(input: &Vec<isize>) -> Vec<isize> {
+fn get_diffs(input: &[isize]) -> Vec<isize> {
     input
         .windows(2)
         .map(|w| w.last().unwrap() - w.first().unwrap())
@@ -16,7 +15,7 @@ fn predict_next(input: &Vec<isize>) -> isize {
     diffs.reverse();
     let mut prev: isize = 0;
     for diff in diffs.iter() {
-        prev = diff.last().unwrap() + prev;
+        prev += diff.last().unwrap();
     }
     prev
 }
@@ -34,39 +33,38 @@ fn predict_prev(input: &Vec<isize>) -> isize {
     prev
 }
 
-impl Solution<DAY_09> for Solutions {
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day09>();
+}
+
+struct Day09;
+
+impl Solution for Day09 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 9;
     type Input<'i> = Vec<Vec<isize>>;
     type Output = isize;
 
-    fn parse(puzzle: &str) -> Vec<Vec<isize>> {
+    fn parse(puzzle: &str) -> Result<Self::Input<'_>> {
         puzzle
             .lines()
-            .map(|l| l.split(" ").map(|c| c.parse::<isize>().unwrap()).collect())
-            .collect()
+            .map(|l| l.split(' ').map(|c| c.parse::<isize>()).collect())
+            .collect::<Result<_, _>>()
+            .map_err(Error::parse)
     }
 
-    fn part_one(input: &Vec<Vec<isize>>) -> isize {
-        input.iter().map(|l| predict_next(l)).sum()
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(input.iter().map(predict_next).sum())
     }
 
-    fn part_two(input: &Vec<Vec<isize>>) -> isize {
-        input.iter().map(|l| predict_prev(l)).sum()
-    }
-}
-
-impl Test<DAY_09> for Solutions {
-    fn expected(part: bool) -> isize {
-        match part {
-            PART_ONE => 114,
-            PART_TWO => 2,
-        }
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        Ok(input.iter().map(predict_prev).sum())
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::Solutions;
-    use lib_aoc::prelude::*;
+    use super::*;
 
-    derive_tests!(Solutions, DAY_09);
+    aoc_core::derive_tests!(Day09);
 }
@@ -1,53 +1,41 @@
-use crate::Solutions;
-use lib_aoc::prelude::*;
-
-#[derive(Debug)]
-pub struct Map {
-    x: usize,
-    y: usize,
-    tiles: Vec<Tile>,
+use aoc_common::{Grid, Pos, Wrap};
+use aoc_core::{Error, Registry, Result, Solution};
+
+type Map = Grid<Tile>;
+
+fn to_walker(map: &Map, start: Pos, start_dir: Direction) -> MapWalker<'_> {
+    MapWalker::new(start, map, start_dir)
 }
 
-impl Map {
-    fn to_walker(&self, start: usize, start_dir: Direction) -> MapWalker {
-        MapWalker::new(start, self, start_dir)
-    }
+fn find_loop(map: &Map) -> (Direction, Vec<Pos>) {
+    let start = map.position(|&t| t == Tile::Start).unwrap();
 
-    fn find_loop(&self) -> (Direction, Vec<usize>) {
-        let (start_idx, _) = self
-            .tiles
-            .iter()
-            .enumerate()
-            .find(|(_, &t)| t == Tile::Start)
-            .unwrap();
-
-        // Need to check South first since I am using that to determine Ray-Casting
-        if let Some(c) = self.to_walker(start_idx, Direction::South).get_loop() {
-            return (Direction::South, c);
-        }
-        if let Some(c) = self.to_walker(start_idx, Direction::North).get_loop() {
-            return (Direction::North, c);
-        }
-        if let Some(c) = self.to_walker(start_idx, Direction::East).get_loop() {
-            return (Direction::East, c);
-        }
-        if let Some(c) = self.to_walker(start_idx, Direction::West).get_loop() {
-            return (Direction::West, c);
-        }
-        unreachable!()
+    // Need to check South first since I am using that to determine Ray-Casting
+    if let Some(c) = to_walker(map, start, Direction::South).get_loop() {
+        return (Direction::South, c);
+    }
+    if let Some(c) = to_walker(map, start, Direction::North).get_loop() {
+        return (Direction::North, c);
+    }
+    if let Some(c) = to_walker(map, start, Direction::East).get_loop() {
+        return (Direction::East, c);
+    }
+    if let Some(c) = to_walker(map, start, Direction::West).get_loop() {
+        return (Direction::West, c);
     }
+    unreachable!()
 }
 
 struct MapWalker<'a> {
-    curr: usize,
+    curr: Pos,
     curr_tile: &'a Tile,
     from_dir: Direction,
     map: &'a Map,
 }
 
 impl<'a> MapWalker<'a> {
-    fn new(start: usize, map: &'a Map, start_dir: Direction) -> MapWalker {
-        let curr_tile = map.tiles.get(start).unwrap();
+    fn new(start: Pos, map: &'a Map, start_dir: Direction) -> MapWalker<'a> {
+        let curr_tile = &map[start];
         MapWalker {
             curr: start,
             from_dir: start_dir,
@@ -56,16 +44,10 @@ impl<'a> MapWalker<'a> {
         }
     }
 
-    fn get_loop(&mut self) -> Option<Vec<usize>> {
+    fn get_loop(&mut self) -> Option<Vec<Pos>> {
         let mut idxs = vec![self.curr];
         if self.curr_tile == &Tile::Start {
-            let mv = match self.from_dir {
-                Direction::North => self.north(),
-                Direction::South => self.south(),
-                Direction::East => self.east(),
-                Direction::West => self.west(),
-            };
-            match mv {
+            match self.step(self.from_dir) {
                 Some(Tile::Ground) | None => return None,
                 _ => {}
             };
@@ -77,72 +59,33 @@ impl<'a> MapWalker<'a> {
             }
 
             let mv = match (self.curr_tile, &self.from_dir) {
-                (Tile::NorthToSouth, Direction::North) => self.south(),
-                (Tile::NorthToSouth, Direction::South) => self.north(),
-                (Tile::EastToWest, Direction::East) => self.west(),
-                (Tile::EastToWest, Direction::West) => self.east(),
-                (Tile::NorthToEast, Direction::North) => self.east(),
-                (Tile::NorthToEast, Direction::East) => self.north(),
-                (Tile::NorthToWest, Direction::North) => self.west(),
-                (Tile::NorthToWest, Direction::West) => self.north(),
-                (Tile::SouthToEast, Direction::South) => self.east(),
-                (Tile::SouthToEast, Direction::East) => self.south(),
-                (Tile::SouthToWest, Direction::South) => self.west(),
-                (Tile::SouthToWest, Direction::West) => self.south(),
+                (Tile::NorthToSouth, Direction::North) => self.step(Direction::South),
+                (Tile::NorthToSouth, Direction::South) => self.step(Direction::North),
+                (Tile::EastToWest, Direction::East) => self.step(Direction::West),
+                (Tile::EastToWest, Direction::West) => self.step(Direction::East),
+                (Tile::NorthToEast, Direction::North) => self.step(Direction::East),
+                (Tile::NorthToEast, Direction::East) => self.step(Direction::North),
+                (Tile::NorthToWest, Direction::North) => self.step(Direction::West),
+                (Tile::NorthToWest, Direction::West) => self.step(Direction::North),
+                (Tile::SouthToEast, Direction::South) => self.step(Direction::East),
+                (Tile::SouthToEast, Direction::East) => self.step(Direction::South),
+                (Tile::SouthToWest, Direction::South) => self.step(Direction::West),
+                (Tile::SouthToWest, Direction::West) => self.step(Direction::South),
                 (Tile::Start, _) => unreachable!(),
                 _ => None,
             };
 
-            if mv.is_none() {
-                return None;
-            }
+            mv?;
             idxs.push(self.curr);
         }
         Some(idxs)
     }
 
-    fn north(&mut self) -> Option<&'a Tile> {
-        if self.curr / self.map.x == 0 {
-            None
-        } else {
-            self.curr = self.curr - self.map.x;
-            self.curr_tile = self.map.tiles.get(self.curr).unwrap();
-            self.from_dir = Direction::South;
-            Some(self.curr_tile)
-        }
-    }
-
-    fn south(&mut self) -> Option<&'a Tile> {
-        if self.curr / self.map.x == self.map.y - 1 {
-            None
-        } else {
-            self.curr = self.curr + self.map.x;
-            self.curr_tile = self.map.tiles.get(self.curr).unwrap();
-            self.from_dir = Direction::North;
-            Some(self.curr_tile)
-        }
-    }
-
-    fn east(&mut self) -> Option<&'a Tile> {
-        if self.curr % self.map.x >= self.map.x - 1 {
-            None
-        } else {
-            self.curr = self.curr + 1;
-            self.curr_tile = self.map.tiles.get(self.curr).unwrap();
-            self.from_dir = Direction::West;
-            Some(self.curr_tile)
-        }
-    }
-
-    fn west(&mut self) -> Option<&'a Tile> {
-        if self.curr % self.map.x == 0 {
-            None
-        } else {
-            self.curr = self.curr - 1;
-            self.curr_tile = self.map.tiles.get(self.curr).unwrap();
-            self.from_dir = Direction::East;
-            Some(self.curr_tile)
-        }
+    fn step(&mut self, dir: Direction) -> Option<&'a Tile> {
+        self.curr = self.map.offset(self.curr, dir.offset(), Wrap::None)?;
+        self.curr_tile = &self.map[self.curr];
+        self.from_dir = dir.opposite();
+        Some(self.curr_tile)
     }
 }
 
@@ -158,7 +101,7 @@ enum Tile {
     Ground,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 enum Direction {
     North,
     South,
@@ -166,6 +109,26 @@ enum Direction {
     West,
 }
 
+impl Direction {
+    fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::South => (0, 1),
+            Direction::East => (1, 0),
+            Direction::West => (-1, 0),
+        }
+    }
+
+    fn opposite(self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::South => Direction::North,
+            Direction::East => Direction::West,
+            Direction::West => Direction::East,
+        }
+    }
+}
+
 impl TryFrom<char> for Tile {
     type Error = &'static str;
 
@@ -184,42 +147,37 @@ impl TryFrom<char> for Tile {
     }
 }
 
-impl Solution<DAY_10> for Solutions {
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day10>();
+}
+
+struct Day10;
+
+impl Solution for Day10 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 10;
     type Input<'i> = Map;
     type Output = usize;
 
-    fn parse(puzzle: &str) -> Map {
-        let lines = puzzle
-            .lines()
-            .map(|l| {
-                l.chars()
-                    .map(|c| Tile::try_from(c))
-                    .collect::<Result<Vec<_>, _>>()
-            })
-            .collect::<Result<Vec<_>, _>>()
-            .unwrap();
-        Map {
-            x: lines.get(0).unwrap().len(),
-            y: lines.len(),
-            tiles: lines.into_iter().flatten().collect(),
-        }
+    fn parse(puzzle: &str) -> Result<Self::Input<'_>> {
+        Grid::parse(puzzle).map_err(Error::parse)
     }
 
-    fn part_one(input: &Map) -> usize {
-        let (_, c) = input.find_loop();
-        c.len() / 2
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
+        let (_, c) = find_loop(input);
+        Ok(c.len() / 2)
     }
-    fn part_two(input: &Map) -> usize {
-        let (d, mut c) = input.find_loop();
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
+        let (d, mut c) = find_loop(input);
         c.sort();
         let mut walls = 0usize;
         let mut count = 0usize;
-        for (i, tile) in input.tiles.iter().enumerate() {
-            if i % input.x == 0 && walls % 2 == 1 {
+        for (pos, tile) in input.iter() {
+            if pos.0 == 0 && walls % 2 == 1 {
                 panic!("In the loop on a new line! You messed up!");
             }
             // Ray-Cast bottom half of cell
-            if let Ok(_) = c.binary_search(&i) {
+            if c.binary_search(&pos).is_ok() {
                 if tile == &Tile::NorthToSouth
                     || tile == &Tile::SouthToEast
                     || tile == &Tile::SouthToWest
@@ -231,23 +189,13 @@ impl Solution<DAY_10> for Solutions {
             }
             count += walls % 2
         }
-        count
-    }
-}
-
-impl Test<DAY_10> for Solutions {
-    fn expected(part: bool) -> usize {
-        match part {
-            PART_ONE => 8,
-            PART_TWO => 10,
-        }
+        Ok(count)
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::Solutions;
-    use lib_aoc::prelude::*;
+    use super::*;
 
-    derive_tests!(Solutions, DAY_10);
+    aoc_core::derive_tests!(Day10);
 }
@@ -1,5 +1,5 @@
-use crate::Solutions;
-use lib_aoc::prelude::*;
+use aoc_common::Grid;
+use aoc_core::{Error, Registry, Result, Solution};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Tile {
@@ -74,31 +74,17 @@ impl TryFrom<char> for Tile {
     }
 }
 
-fn expansion(tiles: &Vec<Vec<Tile>>) -> (Vec<usize>, Vec<usize>) {
-    let empty_rows: Vec<_> = tiles
-        .iter()
+fn expansion(tiles: &Grid<Tile>) -> (Vec<usize>, Vec<usize>) {
+    let empty_rows = tiles
+        .rows()
         .enumerate()
-        .filter_map(|(y, row)| {
-            if row.iter().all(|col| col == &Tile::Space) {
-                Some(y)
-            } else {
-                None
-            }
-        })
+        .filter_map(|(y, row)| row.iter().all(|t| t == &Tile::Space).then_some(y))
+        .collect();
+    let empty_cols = tiles
+        .columns()
+        .enumerate()
+        .filter_map(|(x, mut col)| col.all(|t| t == &Tile::Space).then_some(x))
         .collect();
-    let mut empty_cols = Vec::new();
-    for x in 0..tiles[0].len() {
-        let mut all_space = true;
-        for y in 0..tiles.len() {
-            if tiles[y][x] == Tile::Galaxy {
-                all_space = false;
-                break;
-            }
-        }
-        if all_space {
-            empty_cols.push(x);
-        }
-    }
     (empty_rows, empty_cols)
 }
 
@@ -111,87 +97,61 @@ fn distance(
     let (mut ax, mut ay) = (a.x, a.y);
     let (mut bx, mut by) = (b.x, b.y);
     if ax < bx {
-        let temp = ax;
-        ax = bx;
-        bx = temp;
+        std::mem::swap(&mut ax, &mut bx);
     }
     if ay < by {
-        let temp = ay;
-        ay = by;
-        by = temp;
+        std::mem::swap(&mut ay, &mut by);
     }
     let x_exp = exp_x.iter().filter(|x| (bx..=ax).contains(x)).count();
     let y_exp = exp_y.iter().filter(|y| (by..=ay).contains(y)).count();
     (ax - bx + (x_exp * (rate - 1))) + (ay - by + (y_exp * (rate - 1)))
 }
 
-fn into_galaxies(tiles: &Vec<Vec<Tile>>) -> Vec<Galaxy> {
+fn into_galaxies(tiles: &Grid<Tile>) -> Vec<Galaxy> {
     tiles
         .iter()
-        .enumerate()
-        .filter_map(|(y, row)| {
-            let r = row
-                .iter()
-                .enumerate()
-                .filter_map(|(x, tile)| match tile {
-                    Tile::Space => None,
-                    Tile::Galaxy => Some(Galaxy { x, y }),
-                })
-                .collect::<Vec<_>>();
-            if r.len() == 0 {
-                None
-            } else {
-                Some(r)
-            }
-        })
-        .flatten()
+        .filter(|(_, tile)| tile == &&Tile::Galaxy)
+        .map(|((x, y), _)| Galaxy { x, y })
         .collect()
 }
 
-impl Solution<DAY_11> for Solutions {
-    type Input<'i> = Vec<Vec<Tile>>;
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add::<Day11>();
+}
+
+struct Day11;
+
+impl Solution for Day11 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 11;
+    type Input<'i> = Grid<Tile>;
     type Output = usize;
 
-    fn parse(puzzle: &str) -> Vec<Vec<Tile>> {
-        puzzle
-            .lines()
-            .into_iter()
-            .map(|l| l.chars().map(|c| Tile::try_from(c)).collect())
-            .collect::<Result<Vec<_>, _>>()
-            .unwrap()
+    fn parse(puzzle: &str) -> Result<Self::Input<'_>> {
+        Grid::parse(puzzle).map_err(Error::parse)
     }
 
-    fn part_one(input: &Vec<Vec<Tile>>) -> usize {
+    fn part_one(input: &Self::Input<'_>) -> Result<Self::Output> {
         let expansion = expansion(input);
         let galaxies = into_galaxies(input);
-        GalaxyPairs::iter(&galaxies)
+        Ok(GalaxyPairs::iter(&galaxies)
             .map(|(a, b)| distance(a, b, &expansion, 2))
-            .sum()
+            .sum())
     }
 
-    fn part_two(input: &Vec<Vec<Tile>>) -> usize {
+    fn part_two(input: &Self::Input<'_>) -> Result<Self::Output> {
         let expansion = expansion(input);
         let galaxies = into_galaxies(input);
-        let rate = if input.len() < 20 { 100 } else { 1_000_000 };
-        GalaxyPairs::iter(&galaxies)
+        let rate = if input.height() < 20 { 100 } else { 1_000_000 };
+        Ok(GalaxyPairs::iter(&galaxies)
             .map(|(a, b)| distance(a, b, &expansion, rate))
-            .sum()
-    }
-}
-
-impl Test<DAY_11> for Solutions {
-    fn expected(part: bool) -> usize {
-        match part {
-            PART_ONE => 374,
-            PART_TWO => 8410,
-        }
+            .sum())
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::Solutions;
-    use lib_aoc::prelude::*;
+    use super::*;
 
-    derive_tests!(Solutions, DAY_11);
+    aoc_core::derive_tests!(Day11);
 }
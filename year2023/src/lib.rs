@@ -1,5 +1,4 @@
-use lib_aoc::prelude::*;
-use std::{fs, path::Path};
+use aoc_core::Registry;
 
 mod day01;
 mod day02;
@@ -13,28 +12,16 @@ mod day09;
 mod day10;
 mod day11;
 
-pub struct Solutions {}
-
-impl Solver for Solutions {
-    fn load(day: u8) -> String {
-        fs::read_to_string(format!("inputs/day{day:02}.txt"))
-            .expect("Puzzle input could not be read")
-    }
-
-    fn load_test(day: u8, part: bool) -> String {
-        let test1 = format!("inputs/tests/day{day:02}.txt");
-        let test2 = format!("inputs/tests/day{day:02}b.txt");
-        let path = match part {
-            PART_ONE => Path::new(&test1),
-            PART_TWO => {
-                let test = Path::new(&test2);
-                if test.exists() {
-                    test
-                } else {
-                    Path::new(&test1)
-                }
-            }
-        };
-        fs::read_to_string(path).expect("Puzzle input could not be read")
-    }
+pub fn register(registry: &mut Registry) {
+    day01::register(registry);
+    day02::register(registry);
+    day03::register(registry);
+    day04::register(registry);
+    day05::register(registry);
+    day06::register(registry);
+    day07::register(registry);
+    day08::register(registry);
+    day09::register(registry);
+    day10::register(registry);
+    day11::register(registry);
 }
@@ -1,6 +0,0 @@
-use lib_aoc::solve;
-use year2023::*;
-
-fn main() {
-    solve!(Solutions, 11);
-}